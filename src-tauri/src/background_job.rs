@@ -0,0 +1,217 @@
+// ── Background Jobs ───────────────────────────────────────────
+//
+// Shared runner for long, cancellable project commands (dependency
+// installs, production builds). A job runs a shell command in the
+// project, hands every output line to the caller, keeps the last lines
+// for the finished event, and emits that event once the process exits.
+// Each kind of job keeps its own registry, which allows one running job
+// per project; `cancel` kills the job's process tree.
+
+use serde::Serialize;
+use std::collections::{HashMap, VecDeque};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+use tauri::Emitter;
+
+/// Lines of output kept for the finished event
+const OUTPUT_TAIL_LINES: usize = 40;
+
+/// Canonical project path, used to key per-project state
+pub(crate) fn project_key(project: &str) -> Result<String, String> {
+    let path = PathBuf::from(project);
+    if !path.is_dir() {
+        return Err(format!("Directory not found: {}", project));
+    }
+    Ok(path.canonicalize().unwrap_or(path).to_string_lossy().to_string())
+}
+
+/// What to run. `project` is the key from `project_key`.
+pub(crate) struct JobCommand<'a> {
+    pub job_id: &'a str,
+    pub project: &'a str,
+    pub root: &'a Path,
+    pub command: &'a str,
+    pub env: &'a [(&'a str, &'a str)],
+    /// Event emitted with the payload built from `JobExit`
+    pub finished_event: &'static str,
+}
+
+/// How a job's process ended
+pub(crate) struct JobExit {
+    pub success: bool,
+    pub cancelled: bool,
+    pub exit_code: Option<i32>,
+    pub duration_ms: u64,
+    pub output_tail: Vec<String>,
+}
+
+/// A running job, shared with its worker thread
+struct JobHandle {
+    project: String,
+    pid: u32,
+    cancelled: AtomicBool,
+}
+
+/// Running jobs of one kind, by job ID
+pub(crate) struct JobRegistry {
+    /// "install", "build" — used in error messages
+    kind: &'static str,
+    jobs: Mutex<HashMap<String, Arc<JobHandle>>>,
+}
+
+impl JobRegistry {
+    pub fn new(kind: &'static str) -> Self {
+        Self {
+            kind,
+            jobs: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// ID of the job running for the project, if any
+    pub fn running_job_for(&self, project: &str) -> Option<String> {
+        let jobs = self.jobs.lock().unwrap_or_else(|e| e.into_inner());
+        find_job(&jobs, project)
+    }
+
+    /// Start `spec.command` as job `spec.job_id`. `on_line` gets each
+    /// non-empty output line (ANSI codes stripped) from stdout and stderr;
+    /// `on_exit` runs on the worker thread once the process has exited and
+    /// builds the finished event's payload. The job stays registered until
+    /// `on_exit` returns.
+    pub fn start<E, L, X>(
+        &'static self,
+        app_handle: &tauri::AppHandle,
+        spec: JobCommand,
+        on_line: L,
+        on_exit: X,
+    ) -> Result<(), String>
+    where
+        E: Serialize + Clone,
+        L: Fn(&str) + Send + Sync + 'static,
+        X: FnOnce(JobExit) -> E + Send + 'static,
+    {
+        use std::io::{BufRead, BufReader};
+        use std::process::Stdio;
+
+        // Check and register under one lock, so two starts can't both pass the check
+        let (mut child, handle) = {
+            let mut jobs = self.jobs.lock().map_err(|e| e.to_string())?;
+            if let Some(running) = find_job(&jobs, spec.project) {
+                return Err(format!(
+                    "Another {} is already running for this project ({})",
+                    self.kind, running
+                ));
+            }
+
+            let mut cmd = crate::build_hidden_shell_command(spec.command, &spec.root.to_path_buf());
+            cmd.stdin(Stdio::null()).stdout(Stdio::piped()).stderr(Stdio::piped());
+            // Keep tools from switching to interactive/TTY output
+            cmd.env("CI", "1");
+            for (name, value) in spec.env {
+                cmd.env(name, value);
+            }
+            let child = cmd
+                .spawn()
+                .map_err(|e| format!("Failed to start {}: {}", spec.command, e))?;
+            crate::process_registry::track(child.id(), spec.command);
+
+            let handle = Arc::new(JobHandle {
+                project: spec.project.to_string(),
+                pid: child.id(),
+                cancelled: AtomicBool::new(false),
+            });
+            jobs.insert(spec.job_id.to_string(), handle.clone());
+            (child, handle)
+        };
+
+        let tail = Arc::new(Mutex::new(VecDeque::new()));
+        let on_line = Arc::new(on_line);
+        let streams: Vec<Box<dyn std::io::Read + Send>> = [
+            child.stdout.take().map(|s| Box::new(s) as Box<dyn std::io::Read + Send>),
+            child.stderr.take().map(|s| Box::new(s) as Box<dyn std::io::Read + Send>),
+        ]
+        .into_iter()
+        .flatten()
+        .collect();
+
+        let readers: Vec<_> = streams
+            .into_iter()
+            .map(|stream| {
+                let tail = tail.clone();
+                let on_line = on_line.clone();
+                std::thread::spawn(move || {
+                    for line in BufReader::new(stream).lines().map_while(Result::ok) {
+                        let clean = crate::strip_ansi_codes(&line);
+                        if clean.trim().is_empty() {
+                            continue;
+                        }
+                        {
+                            let mut tail = tail.lock().unwrap_or_else(|e| e.into_inner());
+                            if tail.len() >= OUTPUT_TAIL_LINES {
+                                tail.pop_front();
+                            }
+                            tail.push_back(clean.clone());
+                        }
+                        on_line(&clean);
+                    }
+                })
+            })
+            .collect();
+
+        // Worker — waits for the process, then reports
+        {
+            let app_handle = app_handle.clone();
+            let job_id = spec.job_id.to_string();
+            let finished_event = spec.finished_event;
+            let started = Instant::now();
+            std::thread::spawn(move || {
+                for reader in readers {
+                    let _ = reader.join();
+                }
+                let exit_code = child.wait().ok().and_then(|s| s.code());
+                crate::process_registry::exited(handle.pid);
+
+                let cancelled = handle.cancelled.load(Ordering::Relaxed);
+                let output_tail = tail.lock().map(|t| t.iter().cloned().collect()).unwrap_or_default();
+                let payload = on_exit(JobExit {
+                    success: !cancelled && exit_code == Some(0),
+                    cancelled,
+                    exit_code,
+                    duration_ms: started.elapsed().as_millis() as u64,
+                    output_tail,
+                });
+
+                self.jobs
+                    .lock()
+                    .unwrap_or_else(|e| e.into_inner())
+                    .remove(&job_id);
+                let _ = app_handle.emit(finished_event, payload);
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Cancel a running job (kills its process group). Blocks until it's gone.
+    pub fn cancel(&self, job_id: &str) -> Result<(), String> {
+        let handle = self
+            .jobs
+            .lock()
+            .map_err(|e| e.to_string())?
+            .get(job_id)
+            .cloned()
+            .ok_or_else(|| format!("No {} job with ID {}", self.kind, job_id))?;
+
+        handle.cancelled.store(true, Ordering::Relaxed);
+        crate::kill_process_tree(handle.pid);
+        Ok(())
+    }
+}
+
+fn find_job(jobs: &HashMap<String, Arc<JobHandle>>, project: &str) -> Option<String> {
+    jobs.iter()
+        .find(|(_, job)| job.project == project)
+        .map(|(id, _)| id.clone())
+}
@@ -0,0 +1,337 @@
+// ── Production Build ──────────────────────────────────────────
+//
+// The preview normally shows the dev server, which never minifies,
+// tree-shakes or applies the base path, so those bugs only show up once
+// deployed. This runs the project's own build script as a cancellable
+// background job (`build-output` per line, `build-finished` at the end),
+// finds the output directory, lists what it produced, and serves it with
+// a production-mode preview server under `<project>#build`.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::SystemTime;
+use tauri::Emitter;
+
+use crate::background_job::{project_key, JobCommand, JobExit, JobRegistry};
+use crate::installer::PackageManager;
+/// Where bundlers put their output, in the order we look for it
+const OUTPUT_DIRS: &[&str] = &["dist", "build", "out", ".next"];
+/// Files listed from the output directory
+const MAX_ARTIFACTS: usize = 5_000;
+/// Same default as `vite preview`
+const PREVIEW_PORT: u16 = 4173;
+
+// ── Data Model ────────────────────────────────────────────────
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct BuildOptions {
+    /// Run this instead of the package.json build script
+    pub command: Option<String>,
+    /// Output directory relative to the project, when it isn't one of the usual ones
+    pub output_dir: Option<String>,
+    /// Don't start the preview server after a successful build
+    #[serde(default)]
+    pub skip_preview: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BuildJob {
+    pub job_id: String,
+    pub project: String,
+    pub command: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BuildOutputLine {
+    pub job_id: String,
+    pub project: String,
+    pub line: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BuildArtifact {
+    /// Relative to the output directory, with forward slashes
+    pub path: String,
+    pub size: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BuildFinished {
+    pub job_id: String,
+    pub project: String,
+    pub success: bool,
+    pub cancelled: bool,
+    pub exit_code: Option<i32>,
+    pub duration_ms: u64,
+    pub output_tail: Vec<String>,
+    pub output_dir: Option<String>,
+    /// Largest first
+    pub artifacts: Vec<BuildArtifact>,
+    pub total_size: u64,
+    /// Preview server key and port for the output, when it's being served
+    pub preview_key: Option<String>,
+    pub preview_port: Option<u16>,
+    /// Why the output isn't previewed, or why some files weren't listed
+    pub notes: Vec<String>,
+}
+
+static BUILD_JOBS: once_cell::sync::Lazy<JobRegistry> =
+    once_cell::sync::Lazy::new(|| JobRegistry::new("build"));
+
+/// Result of each project's most recent build
+static LAST_BUILDS: once_cell::sync::Lazy<Mutex<HashMap<String, BuildFinished>>> =
+    once_cell::sync::Lazy::new(|| Mutex::new(HashMap::new()));
+
+// ── Build Command ─────────────────────────────────────────────
+
+/// `build` script from package.json, run with the project's package manager
+fn detect_command(root: &Path) -> Result<String, String> {
+    let content = fs::read_to_string(root.join("package.json"))
+        .map_err(|_| "No package.json found — pass a build command instead".to_string())?;
+    let manifest: serde_json::Value =
+        serde_json::from_str(&content).map_err(|e| format!("Invalid package.json: {}", e))?;
+    if manifest.pointer("/scripts/build").and_then(|s| s.as_str()).is_none() {
+        return Err("package.json has no \"build\" script".to_string());
+    }
+
+    Ok(match PackageManager::detect(root) {
+        Some(PackageManager::Pnpm) => "pnpm run build",
+        Some(PackageManager::Yarn) => "yarn build",
+        Some(PackageManager::Bun) => "bun run build",
+        _ => "npm run build",
+    }
+    .to_string())
+}
+
+// ── Output ────────────────────────────────────────────────────
+
+/// The output directory: the override if given, otherwise the first usual
+/// one the build touched, falling back to the first that exists (some
+/// bundlers write into an existing directory without changing its mtime)
+fn find_output_dir(root: &Path, override_dir: Option<&str>, started: SystemTime) -> Option<PathBuf> {
+    if let Some(dir) = override_dir {
+        let dir = root.join(dir);
+        return dir.is_dir().then_some(dir);
+    }
+
+    let existing: Vec<PathBuf> = OUTPUT_DIRS
+        .iter()
+        .map(|name| root.join(name))
+        .filter(|dir| dir.is_dir())
+        .collect();
+    let fresh = existing.iter().find(|dir| {
+        fs::metadata(dir)
+            .and_then(|m| m.modified())
+            .is_ok_and(|modified| modified >= started)
+    });
+    fresh.or(existing.first()).cloned()
+}
+
+/// Every file under `dir`, largest first. Returns whether the list was cut short.
+pub(crate) fn list_artifacts(dir: &Path) -> (Vec<BuildArtifact>, bool) {
+    let mut artifacts = Vec::new();
+    let mut pending = vec![dir.to_path_buf()];
+    let mut truncated = false;
+
+    while let Some(current) = pending.pop() {
+        let Ok(entries) = fs::read_dir(&current) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let Ok(file_type) = entry.file_type() else {
+                continue;
+            };
+            if file_type.is_dir() {
+                // Next.js keeps its build cache inside .next
+                if !(dir.ends_with(".next") && path == dir.join("cache")) {
+                    pending.push(path);
+                }
+                continue;
+            }
+            if artifacts.len() >= MAX_ARTIFACTS {
+                truncated = true;
+                break;
+            }
+            let size = entry.metadata().map(|m| m.len()).unwrap_or(0);
+            let relative = path.strip_prefix(dir).unwrap_or(&path);
+            artifacts.push(BuildArtifact {
+                path: relative.to_string_lossy().replace('\\', "/"),
+                size,
+            });
+        }
+    }
+
+    artifacts.sort_by(|a, b| b.size.cmp(&a.size).then_with(|| a.path.cmp(&b.path)));
+    (artifacts, truncated)
+}
+
+/// Serve the output in production mode. Returns the preview key and port.
+fn start_preview(app_handle: &tauri::AppHandle, project: &str, dir: &Path) -> Result<(String, u16), String> {
+    let key = format!("{}#build", project);
+    let options = crate::server::PreviewOptions {
+        production: true,
+        ..Default::default()
+    };
+    let port = tauri::async_runtime::block_on(crate::server::start(
+        app_handle,
+        &key,
+        &dir.to_string_lossy(),
+        PREVIEW_PORT,
+        &options,
+    ))?;
+    Ok((key, port))
+}
+
+// ── Jobs ──────────────────────────────────────────────────────
+
+/// Start a production build. Returns immediately; output lines and the
+/// result arrive as events. Only one build runs per project.
+pub fn start(app_handle: &tauri::AppHandle, project: &str, options: BuildOptions) -> Result<BuildJob, String> {
+    let key = project_key(project)?;
+    let root = PathBuf::from(&key);
+    let command = match options.command.as_deref().map(str::trim) {
+        Some(command) if !command.is_empty() => command.to_string(),
+        _ => detect_command(&root)?,
+    };
+
+    let job = BuildJob {
+        job_id: uuid::Uuid::new_v4().to_string(),
+        project: key.clone(),
+        command,
+    };
+
+    let on_line = {
+        let app_handle = app_handle.clone();
+        let job_id = job.job_id.clone();
+        let key = key.clone();
+        move |line: &str| {
+            let _ = app_handle.emit(
+                "build-output",
+                BuildOutputLine {
+                    job_id: job_id.clone(),
+                    project: key.clone(),
+                    line: line.to_string(),
+                },
+            );
+        }
+    };
+
+    // Directory mtimes have coarse resolution on some filesystems
+    let started_at = SystemTime::now() - std::time::Duration::from_secs(2);
+
+    // Lists and serves the output once the build has finished
+    let on_exit = {
+        let app_handle = app_handle.clone();
+        let root = root.clone();
+        let job_id = job.job_id.clone();
+        let key = key.clone();
+        move |exit: JobExit| {
+            let mut notes = Vec::new();
+            let mut output_dir = None;
+            let mut artifacts = Vec::new();
+            let mut preview = None;
+
+            if exit.success {
+                match find_output_dir(&root, options.output_dir.as_deref(), started_at) {
+                    Some(dir) => {
+                        let (listed, truncated) = list_artifacts(&dir);
+                        if truncated {
+                            notes.push(format!("Only the first {} files are listed", MAX_ARTIFACTS));
+                        }
+                        artifacts = listed;
+
+                        if !options.skip_preview {
+                            if dir.file_name().is_some_and(|name| name == ".next") {
+                                notes.push(
+                                    "Next.js server output can't be served statically — run `next start`, \
+                                     or set `output: 'export'` to build a static site into out/"
+                                        .to_string(),
+                                );
+                            } else {
+                                match start_preview(&app_handle, &key, &dir) {
+                                    Ok(started) => preview = Some(started),
+                                    Err(e) => notes.push(format!("Preview failed to start: {}", e)),
+                                }
+                            }
+                        }
+                        output_dir = Some(dir.to_string_lossy().to_string());
+                    }
+                    None => notes.push(format!(
+                        "No output directory found (looked for {})",
+                        options.output_dir.as_deref().map_or_else(|| OUTPUT_DIRS.join(", "), str::to_string)
+                    )),
+                }
+            }
+
+            let finished = BuildFinished {
+                job_id,
+                project: key.clone(),
+                success: exit.success,
+                cancelled: exit.cancelled,
+                exit_code: exit.exit_code,
+                duration_ms: exit.duration_ms,
+                output_tail: exit.output_tail,
+                output_dir,
+                total_size: artifacts.iter().map(|a| a.size).sum(),
+                artifacts,
+                preview_key: preview.as_ref().map(|(key, _)| key.clone()),
+                preview_port: preview.map(|(_, port)| port),
+                notes,
+            };
+            LAST_BUILDS
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .insert(key, finished.clone());
+            finished
+        }
+    };
+
+    BUILD_JOBS.start(
+        app_handle,
+        JobCommand {
+            job_id: &job.job_id,
+            project: &key,
+            root: &root,
+            command: &job.command,
+            env: &[("NODE_ENV", "production")],
+            finished_event: "build-finished",
+        },
+        on_line,
+        on_exit,
+    )?;
+
+    Ok(job)
+}
+
+/// Cancel a running build (kills its process group). Blocks until it's gone.
+pub fn cancel(job_id: &str) -> Result<(), String> {
+    BUILD_JOBS.cancel(job_id)
+}
+
+/// Output directory of the project's last build, or the first usual one
+/// that exists
+pub(crate) fn output_dir(project: &str) -> Option<PathBuf> {
+    let key = project_key(project).ok()?;
+    let last = LAST_BUILDS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .get(&key)
+        .and_then(|build| build.output_dir.clone());
+    last.map(PathBuf::from)
+        .filter(|dir| dir.is_dir())
+        .or_else(|| find_output_dir(Path::new(&key), None, SystemTime::UNIX_EPOCH))
+}
+
+/// Result of the project's last build since the app started
+pub fn last_build(project: &str) -> Result<Option<BuildFinished>, String> {
+    let key = project_key(project)?;
+    Ok(LAST_BUILDS
+        .lock()
+        .map_err(|e| e.to_string())?
+        .get(&key)
+        .cloned())
+}
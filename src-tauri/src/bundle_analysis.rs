@@ -0,0 +1,1119 @@
+// ── Bundle Analysis ───────────────────────────────────────────
+//
+// What a production build costs to download: raw, gzip and brotli sizes
+// for every file in the output directory, chunks grouped under the entry
+// (page or manifest entry) that loads them, and — where the bundler
+// emitted source maps — how many bytes each source module and npm
+// package contributes. Each analysis is compared with the previous
+// build's, and files that grew past the budget are flagged.
+// History lives in ~/.mydevify/data/bundle-history.json, keyed by project path.
+
+use crate::background_job::project_key;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+use std::fs;
+use std::io::Write;
+use std::path::{Component, Path, PathBuf};
+use std::sync::Mutex;
+
+/// Modules listed in the report, largest first
+const MAX_MODULES: usize = 200;
+/// Larger files are listed with their raw size only
+const MAX_COMPRESSED_FILE: u64 = 20 * 1024 * 1024;
+/// Formats that are already compressed — hosts serve them as-is
+const PRECOMPRESSED: &[&str] = &[
+    "png", "jpg", "jpeg", "gif", "webp", "avif", "ico", "woff", "woff2", "mp3", "mp4", "webm", "ogg", "zip", "gz",
+    "br", "pdf",
+];
+
+/// Serializes access to bundle-history.json
+static HISTORY_LOCK: Mutex<()> = Mutex::new(());
+
+// ── Data Model ────────────────────────────────────────────────
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AssetKind {
+    Script,
+    Style,
+    Html,
+    Image,
+    Font,
+    Other,
+}
+
+/// Summed sizes. Files served uncompressed (images, fonts, very large
+/// files) count with their raw size in `gzip` and `brotli`.
+#[derive(Debug, Clone, Copy, Default, Serialize)]
+pub struct SizeTotals {
+    pub raw: u64,
+    pub gzip: u64,
+    pub brotli: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BundleFile {
+    /// Relative to the output directory, with forward slashes
+    pub path: String,
+    pub kind: AssetKind,
+    pub raw: u64,
+    /// None for formats that are served uncompressed
+    pub gzip: Option<u64>,
+    pub brotli: Option<u64>,
+    /// Source map the module breakdown came from ("inline" for data URLs)
+    pub source_map: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ChunkGroup {
+    /// HTML page, or source entry from the Vite manifest
+    pub entry: String,
+    /// Files loaded up front: scripts, styles and their static imports
+    pub initial: Vec<String>,
+    /// Files only reachable through dynamic import()
+    pub lazy: Vec<String>,
+    pub initial_size: SizeTotals,
+    pub lazy_size: SizeTotals,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ModuleSize {
+    pub source: String,
+    pub package: Option<String>,
+    /// Bytes of minified output mapped to this module, across all files
+    pub size: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PackageSize {
+    pub name: String,
+    pub size: u64,
+    pub modules: usize,
+}
+
+/// When a size change counts as a regression. Sizes compared are gzip
+/// sizes, which is what users download.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BundleBudget {
+    /// A file (or the total) regresses when it grows by more than this…
+    pub max_growth_percent: f64,
+    /// …and by more than this many bytes, so tiny files don't trip it
+    pub min_growth_bytes: u64,
+    /// Flag the build when its total gzip size is above this
+    pub max_total_bytes: Option<u64>,
+}
+
+impl Default for BundleBudget {
+    fn default() -> Self {
+        Self {
+            max_growth_percent: 10.0,
+            min_growth_bytes: 1024,
+            max_total_bytes: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct BundleOptions {
+    /// Output directory relative to the project (default: the last build's)
+    pub output_dir: Option<String>,
+    #[serde(default)]
+    pub budget: BundleBudget,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SizeChange {
+    /// File path with the content hash taken out, so builds line up
+    pub name: String,
+    pub previous: Option<u64>,
+    pub current: Option<u64>,
+    pub delta: i64,
+    pub percent: Option<f64>,
+    pub regression: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BundleComparison {
+    pub previous_analyzed_at: String,
+    pub previous_total: u64,
+    pub total_delta: i64,
+    pub total_regression: bool,
+    /// Changed, added and removed files, biggest growth first
+    pub changes: Vec<SizeChange>,
+    pub regressions: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BundleReport {
+    pub output_dir: String,
+    pub analyzed_at: String, // ISO 8601 string
+    /// Largest first
+    pub files: Vec<BundleFile>,
+    pub totals: SizeTotals,
+    pub by_kind: HashMap<AssetKind, SizeTotals>,
+    pub groups: Vec<ChunkGroup>,
+    pub modules: Vec<ModuleSize>,
+    pub packages: Vec<PackageSize>,
+    /// Script/style bytes no source map accounts for
+    pub unmapped: u64,
+    /// None on the first analysis of a project
+    pub comparison: Option<BundleComparison>,
+    /// Total gzip size is above `budget.max_total_bytes`
+    pub over_budget: bool,
+    pub notes: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Snapshot {
+    analyzed_at: String,
+    /// Hash of the output's paths and sizes — the same build analyzed
+    /// twice keeps comparing against the build before it
+    fingerprint: String,
+    /// Stable name → gzip size
+    files: BTreeMap<String, u64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct History {
+    latest: Snapshot,
+    previous: Option<Snapshot>,
+}
+
+/// One file's sizes and the bytes its source map attributes per module
+struct Measured {
+    file: BundleFile,
+    modules: HashMap<String, u64>,
+}
+
+// ── Analysis ──────────────────────────────────────────────────
+
+/// Analyze the project's build output and record it as the latest build
+pub fn analyze(project: &str, options: &BundleOptions) -> Result<BundleReport, String> {
+    let key = project_key(project)?;
+    let root = PathBuf::from(&key);
+    let mut dir = match &options.output_dir {
+        Some(dir) => root.join(dir),
+        None => crate::build_job::output_dir(&key).ok_or("No build output found — build the project first")?,
+    };
+    if !dir.is_dir() {
+        return Err(format!("Output directory not found: {}", dir.display()));
+    }
+    // Only .next/static reaches the browser; the rest is server code
+    if dir.ends_with(".next") && dir.join("static").is_dir() {
+        dir = dir.join("static");
+    }
+
+    let mut notes = Vec::new();
+    let (artifacts, truncated) = crate::build_job::list_artifacts(&dir);
+    if truncated {
+        notes.push("Output has too many files — only the first ones were analyzed".to_string());
+    }
+    let (maps, paths): (Vec<String>, Vec<String>) = artifacts
+        .into_iter()
+        .map(|a| a.path)
+        .filter(|path| !path.starts_with(".vite/"))
+        .partition(|path| path.ends_with(".map"));
+    if !maps.is_empty() {
+        notes.push(format!("{} source map files aren't counted — browsers only fetch them with devtools open", maps.len()));
+    }
+
+    let measured = measure_all(&dir, &paths);
+
+    let mut totals = SizeTotals::default();
+    let mut by_kind: HashMap<AssetKind, SizeTotals> = HashMap::new();
+    let mut modules: HashMap<String, u64> = HashMap::new();
+    let mut unmapped = 0;
+    let mut files = Vec::with_capacity(measured.len());
+    for Measured { file, modules: contributions } in measured {
+        totals.add(&file);
+        by_kind.entry(file.kind).or_default().add(&file);
+        if matches!(file.kind, AssetKind::Script | AssetKind::Style) {
+            let mapped: u64 = contributions.values().sum();
+            unmapped += file.raw.saturating_sub(mapped);
+        }
+        for (source, size) in contributions {
+            *modules.entry(source).or_default() += size;
+        }
+        files.push(file);
+    }
+    files.sort_by(|a, b| b.raw.cmp(&a.raw).then_with(|| a.path.cmp(&b.path)));
+    if files.iter().any(|f| f.kind == AssetKind::Script) && modules.is_empty() {
+        notes.push("No source maps found — enable them in the bundler to see which modules take up space".to_string());
+    }
+
+    let (modules, packages) = summarize_modules(modules);
+    let groups = chunk_groups(&dir, &files);
+
+    let analyzed_at = chrono::Utc::now().to_rfc3339();
+    let snapshot = snapshot_of(&files, &analyzed_at);
+    let comparison = record_snapshot(&key, snapshot.clone())?
+        .map(|previous| compare(&previous, &snapshot, &options.budget));
+    let over_budget = options.budget.max_total_bytes.is_some_and(|max| totals.gzip > max);
+
+    Ok(BundleReport {
+        output_dir: dir.to_string_lossy().to_string(),
+        analyzed_at,
+        files,
+        totals,
+        by_kind,
+        groups,
+        modules,
+        packages,
+        unmapped,
+        comparison,
+        over_budget,
+        notes,
+    })
+}
+
+impl SizeTotals {
+    fn add(&mut self, file: &BundleFile) {
+        self.raw += file.raw;
+        self.gzip += file.gzip.unwrap_or(file.raw);
+        self.brotli += file.brotli.unwrap_or(file.raw);
+    }
+}
+
+fn kind_of(path: &str) -> AssetKind {
+    match extension(path).as_str() {
+        "js" | "mjs" | "cjs" => AssetKind::Script,
+        "css" => AssetKind::Style,
+        "html" | "htm" => AssetKind::Html,
+        "png" | "jpg" | "jpeg" | "gif" | "webp" | "avif" | "svg" | "ico" => AssetKind::Image,
+        "woff" | "woff2" | "ttf" | "otf" | "eot" => AssetKind::Font,
+        _ => AssetKind::Other,
+    }
+}
+
+fn extension(path: &str) -> String {
+    Path::new(path)
+        .extension()
+        .map(|e| e.to_string_lossy().to_lowercase())
+        .unwrap_or_default()
+}
+
+/// Measure every file, spread over the available cores — brotli at its
+/// highest quality is slow on large bundles
+fn measure_all(dir: &Path, paths: &[String]) -> Vec<Measured> {
+    let threads = std::thread::available_parallelism().map(|n| n.get()).unwrap_or(4);
+    let per_thread = paths.len().div_ceil(threads).max(1);
+    std::thread::scope(|scope| {
+        let workers: Vec<_> = paths
+            .chunks(per_thread)
+            .map(|chunk| scope.spawn(move || chunk.iter().filter_map(|path| measure(dir, path)).collect::<Vec<_>>()))
+            .collect();
+        workers.into_iter().flat_map(|w| w.join().unwrap_or_default()).collect()
+    })
+}
+
+fn measure(dir: &Path, path: &str) -> Option<Measured> {
+    let bytes = fs::read(dir.join(path)).ok()?;
+    let kind = kind_of(path);
+    let raw = bytes.len() as u64;
+
+    let compressible = !PRECOMPRESSED.contains(&extension(path).as_str()) && raw <= MAX_COMPRESSED_FILE;
+    let (gzip, brotli) = if compressible {
+        (gzip_size(&bytes), brotli_size(&bytes))
+    } else {
+        (None, None)
+    };
+
+    let (source_map, modules) = match kind {
+        AssetKind::Script | AssetKind::Style => match load_source_map(dir, path, &bytes) {
+            Some((label, map)) => (Some(label), attribute(&map, &String::from_utf8_lossy(&bytes))),
+            None => (None, HashMap::new()),
+        },
+        _ => (None, HashMap::new()),
+    };
+
+    Some(Measured {
+        file: BundleFile {
+            path: path.to_string(),
+            kind,
+            raw,
+            gzip,
+            brotli,
+            source_map,
+        },
+        modules,
+    })
+}
+
+fn gzip_size(bytes: &[u8]) -> Option<u64> {
+    let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::best());
+    encoder.write_all(bytes).ok()?;
+    encoder.finish().ok().map(|out| out.len() as u64)
+}
+
+fn brotli_size(bytes: &[u8]) -> Option<u64> {
+    let mut out = Vec::new();
+    {
+        // Quality 11 with a 4MB window, as used for precompressed assets
+        let mut writer = brotli::CompressorWriter::new(&mut out, 4096, 11, 22);
+        writer.write_all(bytes).ok()?;
+    }
+    Some(out.len() as u64)
+}
+
+fn summarize_modules(modules: HashMap<String, u64>) -> (Vec<ModuleSize>, Vec<PackageSize>) {
+    let mut packages: HashMap<String, PackageSize> = HashMap::new();
+    let mut list: Vec<ModuleSize> = modules
+        .into_iter()
+        .map(|(source, size)| {
+            let package = package_name(&source);
+            if let Some(name) = &package {
+                let entry = packages.entry(name.clone()).or_insert_with(|| PackageSize {
+                    name: name.clone(),
+                    size: 0,
+                    modules: 0,
+                });
+                entry.size += size;
+                entry.modules += 1;
+            }
+            ModuleSize { source, package, size }
+        })
+        .collect();
+    list.sort_by(|a, b| b.size.cmp(&a.size).then_with(|| a.source.cmp(&b.source)));
+    list.truncate(MAX_MODULES);
+
+    let mut packages: Vec<PackageSize> = packages.into_values().collect();
+    packages.sort_by(|a, b| b.size.cmp(&a.size).then_with(|| a.name.cmp(&b.name)));
+    (list, packages)
+}
+
+/// npm package a source belongs to — the segment after the last
+/// node_modules/, so pnpm's nested layout resolves to the real package
+fn package_name(source: &str) -> Option<String> {
+    let (_, rest) = source.rsplit_once("node_modules/")?;
+    let mut segments = rest.split('/');
+    let first = segments.next().filter(|s| !s.is_empty())?;
+    if first.starts_with('@') {
+        Some(format!("{}/{}", first, segments.next()?))
+    } else {
+        Some(first.to_string())
+    }
+}
+
+// ── Source Maps ───────────────────────────────────────────────
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct SourceMap {
+    sources: Vec<Option<String>>,
+    #[serde(rename = "sourceRoot")]
+    source_root: Option<String>,
+    mappings: String,
+    /// Index maps are made of other maps placed at offsets
+    sections: Vec<SourceMapSection>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SourceMapSection {
+    offset: SectionOffset,
+    map: SourceMap,
+}
+
+#[derive(Debug, Deserialize)]
+struct SectionOffset {
+    line: usize,
+    column: usize,
+}
+
+/// The file's source map: the sourceMappingURL comment (file or data
+/// URL), else `<file>.map` next to it
+fn load_source_map(dir: &Path, path: &str, bytes: &[u8]) -> Option<(String, SourceMap)> {
+    static MAPPING_URL: Lazy<Regex> =
+        Lazy::new(|| Regex::new(r"[#@]\s*sourceMappingURL=([^\s*'\x22]+)").unwrap());
+
+    // The comment sits at the very end
+    let tail = String::from_utf8_lossy(&bytes[bytes.len().saturating_sub(64 * 1024)..]);
+    let url = MAPPING_URL.captures_iter(&tail).last().map(|c| c[1].to_string());
+
+    let (label, content) = match url {
+        Some(url) if url.starts_with("data:") => {
+            let (meta, data) = url.split_once(',')?;
+            let content = if meta.ends_with(";base64") {
+                decode_base64(data)?
+            } else {
+                percent_decode(data).into_bytes()
+            };
+            ("inline".to_string(), content)
+        }
+        Some(url) if !url.contains("://") => {
+            let url = url.split(['?', '#']).next().unwrap_or(&url);
+            let map_path = normalize(&Path::new(path).parent().unwrap_or(Path::new("")).join(url))?;
+            (map_path.clone(), fs::read(dir.join(&map_path)).ok()?)
+        }
+        _ => {
+            let map_path = format!("{}.map", path);
+            (map_path.clone(), fs::read(dir.join(&map_path)).ok()?)
+        }
+    };
+    let map: SourceMap = serde_json::from_slice(&content).ok()?;
+    Some((label, map))
+}
+
+/// Bytes of `generated` mapped to each source. Columns in a source map
+/// count UTF-16 code units, so non-ASCII lines are walked char by char.
+fn attribute(map: &SourceMap, generated: &str) -> HashMap<String, u64> {
+    let lines: Vec<&str> = generated.split('\n').collect();
+    let mut sizes = HashMap::new();
+    attribute_at(map, &lines, 0, 0, &mut sizes);
+    sizes
+}
+
+fn attribute_at(map: &SourceMap, lines: &[&str], line_offset: usize, column_offset: usize, sizes: &mut HashMap<String, u64>) {
+    for section in &map.sections {
+        let column = if section.offset.line == 0 { column_offset + section.offset.column } else { section.offset.column };
+        attribute_at(&section.map, lines, line_offset + section.offset.line, column, sizes);
+    }
+    if map.mappings.is_empty() {
+        return;
+    }
+
+    let sources: Vec<Option<String>> = map
+        .sources
+        .iter()
+        .map(|s| s.as_deref().map(|s| clean_source(map.source_root.as_deref(), s)))
+        .collect();
+
+    // Only the source index matters, but it's a running delta across lines
+    let mut source_index: i64 = 0;
+    for (i, line_mappings) in map.mappings.split(';').enumerate() {
+        let Some(line) = lines.get(line_offset + i) else {
+            break;
+        };
+        let base = if i == 0 { column_offset } else { 0 };
+        let mut column: i64 = 0;
+        let mut segments: Vec<(usize, Option<usize>)> = Vec::new();
+        for segment in line_mappings.split(',').filter(|s| !s.is_empty()) {
+            let Some(values) = decode_vlq(segment) else {
+                return;
+            };
+            column += values[0];
+            let source = (values.len() >= 4).then(|| {
+                source_index += values[1];
+                source_index as usize
+            });
+            segments.push((base + column.max(0) as usize, source));
+        }
+
+        let mut cursor = Utf16Cursor::new(line);
+        let mut spans = segments.iter().peekable();
+        while let Some(&(start, source)) = spans.next() {
+            let end = spans.peek().map(|(next, _)| *next);
+            let start_byte = cursor.byte_at(start);
+            let end_byte = end.map_or(line.len(), |end| cursor.byte_at(end));
+            let name = source.and_then(|index| sources.get(index).cloned().flatten());
+            if let Some(name) = name {
+                *sizes.entry(name).or_default() += end_byte.saturating_sub(start_byte) as u64;
+            }
+        }
+    }
+}
+
+/// Maps increasing UTF-16 columns of a line to byte offsets
+struct Utf16Cursor<'a> {
+    line: &'a str,
+    /// Checked once — minified lines run to megabytes
+    ascii: bool,
+    chars: std::str::CharIndices<'a>,
+    utf16: usize,
+    byte: usize,
+}
+
+impl<'a> Utf16Cursor<'a> {
+    fn new(line: &'a str) -> Self {
+        Self {
+            line,
+            ascii: line.is_ascii(),
+            chars: line.char_indices(),
+            utf16: 0,
+            byte: 0,
+        }
+    }
+
+    fn byte_at(&mut self, column: usize) -> usize {
+        if self.ascii {
+            return column.min(self.line.len());
+        }
+        while self.utf16 < column {
+            match self.chars.next() {
+                Some((index, c)) => {
+                    self.utf16 += c.len_utf16();
+                    self.byte = index + c.len_utf8();
+                }
+                None => return self.line.len(),
+            }
+        }
+        self.byte
+    }
+}
+
+/// Readable source path: `webpack://app/./src/App.tsx` → `src/App.tsx`,
+/// `../../node_modules/react/index.js` → `node_modules/react/index.js`
+fn clean_source(root: Option<&str>, source: &str) -> String {
+    let mut path = match root {
+        Some(root) if !root.is_empty() && !source.contains("://") => {
+            format!("{}/{}", root.trim_end_matches('/'), source)
+        }
+        _ => source.to_string(),
+    };
+    if let Some(rest) = path.strip_prefix("webpack://") {
+        // First segment is the bundle's namespace
+        path = rest.split_once('/').map_or(rest, |(_, p)| p).to_string();
+    }
+    let mut trimmed = path.as_str();
+    loop {
+        if let Some(rest) = trimmed.strip_prefix("./") {
+            trimmed = rest;
+        } else if let Some(rest) = trimmed.strip_prefix("../") {
+            trimmed = rest;
+        } else {
+            break;
+        }
+    }
+    trimmed.to_string()
+}
+
+fn base64_value(byte: u8) -> Option<i64> {
+    match byte {
+        b'A'..=b'Z' => Some((byte - b'A') as i64),
+        b'a'..=b'z' => Some((byte - b'a') as i64 + 26),
+        b'0'..=b'9' => Some((byte - b'0') as i64 + 52),
+        b'+' | b'-' => Some(62),
+        b'/' | b'_' => Some(63),
+        _ => None,
+    }
+}
+
+/// Base64 VLQ fields of one mapping segment
+fn decode_vlq(segment: &str) -> Option<Vec<i64>> {
+    let mut values = Vec::with_capacity(5);
+    let mut value: i64 = 0;
+    let mut shift = 0;
+    for byte in segment.bytes() {
+        let digit = base64_value(byte)?;
+        value += (digit & 31) << shift;
+        if digit & 32 != 0 {
+            shift += 5;
+            if shift > 60 {
+                return None;
+            }
+        } else {
+            let negative = value & 1 == 1;
+            value >>= 1;
+            values.push(if negative { -value } else { value });
+            value = 0;
+            shift = 0;
+        }
+    }
+    (!values.is_empty()).then_some(values)
+}
+
+fn decode_base64(data: &str) -> Option<Vec<u8>> {
+    let mut out = Vec::with_capacity(data.len() * 3 / 4);
+    let mut buffer: u32 = 0;
+    let mut bits = 0;
+    for byte in data.bytes().filter(|b| !b.is_ascii_whitespace() && *b != b'=') {
+        buffer = (buffer << 6) | base64_value(byte)? as u32;
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            out.push((buffer >> bits) as u8);
+            buffer &= (1 << bits) - 1;
+        }
+    }
+    Some(out)
+}
+
+fn percent_decode(text: &str) -> String {
+    let bytes = text.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'%' if i + 2 < bytes.len() => match std::str::from_utf8(&bytes[i + 1..i + 3])
+                .ok()
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+            {
+                Some(byte) => {
+                    out.push(byte);
+                    i += 3;
+                    continue;
+                }
+                None => out.push(b'%'),
+            },
+            byte => out.push(byte),
+        }
+        i += 1;
+    }
+    String::from_utf8_lossy(&out).to_string()
+}
+
+// ── Chunk Groups ──────────────────────────────────────────────
+
+/// Entry name and the output files it loads directly
+type EntryRoots = Vec<(String, Vec<String>)>;
+
+/// Static and dynamic imports of one output file
+#[derive(Debug, Default)]
+struct Edges {
+    imports: Vec<String>,
+    dynamic: Vec<String>,
+}
+
+/// Group chunks under their entries: the Vite manifest's entries when the
+/// build wrote one, otherwise each HTML page with what it loads
+fn chunk_groups(dir: &Path, files: &[BundleFile]) -> Vec<ChunkGroup> {
+    let known: HashSet<&str> = files.iter().map(|f| f.path.as_str()).collect();
+    let (roots, mut graph) = match vite_manifest(dir) {
+        Some(manifest) => manifest,
+        None => (html_entries(dir, files, &known), HashMap::new()),
+    };
+
+    let sizes: HashMap<&str, &BundleFile> = files.iter().map(|f| (f.path.as_str(), f)).collect();
+    let mut groups: Vec<ChunkGroup> = roots
+        .into_iter()
+        .map(|(entry, initial_roots)| {
+            let (initial, lazy) = reachable(dir, &known, &mut graph, initial_roots);
+            let total = |paths: &[String]| {
+                let mut totals = SizeTotals::default();
+                for file in paths.iter().filter_map(|p| sizes.get(p.as_str())) {
+                    totals.add(file);
+                }
+                totals
+            };
+            ChunkGroup {
+                initial_size: total(&initial),
+                lazy_size: total(&lazy),
+                entry,
+                initial,
+                lazy,
+            }
+        })
+        .collect();
+    groups.sort_by(|a, b| b.initial_size.gzip.cmp(&a.initial_size.gzip).then_with(|| a.entry.cmp(&b.entry)));
+    groups
+}
+
+/// Entries and the import graph from `.vite/manifest.json` (or
+/// `manifest.json` from Vite < 5)
+fn vite_manifest(dir: &Path) -> Option<(EntryRoots, HashMap<String, Edges>)> {
+    let content = fs::read_to_string(dir.join(".vite").join("manifest.json"))
+        .or_else(|_| fs::read_to_string(dir.join("manifest.json")))
+        .ok()?;
+    let manifest: HashMap<String, serde_json::Value> = serde_json::from_str(&content).ok()?;
+    // A web app manifest is also called manifest.json
+    if !manifest.values().all(|chunk| chunk.get("file").is_some()) {
+        return None;
+    }
+
+    let file_of = |key: &str| manifest.get(key).and_then(|c| c["file"].as_str()).map(str::to_string);
+    let list = |chunk: &serde_json::Value, field: &str| -> Vec<String> {
+        chunk[field]
+            .as_array()
+            .map(|items| items.iter().filter_map(|v| v.as_str().map(str::to_string)).collect())
+            .unwrap_or_default()
+    };
+
+    let mut roots = Vec::new();
+    let mut graph = HashMap::new();
+    for (key, chunk) in &manifest {
+        let Some(file) = chunk["file"].as_str() else {
+            continue;
+        };
+        // CSS comes along with the chunk that imports it
+        let mut imports: Vec<String> = list(chunk, "imports").iter().filter_map(|k| file_of(k)).collect();
+        imports.extend(list(chunk, "css"));
+        let dynamic = list(chunk, "dynamicImports").iter().filter_map(|k| file_of(k)).collect();
+        graph.insert(file.to_string(), Edges { imports, dynamic });
+        if chunk["isEntry"].as_bool() == Some(true) {
+            roots.push((key.clone(), vec![file.to_string()]));
+        }
+    }
+    Some((roots, graph))
+}
+
+/// Each HTML page with the scripts, stylesheets and preloads it references
+fn html_entries(dir: &Path, files: &[BundleFile], known: &HashSet<&str>) -> EntryRoots {
+    static SCRIPT: Lazy<Regex> =
+        Lazy::new(|| Regex::new(r#"(?i)<script\b[^>]*?\bsrc\s*=\s*["']([^"']+)["']"#).unwrap());
+    static LINK: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?i)<link\b[^>]*>").unwrap());
+    static HREF: Lazy<Regex> = Lazy::new(|| Regex::new(r#"(?i)\bhref\s*=\s*["']([^"']+)["']"#).unwrap());
+    static REL: Lazy<Regex> =
+        Lazy::new(|| Regex::new(r#"(?i)\brel\s*=\s*["']?[^"'>]*\b(stylesheet|modulepreload)\b"#).unwrap());
+
+    files
+        .iter()
+        .filter(|f| f.kind == AssetKind::Html)
+        .filter_map(|page| {
+            let html = fs::read_to_string(dir.join(&page.path)).ok()?;
+            let mut refs: Vec<String> = SCRIPT.captures_iter(&html).map(|c| c[1].to_string()).collect();
+            refs.extend(
+                LINK.find_iter(&html)
+                    .filter(|tag| REL.is_match(tag.as_str()))
+                    .filter_map(|tag| HREF.captures(tag.as_str()).map(|c| c[1].to_string())),
+            );
+            let roots: Vec<String> = refs
+                .iter()
+                .filter_map(|reference| resolve_reference(&page.path, reference, known))
+                .collect();
+            (!roots.is_empty()).then(|| (page.path.clone(), roots))
+        })
+        .collect()
+}
+
+/// Output file a URL in `from` points at. Absolute URLs may carry the
+/// deploy base path (`/app/assets/x.js`), so leading segments are
+/// dropped until one matches.
+fn resolve_reference(from: &str, reference: &str, known: &HashSet<&str>) -> Option<String> {
+    let reference = reference.split(['?', '#']).next()?;
+    if reference.is_empty() || reference.contains("://") || reference.starts_with("//") || reference.starts_with("data:") {
+        return None;
+    }
+
+    if let Some(absolute) = reference.strip_prefix('/') {
+        let mut candidate = absolute;
+        loop {
+            if known.contains(candidate) {
+                return Some(candidate.to_string());
+            }
+            candidate = candidate.split_once('/')?.1;
+        }
+    }
+
+    let base = Path::new(from).parent().unwrap_or(Path::new(""));
+    let resolved = normalize(&base.join(reference))?;
+    known.contains(resolved.as_str()).then_some(resolved)
+}
+
+/// `a/./b/../c` → `a/c` with forward slashes; None if it climbs out
+fn normalize(path: &Path) -> Option<String> {
+    let mut parts: Vec<String> = Vec::new();
+    for component in path.components() {
+        match component {
+            Component::Normal(part) => parts.push(part.to_string_lossy().to_string()),
+            Component::ParentDir => {
+                parts.pop()?;
+            }
+            _ => {}
+        }
+    }
+    Some(parts.join("/"))
+}
+
+/// Files loaded up front from `roots` (static imports), and those only
+/// reachable through a dynamic import
+fn reachable(
+    dir: &Path,
+    known: &HashSet<&str>,
+    graph: &mut HashMap<String, Edges>,
+    roots: Vec<String>,
+) -> (Vec<String>, Vec<String>) {
+    let mut initial: Vec<String> = Vec::new();
+    let mut seen: HashSet<String> = HashSet::new();
+    let mut lazy_roots = Vec::new();
+
+    let mut queue: VecDeque<String> = roots.into_iter().collect();
+    while let Some(file) = queue.pop_front() {
+        if !seen.insert(file.clone()) {
+            continue;
+        }
+        let edges = graph.entry(file.clone()).or_insert_with(|| scan_imports(dir, &file, known));
+        queue.extend(edges.imports.iter().cloned());
+        lazy_roots.extend(edges.dynamic.iter().cloned());
+        initial.push(file);
+    }
+
+    let mut lazy = Vec::new();
+    let mut queue: VecDeque<String> = lazy_roots.into_iter().collect();
+    while let Some(file) = queue.pop_front() {
+        if !seen.insert(file.clone()) {
+            continue;
+        }
+        let edges = graph.entry(file.clone()).or_insert_with(|| scan_imports(dir, &file, known));
+        queue.extend(edges.imports.iter().cloned());
+        queue.extend(edges.dynamic.iter().cloned());
+        lazy.push(file);
+    }
+    (initial, lazy)
+}
+
+/// Relative imports of an ES module chunk, read from the minified code
+fn scan_imports(dir: &Path, file: &str, known: &HashSet<&str>) -> Edges {
+    static STATIC: Lazy<Regex> =
+        Lazy::new(|| Regex::new(r#"(?:\bfrom|\bimport)\s*["']([^"'\n]+\.m?js)["']"#).unwrap());
+    static DYNAMIC: Lazy<Regex> =
+        Lazy::new(|| Regex::new(r#"\bimport\(\s*["']([^"'\n]+\.m?js)["']\s*\)"#).unwrap());
+
+    if kind_of(file) != AssetKind::Script {
+        return Edges::default();
+    }
+    let Ok(code) = fs::read_to_string(dir.join(file)) else {
+        return Edges::default();
+    };
+    let resolve = |re: &Regex| -> Vec<String> {
+        let mut found: Vec<String> = re
+            .captures_iter(&code)
+            .filter_map(|c| resolve_reference(file, &c[1], known))
+            .filter(|target| target != file)
+            .collect();
+        found.sort();
+        found.dedup();
+        found
+    };
+    Edges {
+        imports: resolve(&STATIC),
+        dynamic: resolve(&DYNAMIC),
+    }
+}
+
+// ── History ───────────────────────────────────────────────────
+
+/// `assets/index-BfX3a9Zq.js` → `assets/index.js`,
+/// `static/js/main.3f2a1b4c.chunk.js` → `static/js/main.chunk.js`,
+/// `chunks/framework-2c79e2a64abdb08b.js` → `chunks/framework.js`
+fn stable_name(path: &str) -> String {
+    let (dir, name) = match path.rsplit_once('/') {
+        Some((dir, name)) => (format!("{}/", dir), name),
+        None => (String::new(), path),
+    };
+    let segments: Vec<&str> = name.split('.').collect();
+    let last = segments.len() - 1;
+    let kept: Vec<&str> = segments
+        .iter()
+        .enumerate()
+        // webpack's `[name].[contenthash]`: a hex segment between the name and extension
+        .filter(|&(i, segment)| i == 0 || i == last || !is_hex_hash(segment))
+        .map(|(i, segment)| if i == last && last > 0 { *segment } else { strip_dash_hash(segment) })
+        .collect();
+    format!("{}{}", dir, kept.join("."))
+}
+
+/// `name-hash` → `name` for Rollup/Vite's `[name]-[hash]` (8 base64url
+/// chars, which may themselves contain `-`) and Next.js's hex hashes
+fn strip_dash_hash(segment: &str) -> &str {
+    let bytes = segment.as_bytes();
+    if bytes.len() > 9 && bytes[bytes.len() - 9] == b'-' {
+        let hash = &bytes[bytes.len() - 8..];
+        let base64url = hash.iter().all(|b| b.is_ascii_alphanumeric() || *b == b'-' || *b == b'_');
+        // A plain lowercase word in that position is the name, not a hash
+        // (random 8-char hashes are all lowercase well under 0.1% of the time)
+        if base64url && !hash.iter().all(|b| b.is_ascii_lowercase()) {
+            return &segment[..segment.len() - 9];
+        }
+    }
+    match segment.rsplit_once('-') {
+        Some((name, hash)) if !name.is_empty() && is_hex_hash(hash) => name,
+        _ => segment,
+    }
+}
+
+fn is_hex_hash(segment: &str) -> bool {
+    segment.len() >= 8 && segment.bytes().all(|b| b.is_ascii_hexdigit())
+}
+
+fn snapshot_of(files: &[BundleFile], analyzed_at: &str) -> Snapshot {
+    let mut stable: BTreeMap<String, u64> = BTreeMap::new();
+    let mut listing = String::new();
+    for file in files {
+        *stable.entry(stable_name(&file.path)).or_default() += file.gzip.unwrap_or(file.raw);
+        listing.push_str(&format!("{}:{}\n", file.path, file.raw));
+    }
+    Snapshot {
+        analyzed_at: analyzed_at.to_string(),
+        fingerprint: crate::installer::fnv1a(listing.as_bytes()),
+        files: stable,
+    }
+}
+
+fn get_history_file_path() -> PathBuf {
+    let home = dirs_next::home_dir().unwrap_or_else(|| PathBuf::from("."));
+    let dir = home.join(".mydevify").join("data");
+    fs::create_dir_all(&dir).ok();
+    dir.join("bundle-history.json")
+}
+
+fn load_history() -> HashMap<String, History> {
+    fs::read_to_string(get_history_file_path())
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+/// Record `snapshot` as the project's latest build and return the build
+/// to compare it with
+fn record_snapshot(project: &str, snapshot: Snapshot) -> Result<Option<Snapshot>, String> {
+    let _guard = HISTORY_LOCK.lock().map_err(|e| e.to_string())?;
+    let mut all = load_history();
+    let baseline = match all.remove(project) {
+        // Same build analyzed again
+        Some(history) if history.latest.fingerprint == snapshot.fingerprint => history.previous,
+        Some(history) => Some(history.latest),
+        None => None,
+    };
+    all.insert(
+        project.to_string(),
+        History {
+            latest: snapshot,
+            previous: baseline.clone(),
+        },
+    );
+    let json = serde_json::to_string_pretty(&all).map_err(|e| e.to_string())?;
+    fs::write(get_history_file_path(), json).map_err(|e| e.to_string())?;
+    Ok(baseline)
+}
+
+fn compare(previous: &Snapshot, current: &Snapshot, budget: &BundleBudget) -> BundleComparison {
+    let regressed = |before: u64, after: u64| {
+        let delta = after as i64 - before as i64;
+        let percent = if before > 0 { delta as f64 * 100.0 / before as f64 } else { 0.0 };
+        delta > budget.min_growth_bytes as i64 && percent > budget.max_growth_percent
+    };
+
+    let names: HashSet<&String> = previous.files.keys().chain(current.files.keys()).collect();
+    let mut changes: Vec<SizeChange> = names
+        .into_iter()
+        .filter_map(|name| {
+            let before = previous.files.get(name).copied();
+            let after = current.files.get(name).copied();
+            let delta = after.unwrap_or(0) as i64 - before.unwrap_or(0) as i64;
+            if delta == 0 && before.is_some() == after.is_some() {
+                return None;
+            }
+            Some(SizeChange {
+                name: name.clone(),
+                previous: before,
+                current: after,
+                delta,
+                percent: before.filter(|&b| b > 0).map(|b| delta as f64 * 100.0 / b as f64),
+                regression: matches!((before, after), (Some(b), Some(a)) if regressed(b, a)),
+            })
+        })
+        .collect();
+    changes.sort_by(|a, b| b.delta.cmp(&a.delta).then_with(|| a.name.cmp(&b.name)));
+
+    let previous_total: u64 = previous.files.values().sum();
+    let current_total: u64 = current.files.values().sum();
+    BundleComparison {
+        previous_analyzed_at: previous.analyzed_at.clone(),
+        previous_total,
+        total_delta: current_total as i64 - previous_total as i64,
+        total_regression: regressed(previous_total, current_total),
+        regressions: changes.iter().filter(|c| c.regression).count(),
+        changes,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn map(json: &str) -> SourceMap {
+        serde_json::from_str(json).unwrap()
+    }
+
+    #[test]
+    fn decodes_vlq_segments() {
+        assert_eq!(decode_vlq("AAAA"), Some(vec![0, 0, 0, 0]));
+        assert_eq!(decode_vlq("SAAQ"), Some(vec![9, 0, 0, 8]));
+        assert_eq!(decode_vlq("D"), Some(vec![-1]));
+        // 16 needs a continuation digit
+        assert_eq!(decode_vlq("gB"), Some(vec![16]));
+        assert_eq!(decode_vlq("A!"), None);
+        assert_eq!(decode_vlq(""), None);
+    }
+
+    #[test]
+    fn attributes_bytes_between_segments() {
+        let map = map(r#"{"sources":["../node_modules/react/index.js","../src/main.tsx"],"mappings":"AAAA,QCAA"}"#);
+        let sizes = attribute(&map, "var a=1;var b=22;");
+        assert_eq!(sizes["node_modules/react/index.js"], 8);
+        assert_eq!(sizes["src/main.tsx"], 9);
+    }
+
+    #[test]
+    fn source_index_carries_across_lines() {
+        // Line 2 has no source delta, so it still maps to the second source
+        let map = map(r#"{"sources":["a.js","b.js"],"mappings":"AAAA,ECAA;AAAA"}"#);
+        let sizes = attribute(&map, "xxyy\nzzz");
+        assert_eq!(sizes["a.js"], 2);
+        assert_eq!(sizes["b.js"], 2 + 3);
+    }
+
+    #[test]
+    fn columns_count_utf16_units() {
+        // "é€" is 2 UTF-16 units but 5 bytes
+        let map = map(r#"{"sources":["a.js","b.js"],"mappings":"AAAA,ECAA"}"#);
+        let sizes = attribute(&map, "é€xyz");
+        assert_eq!(sizes["a.js"], 5);
+        assert_eq!(sizes["b.js"], 3);
+    }
+
+    #[test]
+    fn unmapped_segments_count_for_nothing() {
+        let map = map(r#"{"sources":["a.js"],"mappings":"AAAA,E"}"#);
+        let sizes = attribute(&map, "abcdef");
+        assert_eq!(sizes["a.js"], 2);
+    }
+
+    #[test]
+    fn index_maps_are_placed_at_their_offsets() {
+        let map = map(
+            r#"{"sections":[
+                {"offset":{"line":0,"column":0},"map":{"sources":["a.js"],"mappings":"AAAA"}},
+                {"offset":{"line":1,"column":2},"map":{"sources":["b.js"],"mappings":"AAAA"}}
+            ]}"#,
+        );
+        let sizes = attribute(&map, "aaaa\nxxbbb");
+        assert_eq!(sizes["a.js"], 4);
+        assert_eq!(sizes["b.js"], 3);
+    }
+
+    #[test]
+    fn cleans_source_paths() {
+        assert_eq!(clean_source(None, "webpack://app/./src/App.tsx"), "src/App.tsx");
+        assert_eq!(clean_source(None, "../../node_modules/react/index.js"), "node_modules/react/index.js");
+        assert_eq!(clean_source(Some("src/"), "main.ts"), "src/main.ts");
+    }
+
+    #[test]
+    fn finds_package_names() {
+        assert_eq!(package_name("node_modules/react/index.js").as_deref(), Some("react"));
+        assert_eq!(package_name("node_modules/@scope/pkg/a.js").as_deref(), Some("@scope/pkg"));
+        assert_eq!(
+            package_name("node_modules/.pnpm/react@18.2.0/node_modules/react/cjs/react.js").as_deref(),
+            Some("react")
+        );
+        assert_eq!(package_name("src/main.tsx"), None);
+    }
+
+    #[test]
+    fn strips_content_hashes() {
+        let cases = [
+            ("assets/index-BfX3a9Zq.js", "assets/index.js"),
+            // Rollup hashes without digits, or containing `-` / `_`
+            ("assets/index-BxQmTwZk.js", "assets/index.js"),
+            ("assets/index-B-x_3a9Z.js", "assets/index.js"),
+            ("assets/vendor-react-Ck-9_aQz.js", "assets/vendor-react.js"),
+            ("assets/logo-Aa11bb22.png", "assets/logo.png"),
+            ("static/js/main.3f2a1b4c.chunk.js", "static/js/main.chunk.js"),
+            ("static/css/main.3f2a1b4c.css", "static/css/main.css"),
+            ("chunks/framework-2c79e2a64abdb08b.js", "chunks/framework.js"),
+            ("123.3f2a1b4cde.js", "123.js"),
+            // Plain names stay as they are
+            ("polyfills-legacy.js", "polyfills-legacy.js"),
+            ("app-settings.js", "app-settings.js"),
+            ("my-component.js", "my-component.js"),
+            ("index.html", "index.html"),
+            ("robots.txt", "robots.txt"),
+        ];
+        for (path, expected) in cases {
+            assert_eq!(stable_name(path), expected, "{}", path);
+        }
+    }
+}
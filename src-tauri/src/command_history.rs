@@ -0,0 +1,286 @@
+// ── Command History — Per-Project ─────────────────────────────
+//
+// Every execute_command call (and every task RunCommand/RunScript step)
+// is appended to a JSON Lines file for its project, so the terminal keeps
+// its history across restarts and users can see what the AI ran.
+// Files live in ~/.mydevify/data/command_history/, one per project.
+// Retention is a per-project entry limit, enforced on append.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// Max characters of output kept per record
+const OUTPUT_EXCERPT_CHARS: usize = 2_000;
+
+// ── Data Model ────────────────────────────────────────────────
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Initiator {
+    /// Typed in the terminal panel
+    User,
+    /// Run by the AI via a tool call
+    Ai,
+    /// Run by a scheduled task step
+    Task,
+    /// Internal housekeeping (git status polling etc.) — not recorded
+    App,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistoryEntry {
+    pub id: String,
+    pub command: String,
+    pub cwd: String,
+    pub initiator: Initiator,
+    pub exit_code: i32,
+    pub duration_ms: u64,
+    /// Tail of stdout + stderr
+    pub output_excerpt: String,
+    pub started_at: String, // ISO 8601 string
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct HistoryQuery {
+    /// Command starts with this text
+    pub prefix: Option<String>,
+    /// Command contains this text (case-insensitive)
+    pub contains: Option<String>,
+    pub initiator: Option<Initiator>,
+    /// ISO 8601 lower bound on started_at (inclusive)
+    pub since: Option<String>,
+    /// ISO 8601 upper bound on started_at (inclusive)
+    pub until: Option<String>,
+    /// Max entries to return, newest first (default 200)
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistoryConfig {
+    /// Entries kept per project; older ones are dropped on append
+    pub max_entries_per_project: usize,
+}
+
+impl Default for HistoryConfig {
+    fn default() -> Self {
+        Self {
+            max_entries_per_project: 1_000,
+        }
+    }
+}
+
+// ── Storage ───────────────────────────────────────────────────
+
+/// Serializes file access; also caches entry counts per history file
+/// so we don't re-read the file on every append.
+static HISTORY_LOCK: once_cell::sync::Lazy<Mutex<HashMap<PathBuf, usize>>> =
+    once_cell::sync::Lazy::new(|| Mutex::new(HashMap::new()));
+
+static HISTORY_CONFIG: once_cell::sync::Lazy<Mutex<HistoryConfig>> =
+    once_cell::sync::Lazy::new(|| Mutex::new(load_config()));
+
+fn get_history_dir() -> PathBuf {
+    let home = dirs_next::home_dir().unwrap_or_else(|| PathBuf::from("."));
+    let dir = home.join(".mydevify").join("data").join("command_history");
+    fs::create_dir_all(&dir).ok();
+    dir
+}
+
+/// History file for a project — the path is flattened into a file name
+/// (e.g. /home/me/site → _home_me_site.jsonl)
+fn get_history_file_path(project: &Path) -> PathBuf {
+    let name: String = project
+        .to_string_lossy()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '.' { c } else { '_' })
+        .collect();
+    get_history_dir().join(format!("{}.jsonl", name))
+}
+
+fn load_config() -> HistoryConfig {
+    fs::read_to_string(get_history_dir().join("settings.json"))
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn read_entries(path: &Path) -> Vec<HistoryEntry> {
+    fs::read_to_string(path)
+        .map(|content| {
+            content
+                .lines()
+                .filter_map(|line| serde_json::from_str(line).ok())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn write_entries(path: &Path, entries: &[HistoryEntry]) -> Result<(), String> {
+    let mut content = String::new();
+    for entry in entries {
+        content.push_str(&serde_json::to_string(entry).map_err(|e| e.to_string())?);
+        content.push('\n');
+    }
+    fs::write(path, content).map_err(|e| e.to_string())
+}
+
+// ── Recording ─────────────────────────────────────────────────
+
+/// Keep the end of the output — that's where errors usually are
+fn excerpt(stdout: &str, stderr: &str) -> String {
+    let combined = match (stdout.trim().is_empty(), stderr.trim().is_empty()) {
+        (false, false) => format!("{}\n{}", stdout.trim_end(), stderr.trim_end()),
+        (false, true) => stdout.trim_end().to_string(),
+        (true, false) => stderr.trim_end().to_string(),
+        (true, true) => String::new(),
+    };
+    let char_count = combined.chars().count();
+    if char_count <= OUTPUT_EXCERPT_CHARS {
+        combined
+    } else {
+        let tail: String = combined.chars().skip(char_count - OUTPUT_EXCERPT_CHARS).collect();
+        format!("…{}", tail)
+    }
+}
+
+/// A command that just finished, as reported by the caller
+pub struct FinishedCommand<'a> {
+    pub command: &'a str,
+    pub cwd: &'a Path,
+    pub initiator: Initiator,
+    pub exit_code: i32,
+    pub stdout: &'a str,
+    pub stderr: &'a str,
+    pub started_at: DateTime<Utc>,
+}
+
+/// Append a finished command to its project's history.
+/// Errors are returned but callers treat history as best-effort.
+pub fn record(project: &Path, run: FinishedCommand) -> Result<(), String> {
+    if run.initiator == Initiator::App || run.command.trim().is_empty() {
+        return Ok(());
+    }
+
+    let duration_ms = (Utc::now() - run.started_at).num_milliseconds().max(0) as u64;
+    let entry = HistoryEntry {
+        id: uuid::Uuid::new_v4().to_string(),
+        command: run.command.trim().to_string(),
+        cwd: run.cwd.to_string_lossy().to_string(),
+        initiator: run.initiator,
+        exit_code: run.exit_code,
+        duration_ms,
+        output_excerpt: excerpt(run.stdout, run.stderr),
+        started_at: run.started_at.to_rfc3339(),
+    };
+
+    let path = get_history_file_path(project);
+    let limit = get_config().max_entries_per_project.max(1);
+    let mut counts = HISTORY_LOCK.lock().map_err(|e| e.to_string())?;
+
+    let count = match counts.get(&path) {
+        Some(c) => *c,
+        None => read_entries(&path).len(),
+    };
+
+    let line = serde_json::to_string(&entry).map_err(|e| e.to_string())?;
+    let mut file = fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .map_err(|e| e.to_string())?;
+    writeln!(file, "{}", line).map_err(|e| e.to_string())?;
+    drop(file);
+
+    // Trim in batches (10% slack) so we don't rewrite the file on every append
+    let mut new_count = count + 1;
+    if new_count > limit + (limit / 10).max(1) {
+        // Rewrite with only the newest `limit` entries
+        let mut entries = read_entries(&path);
+        if entries.len() > limit {
+            entries = entries.split_off(entries.len() - limit);
+        }
+        write_entries(&path, &entries)?;
+        new_count = entries.len();
+    }
+    counts.insert(path, new_count);
+
+    Ok(())
+}
+
+// ── Queries ───────────────────────────────────────────────────
+
+fn parse_time(value: &Option<String>) -> Result<Option<DateTime<Utc>>, String> {
+    match value {
+        Some(s) => DateTime::parse_from_rfc3339(s)
+            .map(|t| Some(t.with_timezone(&Utc)))
+            .map_err(|e| format!("Invalid timestamp '{}': {}", s, e)),
+        None => Ok(None),
+    }
+}
+
+/// Search a project's history. Results are newest first.
+pub fn query(project: &Path, query: &HistoryQuery) -> Result<Vec<HistoryEntry>, String> {
+    let since = parse_time(&query.since)?;
+    let until = parse_time(&query.until)?;
+    let contains = query.contains.as_ref().map(|c| c.to_lowercase());
+    let limit = query.limit.unwrap_or(200);
+
+    let entries = {
+        let _guard = HISTORY_LOCK.lock().map_err(|e| e.to_string())?;
+        read_entries(&get_history_file_path(project))
+    };
+
+    Ok(entries
+        .into_iter()
+        .rev()
+        .filter(|e| query.prefix.as_ref().is_none_or(|p| e.command.starts_with(p.as_str())))
+        .filter(|e| contains.as_ref().is_none_or(|c| e.command.to_lowercase().contains(c)))
+        .filter(|e| query.initiator.is_none_or(|i| e.initiator == i))
+        .filter(|e| {
+            if since.is_none() && until.is_none() {
+                return true;
+            }
+            match DateTime::parse_from_rfc3339(&e.started_at) {
+                Ok(t) => {
+                    let t = t.with_timezone(&Utc);
+                    since.is_none_or(|s| t >= s) && until.is_none_or(|u| t <= u)
+                }
+                Err(_) => false,
+            }
+        })
+        .take(limit)
+        .collect())
+}
+
+/// Delete all history for a project
+pub fn clear(project: &Path) -> Result<(), String> {
+    let path = get_history_file_path(project);
+    let mut counts = HISTORY_LOCK.lock().map_err(|e| e.to_string())?;
+    counts.remove(&path);
+    if path.exists() {
+        fs::remove_file(&path).map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+// ── Settings ──────────────────────────────────────────────────
+
+pub fn get_config() -> HistoryConfig {
+    HISTORY_CONFIG.lock().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+pub fn set_config(config: HistoryConfig) -> Result<HistoryConfig, String> {
+    if config.max_entries_per_project == 0 {
+        return Err("Retention limit must be at least 1 entry".to_string());
+    }
+    let json = serde_json::to_string_pretty(&config).map_err(|e| e.to_string())?;
+    fs::write(get_history_dir().join("settings.json"), json).map_err(|e| e.to_string())?;
+    *HISTORY_CONFIG.lock().map_err(|e| e.to_string())? = config.clone();
+    // Cached counts are still valid; files are trimmed on their next append
+    Ok(config)
+}
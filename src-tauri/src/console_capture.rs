@@ -0,0 +1,356 @@
+// ── Console Capture — Preview Runtime Errors ──────────────────
+//
+// Build errors reach the backend through the dev server output, but
+// runtime errors only ever show in the preview's devtools. HTML served
+// by the preview (static or proxied) gets a small reporter script at the
+// top of <head>: it forwards console calls, uncaught errors, unhandled
+// promise rejections and failed fetches to REPORT_PATH in batches. The
+// backend keeps them with their source locations, collapses immediate
+// repeats, and emits each new entry as a `preview-console` event.
+
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::routing::post;
+use axum::{Json, Router};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use tauri::Emitter;
+
+/// Endpoint the reporter script posts to
+pub const REPORT_PATH: &str = "/__mydevify/console";
+/// Entries kept across all preview servers
+const MAX_ENTRIES: usize = 500;
+/// Entries accepted per report — a render loop can throw thousands
+const MAX_BATCH: usize = 50;
+/// Longer messages and stacks are cut to this many chars
+const MAX_TEXT_CHARS: usize = 4_000;
+
+// ── Data Model ────────────────────────────────────────────────
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConsoleLevel {
+    Debug,
+    Log,
+    Info,
+    Warn,
+    Error,
+    /// Uncaught exception (window `error` event)
+    Exception,
+    /// Unhandled promise rejection
+    Rejection,
+    /// fetch() that failed or answered 4xx/5xx
+    Network,
+}
+
+/// One entry as sent by the reporter script
+#[derive(Debug, Clone, Deserialize)]
+struct ReportedEntry {
+    level: ConsoleLevel,
+    message: String,
+    #[serde(default)]
+    stack: Option<String>,
+    /// Set by the browser for uncaught errors
+    #[serde(default)]
+    source: Option<String>,
+    #[serde(default)]
+    line: Option<u32>,
+    #[serde(default)]
+    column: Option<u32>,
+    #[serde(default)]
+    page: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SourceLocation {
+    pub url: String,
+    pub line: u32,
+    pub column: u32,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ConsoleEntry {
+    pub seq: u64,
+    /// Preview server key (project path or window id)
+    pub server: String,
+    pub timestamp: String, // ISO 8601 string
+    pub level: ConsoleLevel,
+    pub message: String,
+    pub location: Option<SourceLocation>,
+    pub stack: Option<String>,
+    /// Page URL the entry came from
+    pub page: Option<String>,
+    /// Times this exact entry was reported in a row
+    pub count: u32,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ConsoleReadOptions {
+    /// Only entries from this preview server (None = all)
+    pub server: Option<String>,
+    /// Return entries with seq greater than this (None = from the oldest kept)
+    pub since: Option<u64>,
+    /// Max entries to return, oldest first (None = all available)
+    pub limit: Option<usize>,
+    /// Only errors, exceptions, rejections and failed requests
+    #[serde(default)]
+    pub errors_only: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ConsoleRead {
+    pub entries: Vec<ConsoleEntry>,
+    /// Pass back as `since` on the next read
+    pub cursor: u64,
+}
+
+struct ConsoleLog {
+    entries: VecDeque<ConsoleEntry>,
+    last_seq: u64,
+}
+
+static CONSOLE_LOG: Mutex<ConsoleLog> = Mutex::new(ConsoleLog {
+    entries: VecDeque::new(),
+    last_seq: 0,
+});
+
+impl ConsoleLevel {
+    fn is_error(self) -> bool {
+        matches!(
+            self,
+            ConsoleLevel::Error | ConsoleLevel::Exception | ConsoleLevel::Rejection | ConsoleLevel::Network
+        )
+    }
+}
+
+// ── Endpoint ──────────────────────────────────────────────────
+
+struct Reporter {
+    server: String,
+    app_handle: tauri::AppHandle,
+}
+
+/// Route receiving reports for the preview server `server`, to merge into
+/// its router
+pub fn routes(server: &str, app_handle: &tauri::AppHandle) -> Router {
+    let reporter = Arc::new(Reporter {
+        server: server.to_string(),
+        app_handle: app_handle.clone(),
+    });
+    Router::new().route(REPORT_PATH, post(receive)).with_state(reporter)
+}
+
+async fn receive(State(reporter): State<Arc<Reporter>>, Json(batch): Json<Vec<ReportedEntry>>) -> StatusCode {
+    for reported in batch.into_iter().take(MAX_BATCH) {
+        if let Some(entry) = store(&reporter.server, reported) {
+            let _ = reporter.app_handle.emit("preview-console", entry);
+        }
+    }
+    StatusCode::NO_CONTENT
+}
+
+/// Add an entry to the log. Returns it if it's new, None if it only
+/// bumped the count of the previous identical entry.
+fn store(server: &str, reported: ReportedEntry) -> Option<ConsoleEntry> {
+    let location = match (reported.source, reported.line) {
+        (Some(url), Some(line)) if !url.is_empty() => Some(SourceLocation {
+            url,
+            line,
+            column: reported.column.unwrap_or(0),
+        }),
+        _ => reported.stack.as_deref().and_then(first_frame),
+    };
+    let message = truncate(&reported.message);
+    let stack = reported.stack.as_deref().map(truncate);
+
+    let mut log = CONSOLE_LOG.lock().unwrap_or_else(|e| e.into_inner());
+
+    if let Some(last) = log.entries.back_mut() {
+        let same_location = match (&last.location, &location) {
+            (Some(a), Some(b)) => a.url == b.url && a.line == b.line && a.column == b.column,
+            (None, None) => true,
+            _ => false,
+        };
+        if last.server == server && last.level == reported.level && last.message == message && same_location {
+            last.count += 1;
+            return None;
+        }
+    }
+
+    log.last_seq += 1;
+    let entry = ConsoleEntry {
+        seq: log.last_seq,
+        server: server.to_string(),
+        timestamp: chrono::Utc::now().to_rfc3339(),
+        level: reported.level,
+        message,
+        location,
+        stack,
+        page: reported.page,
+        count: 1,
+    };
+    if log.entries.len() >= MAX_ENTRIES {
+        log.entries.pop_front();
+    }
+    log.entries.push_back(entry.clone());
+    Some(entry)
+}
+
+fn truncate(text: &str) -> String {
+    match text.char_indices().nth(MAX_TEXT_CHARS) {
+        Some((cut, _)) => format!("{}…", &text[..cut]),
+        None => text.to_string(),
+    }
+}
+
+/// First `url:line:column` in a V8 (`at f (url:1:2)`) or Firefox/Safari
+/// (`f@url:1:2`) stack trace
+fn first_frame(stack: &str) -> Option<SourceLocation> {
+    static FRAME: Lazy<regex::Regex> =
+        Lazy::new(|| regex::Regex::new(r"((?:https?|file|webpack|blob:https?)://[^\s()]+?):(\d+):(\d+)").unwrap());
+    let caps = FRAME.captures(stack)?;
+    Some(SourceLocation {
+        url: caps[1].to_string(),
+        line: caps[2].parse().ok()?,
+        column: caps[3].parse().ok()?,
+    })
+}
+
+// ── Reading ───────────────────────────────────────────────────
+
+pub fn read(options: &ConsoleReadOptions) -> ConsoleRead {
+    let log = CONSOLE_LOG.lock().unwrap_or_else(|e| e.into_inner());
+    let since = options.since.unwrap_or(0);
+    let limit = options.limit.unwrap_or(usize::MAX);
+    let mut matching = log
+        .entries
+        .iter()
+        .filter(|e| e.seq > since)
+        .filter(|e| options.server.as_ref().is_none_or(|s| &e.server == s))
+        .filter(|e| !options.errors_only || e.level.is_error());
+    let entries: Vec<ConsoleEntry> = matching.by_ref().take(limit).cloned().collect();
+
+    // Skip past filtered-out entries too, unless the limit cut the read short
+    let cursor = match (matching.next(), entries.last()) {
+        (Some(_), Some(last)) => last.seq,
+        _ => log.last_seq,
+    };
+    ConsoleRead { entries, cursor }
+}
+
+/// Forget the entries of one preview server (all when None)
+pub fn clear(server: Option<&str>) {
+    let mut log = CONSOLE_LOG.lock().unwrap_or_else(|e| e.into_inner());
+    match server {
+        Some(server) => log.entries.retain(|e| e.server != server),
+        None => log.entries.clear(),
+    }
+}
+
+// ── Reporter Script ───────────────────────────────────────────
+
+const REPORTER_SCRIPT: &str = r#"<script data-mydevify-console>
+(function () {
+  if (window.__mydevifyConsole) return;
+  window.__mydevifyConsole = true;
+  var queue = [], timer = null;
+  var origFetch = window.fetch;
+  function flush() {
+    timer = null;
+    if (!queue.length) return;
+    var batch = queue.splice(0, 50);
+    try {
+      (origFetch || fetch).call(window, "/__mydevify/console", {
+        method: "POST", keepalive: true,
+        headers: { "content-type": "application/json" },
+        body: JSON.stringify(batch)
+      }).catch(function () {});
+    } catch (_) {}
+  }
+  function report(entry) {
+    entry.page = location.href;
+    queue.push(entry);
+    if (queue.length > 200) queue.shift();
+    if (!timer) timer = setTimeout(flush, 250);
+  }
+  function text(value) {
+    if (value instanceof Error) return value.stack || String(value);
+    if (typeof value === "string") return value;
+    try { return JSON.stringify(value); } catch (_) { return String(value); }
+  }
+  function callerStack() {
+    var stack = new Error().stack || "";
+    return stack.split("\n").filter(function (l) {
+      return l.indexOf("__mydevifyConsole") < 0 && l.indexOf("callerStack") < 0 && l.trim() !== "Error";
+    }).join("\n");
+  }
+  ["debug", "log", "info", "warn", "error"].forEach(function (level) {
+    var original = console[level];
+    if (!original) return;
+    console[level] = function __mydevifyConsole() {
+      var args = Array.prototype.slice.call(arguments);
+      var error = args.filter(function (a) { return a instanceof Error; })[0];
+      report({ level: level, message: args.map(text).join(" "), stack: error ? error.stack : callerStack() });
+      return original.apply(console, arguments);
+    };
+  });
+  window.addEventListener("error", function (e) {
+    if (e.target && e.target !== window) {
+      var url = e.target.src || e.target.href;
+      if (url) report({ level: "network", message: "Failed to load " + e.target.tagName.toLowerCase() + ": " + url });
+      return;
+    }
+    report({
+      level: "exception", message: e.message || text(e.error),
+      stack: e.error && e.error.stack, source: e.filename, line: e.lineno, column: e.colno
+    });
+  }, true);
+  window.addEventListener("unhandledrejection", function (e) {
+    var reason = e.reason;
+    report({ level: "rejection", message: "Unhandled rejection: " + text(reason), stack: reason && reason.stack });
+  });
+  if (origFetch) {
+    window.fetch = function __mydevifyConsole(input, init) {
+      var url = typeof input === "string" ? input : (input && input.url) || String(input);
+      var method = (init && init.method) || (input && input.method) || "GET";
+      var stack = callerStack();
+      return origFetch.apply(this, arguments).then(function (response) {
+        if (!response.ok) report({ level: "network", message: method + " " + url + " → " + response.status, stack: stack });
+        return response;
+      }, function (err) {
+        report({ level: "network", message: method + " " + url + " failed: " + text(err), stack: stack });
+        throw err;
+      });
+    };
+  }
+  window.addEventListener("pagehide", flush);
+})();
+</script>"#;
+
+/// Insert the reporter at the top of <head> so it sees errors from every
+/// script after it (falls back to right after <html>, then after the
+/// doctype — anything before it would put the page in quirks mode)
+pub fn inject_reporter(html: &str) -> String {
+    // ASCII-only lowercasing keeps byte offsets valid for slicing `html`
+    let lower = html.to_ascii_lowercase();
+    let after_doctype = || {
+        let start = lower.find("<!doctype")?;
+        lower[start..].find('>').map(|end| start + end + 1)
+    };
+    let insert_at = ["<head", "<html"]
+        .iter()
+        .find_map(|tag| {
+            let start = lower.find(tag)?;
+            // `<header` isn't `<head`
+            let next = lower[start + tag.len()..].chars().next()?;
+            if next != '>' && !next.is_whitespace() {
+                return None;
+            }
+            lower[start..].find('>').map(|end| start + end + 1)
+        })
+        .or_else(after_doctype)
+        .unwrap_or(0);
+    format!("{}{}{}", &html[..insert_at], REPORTER_SCRIPT, &html[insert_at..])
+}
@@ -0,0 +1,717 @@
+// ── Dev Server — Framework Projects ───────────────────────────
+//
+// Runs the project's dev server (`npm run dev` etc.), watches its output
+// for the port (falling back to the sockets the process tree is listening
+// on, see port_discovery.rs), then confirms readiness with an HTTP probe. All
+// output goes into a cursor-based ring buffer (log_buffer.rs) that the UI
+// and the AI can each follow for build errors.
+// If the process exits on its own we emit `dev-server-exited` with the
+// exit code and last log lines, and (opt-in) restart it with exponential
+// backoff, emitting `dev-server-restarted` once it's serving again.
+// All output is also fed to a diagnostics tracker (diagnostics.rs) and
+// changes to the parsed build errors go out as `dev-server-diagnostics`.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tauri::Emitter;
+
+use crate::diagnostics::{Diagnostic, DiagnosticsTracker};
+use crate::log_buffer::{LogBuffer, LogRead, LogReadOptions, LogStream};
+
+/// How long to wait for a port to show up in the output
+const PORT_TIMEOUT: Duration = Duration::from_secs(30);
+/// Give the port line this long before scanning listening sockets —
+/// the printed port is more reliable when a server opens several
+const SOCKET_SCAN_DELAY: Duration = Duration::from_secs(3);
+/// How long the HTTP probe keeps trying after the port is known
+const HEALTH_TIMEOUT: Duration = Duration::from_secs(20);
+/// Lines kept for the `dev-server-exited` event
+const TAIL_LINES: usize = 50;
+/// A server that stayed up this long resets the restart counter
+const STABLE_UPTIME: Duration = Duration::from_secs(60);
+const MAX_BACKOFF: Duration = Duration::from_secs(30);
+/// Lines of output kept unless the caller asks for another capacity
+const DEFAULT_LOG_CAPACITY: usize = 2_000;
+
+// ── Data Model ────────────────────────────────────────────────
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct DevServerOptions {
+    /// Restart the server if it crashes after becoming ready
+    pub auto_restart: bool,
+    /// Give up after this many consecutive restarts
+    pub max_restarts: u32,
+    /// Path probed over HTTP to confirm readiness (None = trust the port line)
+    pub health_check_path: Option<String>,
+    /// Lines of output kept in the log buffer
+    pub log_capacity: usize,
+    /// Extra environment variables for the server process
+    pub env: HashMap<String, String>,
+    /// Port the server is configured to use — ready once it accepts
+    /// connections, without waiting for the port line
+    pub port: Option<u16>,
+}
+
+impl Default for DevServerOptions {
+    fn default() -> Self {
+        Self {
+            auto_restart: false,
+            max_restarts: 5,
+            health_check_path: Some("/".to_string()),
+            log_capacity: DEFAULT_LOG_CAPACITY,
+            env: HashMap::new(),
+            port: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PortSource {
+    /// `port_pattern` matched a line of output
+    OutputPattern,
+    /// Found among the process tree's listening sockets (Linux only)
+    ListeningSocket,
+    /// Fixed in the run configuration (`DevServerOptions.port`)
+    Configured,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DevServerStarted {
+    pub port: u16,
+    pub detected_by: PortSource,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DevServerExited {
+    pub exit_code: Option<i32>,
+    pub last_lines: Vec<String>,
+    pub will_restart: bool,
+    /// Which restart attempt comes next (1-based)
+    pub restart_attempt: u32,
+    pub restart_delay_ms: Option<u64>,
+    /// Why a restart attempt failed, when it didn't simply exit
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DevServerRestarted {
+    pub port: u16,
+    pub detected_by: PortSource,
+    pub attempt: u32,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DevServerDiagnostics {
+    /// Full current list — empty means earlier errors were resolved
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+/// A freshly spawned server process
+struct Launched {
+    /// Yields the port from the output, or an error if the process
+    /// exits without printing one
+    rx: std::sync::mpsc::Receiver<Result<u16, String>>,
+    pid: u32,
+    /// Set once the port is known so the readers stop matching lines
+    port_found: Arc<AtomicBool>,
+}
+
+/// Everything needed to (re)launch the same server
+struct LaunchConfig {
+    command: String,
+    cwd: PathBuf,
+    port_re: regex::Regex,
+    options: DevServerOptions,
+    /// Set once the server has served at least once this session
+    was_ready: AtomicBool,
+}
+
+struct DevServerState {
+    /// Bumped on every start/stop so threads of an old server can tell
+    /// they've been superseded (their exits are expected, not crashes)
+    generation: u64,
+    pid: Option<u32>,
+    /// Port the server is answering on, once ready
+    port: Option<u16>,
+}
+
+static DEV_SERVER: Mutex<DevServerState> = Mutex::new(DevServerState {
+    generation: 0,
+    pid: None,
+    port: None,
+});
+
+// All output from both streams, from the moment the process starts.
+// Its tail also goes out with `dev-server-exited` so the user can see why it died
+static DEV_SERVER_LOG: once_cell::sync::Lazy<Mutex<LogBuffer>> =
+    once_cell::sync::Lazy::new(|| Mutex::new(LogBuffer::new(DEFAULT_LOG_CAPACITY)));
+
+// Parses build errors out of the output; replaced on every start
+static DEV_SERVER_DIAGNOSTICS: once_cell::sync::Lazy<Mutex<Option<DiagnosticsTracker>>> =
+    once_cell::sync::Lazy::new(|| Mutex::new(None));
+
+fn is_current(generation: u64) -> bool {
+    DEV_SERVER.lock().unwrap_or_else(|e| e.into_inner()).generation == generation
+}
+
+// ── Start / Stop ──────────────────────────────────────────────
+
+/// Start a long-running dev server process (e.g. `npm run dev`).
+/// Returns the port once it's known and the server answers HTTP,
+/// or errors after a timeout. Blocks — call from a blocking task.
+pub fn start(
+    app_handle: &tauri::AppHandle,
+    command: String,
+    cwd: String,
+    port_pattern: String,
+    options: DevServerOptions,
+) -> Result<DevServerStarted, String> {
+    // Kill any existing dev server first
+    let generation = stop_internal();
+
+    let cwd_path = PathBuf::from(&cwd);
+    if !cwd_path.exists() || !cwd_path.is_dir() {
+        return Err(format!("Directory not found: {}", cwd));
+    }
+
+    // Compile the port pattern regex
+    let port_re = regex::Regex::new(&port_pattern)
+        .map_err(|e| format!("Invalid port pattern: {}", e))?;
+
+    DEV_SERVER_LOG
+        .lock()
+        .map_err(|e| e.to_string())?
+        .set_capacity(options.log_capacity);
+    *DEV_SERVER_DIAGNOSTICS.lock().map_err(|e| e.to_string())? =
+        Some(DiagnosticsTracker::new(&cwd_path));
+    spawn_diagnostics_watcher(app_handle, generation);
+
+    let config = Arc::new(LaunchConfig {
+        command,
+        cwd: cwd_path,
+        port_re,
+        options,
+        was_ready: AtomicBool::new(false),
+    });
+
+    let launched = launch(app_handle, config.clone(), generation, 0)?;
+
+    match wait_until_ready(&launched, &config) {
+        Ok(started) => {
+            config.was_ready.store(true, Ordering::Relaxed);
+            set_port(generation, started.port);
+            Ok(started)
+        }
+        Err(e) => {
+            stop_internal();
+            Err(e)
+        }
+    }
+}
+
+/// Kill the dev server process tree and clear buffers.
+/// Returns the new generation number.
+pub fn stop_internal() -> u64 {
+    let (pid, generation) = {
+        let mut state = DEV_SERVER.lock().unwrap_or_else(|e| e.into_inner());
+        state.generation += 1;
+        state.port = None;
+        (state.pid.take(), state.generation)
+    };
+
+    if let Some(pid) = pid {
+        crate::kill_process_tree(pid);
+    }
+
+    // Clear the output buffers
+    if let Ok(mut log) = DEV_SERVER_LOG.lock() {
+        log.clear();
+    }
+    if let Ok(mut tracker) = DEV_SERVER_DIAGNOSTICS.lock() {
+        *tracker = None;
+    }
+
+    generation
+}
+
+/// Port of the running dev server, once it's ready
+pub fn current_port() -> Option<u16> {
+    DEV_SERVER.lock().unwrap_or_else(|e| e.into_inner()).port
+}
+
+fn set_port(generation: u64, port: u16) {
+    let mut state = DEV_SERVER.lock().unwrap_or_else(|e| e.into_inner());
+    if state.generation == generation {
+        state.port = Some(port);
+    }
+}
+
+/// Read buffered dev server output newer than the caller's cursor.
+/// Reads don't consume anything — each consumer keeps its own cursor.
+pub fn read_output(options: &LogReadOptions) -> LogRead {
+    DEV_SERVER_LOG.lock().unwrap_or_else(|e| e.into_inner()).read(options)
+}
+
+/// Build diagnostics currently present in the dev server output
+pub fn diagnostics() -> Vec<Diagnostic> {
+    DEV_SERVER_DIAGNOSTICS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .as_ref()
+        .map(|tracker| tracker.current())
+        .unwrap_or_default()
+}
+
+// ── Process Launch ────────────────────────────────────────────
+
+/// Record a line of output and feed it to the diagnostics tracker
+fn push_line(stream: LogStream, line: &str) {
+    DEV_SERVER_LOG
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .push(stream, line);
+
+    if let Some(tracker) = DEV_SERVER_DIAGNOSTICS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .as_mut()
+    {
+        tracker.feed(&crate::strip_ansi_codes(line));
+    }
+}
+
+/// Re-parse diagnostics once output goes quiet and emit them when they
+/// change. Runs until the server is stopped or replaced.
+fn spawn_diagnostics_watcher(app_handle: &tauri::AppHandle, generation: u64) {
+    let handle = app_handle.clone();
+    std::thread::spawn(move || {
+        while is_current(generation) {
+            std::thread::sleep(Duration::from_millis(200));
+            let changed = DEV_SERVER_DIAGNOSTICS
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .as_mut()
+                .and_then(|tracker| tracker.flush());
+            if let Some(diagnostics) = changed {
+                if is_current(generation) {
+                    let _ = handle.emit("dev-server-diagnostics", DevServerDiagnostics { diagnostics });
+                }
+            }
+        }
+    });
+}
+
+/// Spawn the server and its reader/waiter threads.
+fn launch(
+    app_handle: &tauri::AppHandle,
+    config: Arc<LaunchConfig>,
+    generation: u64,
+    attempt: u32,
+) -> Result<Launched, String> {
+    use std::io::{BufRead, BufReader};
+    use std::process::Stdio;
+
+    // Spawn the dev server process with piped stdout and stderr
+    let mut child = {
+        let mut cmd = crate::build_hidden_shell_command(&config.command, &config.cwd);
+        cmd.envs(config.options.env.iter());
+        cmd.stdout(Stdio::piped()).stderr(Stdio::piped());
+        cmd.spawn()
+    }
+    .map_err(|e| format!("Failed to start dev server: {}", e))?;
+
+    // Store the PID so we can kill it later — unless we were stopped meanwhile
+    let pid = child.id();
+    crate::process_registry::track(pid, &config.command);
+    {
+        let mut state = DEV_SERVER.lock().map_err(|e| e.to_string())?;
+        if state.generation != generation {
+            drop(state);
+            crate::kill_process_tree(pid);
+            return Err("Dev server was stopped while starting".to_string());
+        }
+        state.pid = Some(pid);
+    }
+
+    // Take stdout and stderr handles
+    let stdout = child.stdout.take();
+    let stderr = child.stderr.take();
+
+    // Read both stdout and stderr in separate threads,
+    // looking for the port pattern in either stream.
+    // Dev servers vary — some print to stdout, some to stderr.
+    let (tx, rx) = std::sync::mpsc::channel::<Result<u16, String>>();
+
+    // Shared flag so both threads know when port has been found
+    let port_found = Arc::new(AtomicBool::new(false));
+
+    // Spawn stdout reader
+    if let Some(out) = stdout {
+        let re_clone = config.port_re.clone();
+        let tx_clone = tx.clone();
+        let port_found_clone = port_found.clone();
+        std::thread::spawn(move || {
+            let reader = BufReader::new(out);
+            for line in reader.lines().map_while(Result::ok) {
+                push_line(LogStream::Stdout, &line);
+
+                // Before port is found, check for port pattern
+                if !port_found_clone.load(Ordering::Relaxed) {
+                    let clean = crate::strip_ansi_codes(&line);
+                    if let Some(port) = match_port(&re_clone, &clean) {
+                        port_found_clone.store(true, Ordering::Relaxed);
+                        let _ = tx_clone.send(Ok(port));
+                    }
+                }
+            }
+            // Stream closed — if port was never found, report it
+            if !port_found_clone.load(Ordering::Relaxed) {
+                let _ = tx_clone.send(Err("Dev server stdout closed without printing a port".to_string()));
+            }
+        });
+    }
+
+    // Spawn stderr reader
+    if let Some(err_stream) = stderr {
+        let re_clone = config.port_re.clone();
+        let tx_clone = tx.clone();
+        let port_found_clone = port_found.clone();
+        std::thread::spawn(move || {
+            let reader = BufReader::new(err_stream);
+            let mut captured = String::new();
+            for line in reader.lines().map_while(Result::ok) {
+                push_line(LogStream::Stderr, &line);
+
+                // Before port is found, keep capturing for error reporting
+                if !port_found_clone.load(Ordering::Relaxed) {
+                    if captured.len() < 2000 {
+                        captured.push_str(&line);
+                        captured.push('\n');
+                    }
+                    if let Some(port) = match_port(&re_clone, &crate::strip_ansi_codes(&line)) {
+                        port_found_clone.store(true, Ordering::Relaxed);
+                        let _ = tx_clone.send(Ok(port));
+                    }
+                }
+            }
+            // Stream closed — if port was never found, report it
+            if !port_found_clone.load(Ordering::Relaxed) {
+                let _ = tx_clone.send(Err(format!(
+                    "Dev server exited without starting. Output:\n{}",
+                    if captured.is_empty() { "(no output)".to_string() } else { captured }
+                )));
+            }
+        });
+    }
+
+    // Waiter — notices when the process exits on its own
+    {
+        let handle = app_handle.clone();
+        let started = Instant::now();
+        std::thread::spawn(move || {
+            let exit_code = child.wait().ok().and_then(|s| s.code());
+            crate::process_registry::exited(pid);
+            on_exit(&handle, config, generation, attempt, started.elapsed(), exit_code);
+        });
+    }
+
+    Ok(Launched { rx, pid, port_found })
+}
+
+fn match_port(re: &regex::Regex, line: &str) -> Option<u16> {
+    re.captures(line)?.get(1)?.as_str().parse::<u16>().ok()
+}
+
+/// Wait for the port (printed, or found among listening sockets),
+/// then for the server to answer HTTP
+fn wait_until_ready(launched: &Launched, config: &LaunchConfig) -> Result<DevServerStarted, String> {
+    use std::sync::mpsc::RecvTimeoutError;
+
+    let started = Instant::now();
+    let (port, detected_by) = loop {
+        match launched.rx.recv_timeout(Duration::from_millis(500)) {
+            Ok(Ok(port)) => break (port, PortSource::OutputPattern),
+            Ok(Err(e)) => return Err(e),
+            Err(RecvTimeoutError::Disconnected) => {
+                return Err("Dev server exited without starting".to_string())
+            }
+            Err(RecvTimeoutError::Timeout) => {}
+        }
+
+        if let Some(port) = config.options.port {
+            if port_open(port) {
+                launched.port_found.store(true, Ordering::Relaxed);
+                break (port, PortSource::Configured);
+            }
+        }
+
+        if started.elapsed() >= SOCKET_SCAN_DELAY {
+            if let Some(port) = pick_listening_port(launched.pid) {
+                // The readers can stop looking for a port line
+                launched.port_found.store(true, Ordering::Relaxed);
+                break (port, PortSource::ListeningSocket);
+            }
+        }
+
+        if started.elapsed() >= PORT_TIMEOUT {
+            return Err("Dev server timed out after 30 seconds without printing a port or listening on one. Try running the command manually in the terminal.".to_string());
+        }
+    };
+
+    if let Some(ref path) = config.options.health_check_path {
+        if !wait_until_healthy(port, path, HEALTH_TIMEOUT) {
+            return Err(format!(
+                "Dev server reported port {} but isn't answering HTTP requests on it",
+                port
+            ));
+        }
+    }
+
+    Ok(DevServerStarted { port, detected_by })
+}
+
+/// Choose among the ports the server's process tree listens on.
+/// Servers often open extra ports (HMR websockets, debuggers), so prefer
+/// one that returns a 2xx/3xx page, then any HTTP answer, then the lowest.
+fn pick_listening_port(pid: u32) -> Option<u16> {
+    let ports = crate::port_discovery::listening_ports(pid);
+    if ports.len() <= 1 {
+        return ports.first().copied();
+    }
+
+    let probed: Vec<(u16, Option<u16>)> = ports.iter().map(|&p| (p, http_probe(p, "/"))).collect();
+    probed
+        .iter()
+        .find(|(_, status)| matches!(status, Some(code) if (200..400).contains(code)))
+        .or_else(|| probed.iter().find(|(_, status)| status.is_some()))
+        .map(|(port, _)| *port)
+        .or_else(|| ports.first().copied())
+}
+
+// ── Crash Handling ────────────────────────────────────────────
+
+fn backoff_delay(attempt: u32) -> Duration {
+    // 1s, 2s, 4s, 8s… capped at 30s
+    let delay = Duration::from_secs(1) * 2u32.saturating_pow(attempt.saturating_sub(1));
+    delay.min(MAX_BACKOFF)
+}
+
+/// Called by the waiter thread when the server process exits.
+/// Ignores exits we caused (stop/restart bump the generation).
+fn on_exit(
+    app_handle: &tauri::AppHandle,
+    config: Arc<LaunchConfig>,
+    generation: u64,
+    attempt: u32,
+    uptime: Duration,
+    exit_code: Option<i32>,
+) {
+    // A long healthy run earns a fresh set of restart attempts
+    let attempt = if uptime >= STABLE_UPTIME { 0 } else { attempt };
+    let next_attempt = attempt + 1;
+    let will_restart = config.options.auto_restart
+        && config.was_ready.load(Ordering::Relaxed)
+        && attempt < config.options.max_restarts;
+    let delay = backoff_delay(next_attempt);
+
+    {
+        let mut state = DEV_SERVER.lock().unwrap_or_else(|e| e.into_inner());
+        if state.generation != generation {
+            return;
+        }
+        state.pid = None;
+        if !will_restart {
+            state.port = None;
+        }
+    }
+
+    let last_lines = DEV_SERVER_LOG
+        .lock()
+        .map(|log| log.tail(TAIL_LINES))
+        .unwrap_or_default();
+
+    let _ = app_handle.emit(
+        "dev-server-exited",
+        DevServerExited {
+            exit_code,
+            last_lines,
+            will_restart,
+            restart_attempt: next_attempt,
+            restart_delay_ms: if will_restart { Some(delay.as_millis() as u64) } else { None },
+            error: None,
+        },
+    );
+
+    if !will_restart {
+        return;
+    }
+
+    // Sleep in small steps so a stop during backoff cancels the restart
+    let wake_at = Instant::now() + delay;
+    while Instant::now() < wake_at {
+        if !is_current(generation) {
+            return;
+        }
+        std::thread::sleep(Duration::from_millis(100));
+    }
+
+    let launched = match launch(app_handle, config.clone(), generation, next_attempt) {
+        Ok(launched) => launched,
+        Err(e) => return abandon_restart(app_handle, generation, None, next_attempt, e),
+    };
+
+    match wait_until_ready(&launched, &config) {
+        Ok(started) => {
+            if is_current(generation) {
+                set_port(generation, started.port);
+                let _ = app_handle.emit(
+                    "dev-server-restarted",
+                    DevServerRestarted {
+                        port: started.port,
+                        detected_by: started.detected_by,
+                        attempt: next_attempt,
+                    },
+                );
+            }
+        }
+        Err(e) => {
+            // If the new process died before it was ready, its own waiter
+            // takes over and schedules the next attempt. Give it a moment
+            // to notice — the output closes just before the exit is reaped.
+            let reaped_at = Instant::now() + Duration::from_secs(1);
+            while Instant::now() < reaped_at {
+                let state = DEV_SERVER.lock().unwrap_or_else(|e| e.into_inner());
+                if state.generation != generation || state.pid != Some(launched.pid) {
+                    return;
+                }
+                drop(state);
+                std::thread::sleep(Duration::from_millis(50));
+            }
+            // Still running but never became ready (timeout, failed health check)
+            abandon_restart(app_handle, generation, Some(launched.pid), next_attempt, e);
+        }
+    }
+}
+
+/// Give up on a restart that failed without the process exiting: kill
+/// what was launched and report the server as stopped for good
+fn abandon_restart(app_handle: &tauri::AppHandle, generation: u64, pid: Option<u32>, attempt: u32, error: String) {
+    {
+        let mut state = DEV_SERVER.lock().unwrap_or_else(|e| e.into_inner());
+        if state.generation != generation {
+            return;
+        }
+        // Supersede the process so its waiter treats the kill as expected
+        state.generation += 1;
+        state.pid = None;
+        state.port = None;
+    }
+    if let Some(pid) = pid {
+        crate::kill_process_tree(pid);
+    }
+
+    let last_lines = DEV_SERVER_LOG
+        .lock()
+        .map(|log| log.tail(TAIL_LINES))
+        .unwrap_or_default();
+    let _ = app_handle.emit(
+        "dev-server-exited",
+        DevServerExited {
+            exit_code: None,
+            last_lines,
+            will_restart: false,
+            restart_attempt: attempt,
+            restart_delay_ms: None,
+            error: Some(error),
+        },
+    );
+}
+
+// ── HTTP Health Check ─────────────────────────────────────────
+
+/// Poll until the server answers any HTTP response (even 404/500 means
+/// it's up — the page itself may just have a build error).
+fn wait_until_healthy(port: u16, path: &str, timeout: Duration) -> bool {
+    let deadline = Instant::now() + timeout;
+    while Instant::now() < deadline {
+        if http_probe(port, path).is_some() {
+            return true;
+        }
+        std::thread::sleep(Duration::from_millis(250));
+    }
+    false
+}
+
+/// Whether anything accepts TCP connections on localhost:`port`
+pub fn port_open(port: u16) -> bool {
+    use std::net::{TcpStream, ToSocketAddrs};
+
+    match ("localhost", port).to_socket_addrs() {
+        Ok(addrs) => addrs
+            .into_iter()
+            .any(|addr| TcpStream::connect_timeout(&addr, Duration::from_millis(300)).is_ok()),
+        Err(_) => false,
+    }
+}
+
+/// Minimal HTTP/1.1 GET against localhost. Returns the status code.
+/// Tries every address `localhost` resolves to, since Node 17+ dev
+/// servers often bind to ::1 only.
+pub fn http_probe(port: u16, path: &str) -> Option<u16> {
+    use std::io::{Read, Write};
+    use std::net::{TcpStream, ToSocketAddrs};
+
+    let path = if path.starts_with('/') { path.to_string() } else { format!("/{}", path) };
+    let addrs = ("localhost", port).to_socket_addrs().ok()?;
+
+    for addr in addrs {
+        let mut stream = match TcpStream::connect_timeout(&addr, Duration::from_millis(500)) {
+            Ok(s) => s,
+            Err(_) => continue,
+        };
+        let _ = stream.set_read_timeout(Some(Duration::from_secs(5)));
+        let _ = stream.set_write_timeout(Some(Duration::from_secs(2)));
+
+        let request = format!(
+            "GET {} HTTP/1.1\r\nHost: localhost:{}\r\nUser-Agent: mydevify-health-check\r\nConnection: close\r\n\r\n",
+            path, port
+        );
+        if stream.write_all(request.as_bytes()).is_err() {
+            continue;
+        }
+
+        // Only the status line matters: "HTTP/1.1 200 OK"
+        let mut buf = [0u8; 64];
+        let n = match stream.read(&mut buf) {
+            Ok(n) if n > 0 => n,
+            _ => continue,
+        };
+        let head = String::from_utf8_lossy(&buf[..n]).to_string();
+
+        // Drain the rest so the server doesn't log a connection reset
+        let mut sink = [0u8; 8192];
+        let mut drained = 0usize;
+        while drained < 1_000_000 {
+            match stream.read(&mut sink) {
+                Ok(0) | Err(_) => break,
+                Ok(m) => drained += m,
+            }
+        }
+
+        if let Some(code) = head
+            .strip_prefix("HTTP/")
+            .and_then(|rest| rest.split_whitespace().nth(1))
+            .and_then(|code| code.parse::<u16>().ok())
+        {
+            return Some(code);
+        }
+    }
+
+    None
+}
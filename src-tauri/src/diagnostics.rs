@@ -0,0 +1,766 @@
+// ── Build Diagnostics — Dev Server Output Parser ──────────────
+//
+// Turns raw dev server output into structured diagnostics so the AI
+// doesn't have to eyeball logs. Recognises Vite, Next.js, webpack, tsc,
+// esbuild, ESLint and Python tracebacks.
+//
+// The tracker keeps the lines of the current build cycle. Success
+// markers ("compiled successfully", "hmr update", "Found 0 errors")
+// clear them, rebuild markers start a new cycle, and the window is
+// re-parsed after output goes quiet. Results are deduplicated.
+
+use regex::Regex;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+/// Lines kept for the current build cycle
+const MAX_WINDOW_LINES: usize = 400;
+/// Re-parse once output has been quiet this long
+pub const QUIET_PERIOD: Duration = Duration::from_millis(400);
+/// Don't report an empty list mid-rebuild unless it's been this long
+const REBUILD_GRACE: Duration = Duration::from_secs(3);
+
+// ── Data Model ────────────────────────────────────────────────
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Tool {
+    Vite,
+    NextJs,
+    Webpack,
+    Typescript,
+    Esbuild,
+    Eslint,
+    Python,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub tool: Tool,
+    /// Relative to the project root when possible
+    pub file: Option<String>,
+    pub line: Option<u32>,
+    pub column: Option<u32>,
+    pub message: String,
+    /// Source excerpt the tool printed around the error
+    pub code_frame: Option<String>,
+}
+
+// ── Patterns ──────────────────────────────────────────────────
+
+struct Patterns {
+    tsc_classic: Regex,
+    tsc_pretty: Regex,
+    tsc_summary: Regex,
+    esbuild_header: Regex,
+    esbuild_location: Regex,
+    vite_error: Regex,
+    vite_file: Regex,
+    vite_import_from: Regex,
+    path_with_location: Regex,
+    webpack_header: Regex,
+    babel_location: Regex,
+    eslint_row: Regex,
+    eslint_cra_row: Regex,
+    eslint_file_header: Regex,
+    python_frame: Regex,
+    python_exception: Regex,
+    code_frame: Regex,
+    success: Regex,
+    rebuild: Regex,
+}
+
+static PATTERNS: once_cell::sync::Lazy<Patterns> = once_cell::sync::Lazy::new(|| Patterns {
+    // src/app.ts(12,5): error TS2322: Type 'string' is not assignable…
+    tsc_classic: Regex::new(r"^(\S.*?)\((\d+),(\d+)\): (error|warning) (TS\d+): (.+)$").unwrap(),
+    // src/app.ts:12:5 - error TS2322: Type 'string' is not assignable…
+    tsc_pretty: Regex::new(r"^(\S.*?):(\d+):(\d+) - (error|warning) (TS\d+): (.+)$").unwrap(),
+    // Found 3 errors. Watching for file changes.
+    tsc_summary: Regex::new(r"Found (\d+) errors?\b").unwrap(),
+    // ✘ [ERROR] Could not resolve "foo"      ▲ [WARNING] …
+    esbuild_header: Regex::new(r"^\s*[✘▲X!]?\s*\[(ERROR|WARNING)\] (.+)$").unwrap(),
+    //     src/main.ts:3:7:
+    esbuild_location: Regex::new(r"^\s+(\S+?):(\d+):(\d+):\s*$").unwrap(),
+    // [vite] Internal server error: …   [plugin:vite:import-analysis] …   Pre-transform error: …
+    vite_error: Regex::new(
+        r"(?:\[vite\] (?:Internal server error|Pre-transform error): |\[plugin:[^\]]+\] |error when starting dev server:?\s*)(.*)$",
+    )
+    .unwrap(),
+    //   File: /abs/src/App.tsx:12:5
+    vite_file: Regex::new(r"^\s*File: (\S+?)(?::(\d+)(?::(\d+))?)?\s*$").unwrap(),
+    // Failed to resolve import "x" from "src/App.tsx"
+    vite_import_from: Regex::new(r#"from "([^"]+)""#).unwrap(),
+    // ./src/app/page.tsx:12:5   or   ⨯ ./app/page.tsx:5:1   (Next.js / CRA)
+    path_with_location: Regex::new(r"^\s*(?:⨯\s*)?(\.{1,2}/\S+?):(\d+):(\d+)\s*$").unwrap(),
+    // ERROR in ./src/index.js 5:0-20
+    webpack_header: Regex::new(r"^\s*(ERROR|WARNING) in (\S+)(?: (\d+):(\d+)(?:-\d+)?)?").unwrap(),
+    // SyntaxError: /abs/src/App.js: Unexpected token (5:2)
+    babel_location: Regex::new(r"^(\w*Error): (\S+?): (.+) \((\d+):(\d+)\)\s*$").unwrap(),
+    //   12:5  error  'x' is not defined  no-undef
+    eslint_row: Regex::new(r"^\s+(\d+):(\d+)\s+(error|warning)\s+(.+?)(?:\s{2,}(\S+))?\s*$").unwrap(),
+    //   Line 5:7:  'x' is assigned a value but never used  no-unused-vars
+    eslint_cra_row: Regex::new(r"^\s+Line (\d+):(\d+):\s+(.+?)(?:\s{2,}(\S+))?\s*$").unwrap(),
+    // /abs/path/src/App.js   or   src/App.js  (a bare path on its own line)
+    eslint_file_header: Regex::new(r"^(?:\./)?[\w./\\:-]+\.(?:[cm]?[jt]sx?|vue|svelte|astro)\s*$").unwrap(),
+    //   File "/app/views.py", line 12, in index
+    python_frame: Regex::new(r#"^\s*File "(.+?)", line (\d+)(?:, in .+)?$"#).unwrap(),
+    // ValueError: bad value
+    python_exception: Regex::new(r"^([A-Za-z_][\w.]*(?:Error|Exception|Warning|Interrupt|Exit))(?::\s*(.*))?$").unwrap(),
+    //  > 12 | const x = ;     or     3 │ import x     or       |     ^
+    code_frame: Regex::new(r"^\s*>?\s*(?:\d+\s*)?[|│╵]").unwrap(),
+    success: Regex::new(
+        r"(?i)compiled successfully|\bhmr update\b|\bpage reload\b|✓ (?:compiled|ready)|\bFound 0 errors\b|identified no issues",
+    )
+    .unwrap(),
+    rebuild: Regex::new(
+        r"(?i)\bcompiling\b|file change detected|starting compilation|\[watch\] build started|watching for file changes with StatReloader|detected change in",
+    )
+    .unwrap(),
+});
+
+// ── Parsing ───────────────────────────────────────────────────
+
+fn severity_from(s: &str) -> Severity {
+    if s.eq_ignore_ascii_case("warning") {
+        Severity::Warning
+    } else {
+        Severity::Error
+    }
+}
+
+/// Collect code-frame lines starting at `start`. Returns (frame, lines consumed).
+fn take_code_frame(lines: &[String], start: usize) -> (Option<String>, usize) {
+    let mut frame = Vec::new();
+    let mut i = start;
+    while i < lines.len() && frame.len() < 12 {
+        let line = &lines[i];
+        if PATTERNS.code_frame.is_match(line) {
+            frame.push(line.trim_end().to_string());
+            i += 1;
+        } else if line.trim().is_empty() && !frame.is_empty() {
+            break;
+        } else if frame.is_empty() && line.trim().is_empty() {
+            i += 1;
+        } else {
+            break;
+        }
+    }
+    if frame.is_empty() {
+        (None, 0)
+    } else {
+        (Some(frame.join("\n")), i - start)
+    }
+}
+
+fn num(s: Option<regex::Match>) -> Option<u32> {
+    s.and_then(|m| m.as_str().parse().ok())
+}
+
+fn diag(
+    severity: Severity,
+    tool: Tool,
+    file: Option<&str>,
+    line: Option<u32>,
+    column: Option<u32>,
+    message: &str,
+) -> Diagnostic {
+    Diagnostic {
+        severity,
+        tool,
+        file: file.map(|f| f.to_string()),
+        line,
+        column,
+        message: message.trim().to_string(),
+        code_frame: None,
+    }
+}
+
+/// Try every parser at `lines[i]`. Returns the diagnostic and how many
+/// lines it spans, so the caller can skip them.
+fn parse_at(lines: &[String], i: usize, eslint_file: &mut Option<String>) -> Option<(Diagnostic, usize)> {
+    let p = &*PATTERNS;
+    let line = lines[i].as_str();
+    let next_text = |from: usize| -> Option<(usize, &str)> {
+        lines[from..]
+            .iter()
+            .enumerate()
+            .take(3)
+            .find(|(_, l)| !l.trim().is_empty())
+            .map(|(off, l)| (from + off, l.as_str()))
+    };
+
+    // TypeScript (tsc)
+    if let Some(c) = p.tsc_pretty.captures(line).or_else(|| p.tsc_classic.captures(line)) {
+        let mut d = diag(
+            severity_from(&c[4]),
+            Tool::Typescript,
+            Some(&c[1]),
+            num(c.get(2)),
+            num(c.get(3)),
+            &format!("{} {}", &c[5], &c[6]),
+        );
+        let (frame, used) = take_code_frame(lines, i + 1);
+        d.code_frame = frame;
+        return Some((d, 1 + used));
+    }
+
+    // esbuild
+    if let Some(c) = p.esbuild_header.captures(line) {
+        let mut d = diag(severity_from(&c[1]), Tool::Esbuild, None, None, None, &c[2]);
+        let mut used = 1;
+        if let Some((loc_idx, loc_line)) = next_text(i + 1) {
+            if let Some(loc) = p.esbuild_location.captures(loc_line) {
+                d.file = Some(loc[1].to_string());
+                d.line = num(loc.get(2));
+                d.column = num(loc.get(3));
+                let (frame, frame_used) = take_code_frame(lines, loc_idx + 1);
+                d.code_frame = frame;
+                used = loc_idx + 1 + frame_used - i;
+            }
+        }
+        return Some((d, used));
+    }
+
+    // Vite
+    if let Some(c) = p.vite_error.captures(line) {
+        let mut d = diag(Severity::Error, Tool::Vite, None, None, None, &c[1]);
+        let mut j = i + 1;
+        // Following indented lines carry "Plugin:", "File:" and the code frame
+        while j < lines.len() && j < i + 20 {
+            if let Some(f) = p.vite_file.captures(&lines[j]) {
+                d.file = Some(f[1].to_string());
+                d.line = num(f.get(2));
+                d.column = num(f.get(3));
+                j += 1;
+            } else if PATTERNS.code_frame.is_match(&lines[j]) {
+                let (frame, used) = take_code_frame(lines, j);
+                d.code_frame = frame;
+                j += used.max(1);
+            } else if lines[j].starts_with("  ") && !lines[j].trim().is_empty() {
+                j += 1;
+            } else {
+                break;
+            }
+        }
+        // "Failed to resolve import "x" from "src/App.tsx"" names the file inline
+        if d.file.is_none() {
+            if let Some(from) = p.vite_import_from.captures(&d.message) {
+                d.file = Some(from[1].to_string());
+            }
+        }
+        return Some((d, j - i));
+    }
+
+    // webpack (also Next.js/CRA under the hood)
+    if let Some(c) = p.webpack_header.captures(line) {
+        let mut d = diag(
+            severity_from(&c[1]),
+            Tool::Webpack,
+            Some(&c[2]),
+            num(c.get(3)),
+            num(c.get(4)),
+            "",
+        );
+        let mut j = i + 1;
+        let mut message_lines = Vec::new();
+        while j < lines.len() && !lines[j].trim().is_empty() && message_lines.len() < 6 {
+            if PATTERNS.code_frame.is_match(&lines[j]) {
+                break;
+            }
+            if let Some(b) = p.babel_location.captures(&lines[j]) {
+                d.line = num(b.get(4));
+                d.column = num(b.get(5));
+                message_lines.push(format!("{}: {}", &b[1], &b[3]));
+            } else if !lines[j].trim_start().starts_with("Module build failed") {
+                message_lines.push(lines[j].trim().to_string());
+            }
+            j += 1;
+        }
+        d.message = message_lines.join("\n");
+        let (frame, used) = take_code_frame(lines, j);
+        d.code_frame = frame;
+        return Some((d, j - i + used));
+    }
+
+    // Next.js / CRA: "./src/page.tsx:12:5" then the message on the next line
+    if let Some(c) = p.path_with_location.captures(line) {
+        if let Some((msg_idx, msg)) = next_text(i + 1) {
+            let mut d = diag(
+                Severity::Error,
+                Tool::NextJs,
+                Some(&c[1]),
+                num(c.get(2)),
+                num(c.get(3)),
+                msg,
+            );
+            let (frame, used) = take_code_frame(lines, msg_idx + 1);
+            d.code_frame = frame;
+            return Some((d, msg_idx + 1 + used - i));
+        }
+    }
+
+    // ESLint — rows belong to the most recent file header line
+    if p.eslint_file_header.is_match(line) {
+        *eslint_file = Some(line.trim().to_string());
+        return None;
+    }
+    if let Some(c) = p.eslint_row.captures(line) {
+        if eslint_file.is_some() {
+            let mut message = c[4].to_string();
+            if let Some(rule) = c.get(5) {
+                message = format!("{} ({})", message, rule.as_str());
+            }
+            let d = diag(
+                severity_from(&c[3]),
+                Tool::Eslint,
+                eslint_file.as_deref(),
+                num(c.get(1)),
+                num(c.get(2)),
+                &message,
+            );
+            return Some((d, 1));
+        }
+    }
+    if let Some(c) = p.eslint_cra_row.captures(line) {
+        let mut message = c[3].to_string();
+        if let Some(rule) = c.get(4) {
+            message = format!("{} ({})", message, rule.as_str());
+        }
+        // CRA prints lint problems under "Compiled with warnings" unless the build failed
+        let d = diag(
+            Severity::Warning,
+            Tool::Eslint,
+            eslint_file.as_deref(),
+            num(c.get(1)),
+            num(c.get(2)),
+            &message,
+        );
+        return Some((d, 1));
+    }
+
+    // Python traceback — report the innermost frame and the exception line
+    if line.trim_start().starts_with("Traceback (most recent call last)") || is_python_syntax_error(lines, i) {
+        let mut j = if line.trim_start().starts_with("Traceback") { i + 1 } else { i };
+        let mut frame: Option<(String, u32)> = None;
+        let mut code = Vec::new();
+        while j < lines.len() && j < i + 200 {
+            let l = &lines[j];
+            if let Some(f) = p.python_frame.captures(l) {
+                frame = Some((f[1].to_string(), f[2].parse().unwrap_or(0)));
+                code.clear();
+                j += 1;
+            } else if l.starts_with(' ') || l.trim().is_empty() {
+                if !l.trim().is_empty() {
+                    code.push(l.trim_end().to_string());
+                }
+                j += 1;
+            } else {
+                break;
+            }
+        }
+        let exception = lines.get(j).map(|l| l.trim()).unwrap_or("");
+        if p.python_exception.is_match(exception) {
+            let mut d = diag(
+                Severity::Error,
+                Tool::Python,
+                frame.as_ref().map(|(f, _)| f.as_str()),
+                frame.as_ref().map(|(_, l)| *l),
+                None,
+                exception,
+            );
+            if !code.is_empty() {
+                d.code_frame = Some(code.join("\n"));
+            }
+            return Some((d, j + 1 - i));
+        }
+    }
+
+    None
+}
+
+/// SyntaxErrors print a bare `File "x.py", line 3` frame (no "Traceback"
+/// header) followed by the source line, a caret and the exception
+fn is_python_syntax_error(lines: &[String], i: usize) -> bool {
+    PATTERNS.python_frame.is_match(&lines[i])
+        && lines[i + 1..]
+            .iter()
+            .take(3)
+            .any(|l| l.trim().starts_with("SyntaxError") || l.trim() == "^")
+}
+
+/// Parse a block of (ANSI-stripped) output into deduplicated diagnostics.
+pub fn parse(lines: &[String]) -> Vec<Diagnostic> {
+    let mut found: Vec<Diagnostic> = Vec::new();
+    let mut eslint_file: Option<String> = None;
+    let mut i = 0;
+
+    while i < lines.len() {
+        match parse_at(lines, i, &mut eslint_file) {
+            Some((d, used)) => {
+                let duplicate = found.iter().any(|f| {
+                    f.file == d.file && f.line == d.line && f.column == d.column && f.message == d.message
+                });
+                if !duplicate {
+                    found.push(d);
+                }
+                i += used.max(1);
+            }
+            None => i += 1,
+        }
+    }
+
+    found
+}
+
+// ── Tracker ───────────────────────────────────────────────────
+
+/// Follows one dev server's output across build cycles.
+pub struct DiagnosticsTracker {
+    root: PathBuf,
+    window: Vec<String>,
+    current: Vec<Diagnostic>,
+    last_line_at: Option<Instant>,
+    rebuilding_since: Option<Instant>,
+    dirty: bool,
+}
+
+impl DiagnosticsTracker {
+    pub fn new(root: &Path) -> Self {
+        Self {
+            root: root.to_path_buf(),
+            window: Vec::new(),
+            current: Vec::new(),
+            last_line_at: None,
+            rebuilding_since: None,
+            dirty: false,
+        }
+    }
+
+    /// Feed one line of output (ANSI codes already stripped)
+    pub fn feed(&mut self, line: &str) {
+        let p = &*PATTERNS;
+        self.last_line_at = Some(Instant::now());
+        self.dirty = true;
+
+        // tsc reports "Found N errors" at the end of each cycle;
+        // N = 0 is handled by the success pattern
+        let tsc_done = p.tsc_summary.is_match(line);
+
+        if p.success.is_match(line) {
+            self.window.clear();
+            self.rebuilding_since = None;
+            return;
+        }
+        if p.rebuild.is_match(line) && !tsc_done {
+            self.window.clear();
+            self.rebuilding_since = Some(Instant::now());
+            return;
+        }
+        // End of a cycle that had problems — the window is complete
+        if tsc_done
+            || line.contains("Failed to compile")
+            || line.contains("Compiled with")
+            || line.contains("build finished")
+        {
+            self.rebuilding_since = None;
+        }
+
+        if self.window.len() >= MAX_WINDOW_LINES {
+            self.window.remove(0);
+        }
+        self.window.push(line.to_string());
+    }
+
+    /// Re-parse if output has gone quiet. Returns the new list when it
+    /// changed (including becoming empty — the errors were resolved).
+    pub fn flush(&mut self) -> Option<Vec<Diagnostic>> {
+        if !self.dirty {
+            return None;
+        }
+        if let Some(at) = self.last_line_at {
+            if at.elapsed() < QUIET_PERIOD {
+                return None;
+            }
+        }
+        // Mid-rebuild the window is partial — wait for the build to finish
+        if let Some(since) = self.rebuilding_since {
+            if since.elapsed() < REBUILD_GRACE {
+                return None;
+            }
+        }
+        self.dirty = false;
+
+        let mut parsed = parse(&self.window);
+        for d in &mut parsed {
+            d.file = d.file.take().map(|f| self.relativize(&f));
+        }
+
+        if parsed == self.current {
+            return None;
+        }
+        self.current = parsed.clone();
+        Some(parsed)
+    }
+
+    pub fn current(&self) -> Vec<Diagnostic> {
+        self.current.clone()
+    }
+
+    /// Show paths relative to the project root where possible
+    fn relativize(&self, file: &str) -> String {
+        let trimmed = file.trim_start_matches("./");
+        let path = Path::new(trimmed);
+        if path.is_absolute() {
+            if let Ok(rel) = path.strip_prefix(&self.root) {
+                return rel.to_string_lossy().replace('\\', "/");
+            }
+        }
+        trimmed.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lines(output: &str) -> Vec<String> {
+        output.lines().map(str::to_string).collect()
+    }
+
+    fn parse_one(output: &str) -> Diagnostic {
+        let found = parse(&lines(output));
+        assert_eq!(found.len(), 1, "{:#?}", found);
+        found.into_iter().next().unwrap()
+    }
+
+    /// Feed output and re-parse as if it had gone quiet
+    fn settle(tracker: &mut DiagnosticsTracker, output: &str) -> Option<Vec<Diagnostic>> {
+        for line in output.lines() {
+            tracker.feed(line);
+        }
+        tracker.last_line_at = None;
+        tracker.flush()
+    }
+
+    #[test]
+    fn tsc_pretty() {
+        let d = parse_one(
+            "src/app.ts:12:5 - error TS2322: Type 'string' is not assignable to type 'number'.\n\
+             \n\
+             12     count = \"x\";\n\
+             \x20      ~~~~~\n\
+             \n\
+             Found 1 error. Watching for file changes.",
+        );
+        assert_eq!(d.tool, Tool::Typescript);
+        assert_eq!(d.severity, Severity::Error);
+        assert_eq!(d.file.as_deref(), Some("src/app.ts"));
+        assert_eq!((d.line, d.column), (Some(12), Some(5)));
+        assert_eq!(d.message, "TS2322 Type 'string' is not assignable to type 'number'.");
+    }
+
+    #[test]
+    fn tsc_classic() {
+        let d = parse_one("src/util.ts(3,10): warning TS6133: 'unused' is declared but its value is never read.");
+        assert_eq!(d.tool, Tool::Typescript);
+        assert_eq!(d.severity, Severity::Warning);
+        assert_eq!(d.file.as_deref(), Some("src/util.ts"));
+        assert_eq!((d.line, d.column), (Some(3), Some(10)));
+        assert_eq!(d.message, "TS6133 'unused' is declared but its value is never read.");
+    }
+
+    #[test]
+    fn esbuild() {
+        let d = parse_one(
+            "✘ [ERROR] Could not resolve \"lodash\"\n\
+             \n\
+             \x20   src/main.ts:3:14:\n\
+             \x20     3 │ import _ from \"lodash\";\n\
+             \x20       ╵               ~~~~~~~~\n\
+             \n\
+             \x20 You can mark the path \"lodash\" as external to exclude it from the bundle.",
+        );
+        assert_eq!(d.tool, Tool::Esbuild);
+        assert_eq!(d.file.as_deref(), Some("src/main.ts"));
+        assert_eq!((d.line, d.column), (Some(3), Some(14)));
+        assert_eq!(d.message, "Could not resolve \"lodash\"");
+        assert_eq!(d.code_frame.as_deref().map(|f| f.lines().count()), Some(2));
+    }
+
+    #[test]
+    fn vite() {
+        let d = parse_one(
+            "12:01:33 [vite] Internal server error: Failed to resolve import \"./Missing\" from \"src/App.tsx\". Does the file exist?\n\
+             \x20 Plugin: vite:import-analysis\n\
+             \x20 File: /home/dev/site/src/App.tsx:3:20\n\
+             \x20 1  |  import React from \"react\";\n\
+             \x20 2  |\n\
+             \x20 3  |  import Missing from \"./Missing\";\n\
+             \x20    |                      ^",
+        );
+        assert_eq!(d.tool, Tool::Vite);
+        assert_eq!(d.file.as_deref(), Some("/home/dev/site/src/App.tsx"));
+        assert_eq!((d.line, d.column), (Some(3), Some(20)));
+        assert!(d.message.starts_with("Failed to resolve import \"./Missing\""));
+        assert_eq!(d.code_frame.as_deref().map(|f| f.lines().count()), Some(4));
+    }
+
+    #[test]
+    fn vite_file_from_import_message() {
+        let d = parse_one("[plugin:vite:import-analysis] Failed to resolve import \"./x\" from \"src/main.ts\"");
+        assert_eq!(d.file.as_deref(), Some("src/main.ts"));
+        assert_eq!(d.line, None);
+    }
+
+    #[test]
+    fn webpack_babel() {
+        let d = parse_one(
+            "ERROR in ./src/index.js\n\
+             Module build failed (from ./node_modules/babel-loader/lib/index.js):\n\
+             SyntaxError: /home/dev/app/src/index.js: Unexpected token (5:2)\n\
+             \n\
+             \x20 3 | function App() {\n\
+             \x20 4 |   return (\n\
+             > 5 |   <div>\n\
+             \x20   |   ^",
+        );
+        assert_eq!(d.tool, Tool::Webpack);
+        assert_eq!(d.file.as_deref(), Some("./src/index.js"));
+        assert_eq!((d.line, d.column), (Some(5), Some(2)));
+        assert_eq!(d.message, "SyntaxError: Unexpected token");
+        assert_eq!(d.code_frame.as_deref().map(|f| f.lines().count()), Some(4));
+    }
+
+    #[test]
+    fn next_js() {
+        let d = parse_one(
+            " ⨯ ./app/page.tsx:5:1\n\
+             Module not found: Can't resolve './missing'\n\
+             \x20 3 | import Link from 'next/link'\n\
+             > 5 | import Missing from './missing'",
+        );
+        assert_eq!(d.tool, Tool::NextJs);
+        assert_eq!(d.file.as_deref(), Some("./app/page.tsx"));
+        assert_eq!((d.line, d.column), (Some(5), Some(1)));
+        assert_eq!(d.message, "Module not found: Can't resolve './missing'");
+        assert!(d.code_frame.is_some());
+    }
+
+    #[test]
+    fn eslint_stylish() {
+        let found = parse(&lines(
+            "/home/dev/app/src/App.js\n\
+             \x20 12:5  error    'x' is not defined            no-undef\n\
+             \x20 14:1  warning  Unexpected console statement  no-console\n\
+             \n\
+             ✖ 2 problems (1 error, 1 warning)",
+        ));
+        assert_eq!(found.len(), 2, "{:#?}", found);
+        assert_eq!(found[0].tool, Tool::Eslint);
+        assert_eq!(found[0].file.as_deref(), Some("/home/dev/app/src/App.js"));
+        assert_eq!((found[0].line, found[0].column), (Some(12), Some(5)));
+        assert_eq!(found[0].message, "'x' is not defined (no-undef)");
+        assert_eq!(found[1].severity, Severity::Warning);
+        assert_eq!(found[1].message, "Unexpected console statement (no-console)");
+    }
+
+    #[test]
+    fn eslint_create_react_app() {
+        let d = parse_one(
+            "Compiled with warnings.\n\
+             \n\
+             src/App.js\n\
+             \x20 Line 5:7:  'unused' is assigned a value but never used  no-unused-vars\n\
+             \n\
+             Search for the keywords to learn more about each warning.",
+        );
+        assert_eq!(d.tool, Tool::Eslint);
+        assert_eq!(d.severity, Severity::Warning);
+        assert_eq!(d.file.as_deref(), Some("src/App.js"));
+        assert_eq!((d.line, d.column), (Some(5), Some(7)));
+        assert_eq!(d.message, "'unused' is assigned a value but never used (no-unused-vars)");
+    }
+
+    #[test]
+    fn python_traceback_reports_innermost_frame() {
+        let d = parse_one(
+            "Traceback (most recent call last):\n\
+             \x20 File \"/app/manage.py\", line 22, in <module>\n\
+             \x20   main()\n\
+             \x20 File \"/app/views.py\", line 12, in index\n\
+             \x20   return int(value)\n\
+             ValueError: invalid literal for int() with base 10: 'x'",
+        );
+        assert_eq!(d.tool, Tool::Python);
+        assert_eq!(d.file.as_deref(), Some("/app/views.py"));
+        assert_eq!(d.line, Some(12));
+        assert_eq!(d.message, "ValueError: invalid literal for int() with base 10: 'x'");
+        assert_eq!(d.code_frame.as_deref(), Some("    return int(value)"));
+    }
+
+    #[test]
+    fn python_syntax_error() {
+        let d = parse_one(
+            "  File \"/app/views.py\", line 3\n\
+             \x20   def index(\n\
+             \x20            ^\n\
+             SyntaxError: '(' was never closed",
+        );
+        assert_eq!(d.tool, Tool::Python);
+        assert_eq!(d.file.as_deref(), Some("/app/views.py"));
+        assert_eq!(d.line, Some(3));
+        assert_eq!(d.message, "SyntaxError: '(' was never closed");
+    }
+
+    #[test]
+    fn repeated_errors_are_reported_once() {
+        let found = parse(&lines(
+            "src/a.ts(1,1): error TS1005: ';' expected.\n\
+             src/a.ts(1,1): error TS1005: ';' expected.",
+        ));
+        assert_eq!(found.len(), 1);
+    }
+
+    #[test]
+    fn tracker_clears_resolved_errors() {
+        let mut tracker = DiagnosticsTracker::new(Path::new("/home/dev/site"));
+        let reported = settle(
+            &mut tracker,
+            "[vite] Internal server error: Transform failed with 1 error\n\
+             \x20 File: /home/dev/site/src/App.tsx:4:2",
+        )
+        .unwrap();
+        assert_eq!(reported.len(), 1);
+        assert_eq!(reported[0].file.as_deref(), Some("src/App.tsx"));
+
+        // Unchanged output isn't reported again
+        assert_eq!(settle(&mut tracker, ""), None);
+        tracker.dirty = true;
+        assert_eq!(settle(&mut tracker, ""), None);
+
+        assert_eq!(settle(&mut tracker, "12:02:10 [vite] hmr update /src/App.tsx"), Some(Vec::new()));
+        assert!(tracker.current().is_empty());
+    }
+
+    #[test]
+    fn tracker_waits_for_rebuild_to_finish() {
+        let mut tracker = DiagnosticsTracker::new(Path::new("/proj"));
+        let first = "src/a.ts(1,1): error TS1005: ';' expected.\nFound 1 error. Watching for file changes.";
+        assert_eq!(settle(&mut tracker, first).map(|d| d.len()), Some(1));
+
+        // Mid-rebuild the old errors stay until the cycle ends
+        assert_eq!(settle(&mut tracker, "File change detected. Starting incremental compilation..."), None);
+        assert_eq!(tracker.current().len(), 1);
+
+        assert_eq!(settle(&mut tracker, "Found 0 errors. Watching for file changes."), Some(Vec::new()));
+    }
+}
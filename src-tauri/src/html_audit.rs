@@ -0,0 +1,772 @@
+// ── HTML Audit — Offline Page Quality Checks ──────────────────
+//
+// Parses every HTML file under the project root and reports problems in
+// three categories, each with a rule id and a file/line the AI can act on:
+//
+//   - accessibility: alt text, form labels, heading order, empty links
+//     and buttons, `lang`, frame titles, contrast of inline styles
+//   - seo: title, meta description, canonical, Open Graph, viewport
+//   - validity: doctype, charset, duplicate ids, unclosed or stray tags,
+//     obsolete elements
+//
+// Each page gets a 0–100 score from its issues (errors cost more than
+// warnings, notices barely count); the project score is the average.
+// Nothing is fetched — contrast is only checked where one element sets
+// both its text and background colour inline.
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Stops runaway audits of generated sites
+const MAX_FILES: usize = 2_000;
+const SKIPPED_DIRS: &[&str] = &["node_modules", ".git", ".mydevify"];
+/// Score lost per issue
+const ERROR_COST: u32 = 10;
+const WARNING_COST: u32 = 4;
+const NOTICE_COST: u32 = 1;
+/// WCAG AA for normal text
+const MIN_CONTRAST: f64 = 4.5;
+const MAX_TITLE_CHARS: usize = 60;
+const MAX_DESCRIPTION_CHARS: usize = 160;
+
+const VOID_ELEMENTS: &[&str] = &[
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "source", "track", "wbr",
+];
+/// Elements whose end tag may be left out
+const OPTIONAL_END: &[&str] = &[
+    "html", "head", "body", "p", "li", "dt", "dd", "option", "optgroup", "tr", "td", "th", "thead", "tbody",
+    "tfoot", "colgroup", "rb", "rt", "rp",
+];
+const OBSOLETE_ELEMENTS: &[&str] = &["center", "font", "marquee", "blink", "big", "strike", "tt", "frame", "frameset"];
+
+// ── Data Model ────────────────────────────────────────────────
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditCategory {
+    Accessibility,
+    Seo,
+    Validity,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditSeverity {
+    Error,
+    Warning,
+    Notice,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AuditIssue {
+    pub category: AuditCategory,
+    pub severity: AuditSeverity,
+    /// Stable id, e.g. "img-alt"
+    pub rule: String,
+    pub message: String,
+    pub line: usize,
+    /// Start of the offending tag, cut to a readable length
+    pub snippet: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PageAudit {
+    /// Relative to the project root
+    pub file: String,
+    pub score: u32,
+    pub issues: Vec<AuditIssue>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AuditReport {
+    /// Average of the page scores
+    pub score: u32,
+    /// Average per category across pages
+    pub category_scores: HashMap<AuditCategory, u32>,
+    pub errors: usize,
+    pub warnings: usize,
+    pub notices: usize,
+    pub pages: Vec<PageAudit>,
+}
+
+// ── Tokenizer ─────────────────────────────────────────────────
+
+#[derive(Clone)]
+struct Tag {
+    name: String,
+    attrs: Vec<(String, String)>,
+    line: usize,
+    /// `<br/>`-style
+    self_closing: bool,
+    snippet: String,
+}
+
+impl Tag {
+    fn attr(&self, name: &str) -> Option<&str> {
+        self.attrs.iter().find(|(n, _)| n == name).map(|(_, v)| v.as_str())
+    }
+
+    fn has_attr(&self, name: &str) -> bool {
+        self.attrs.iter().any(|(n, _)| n == name)
+    }
+
+    /// Attribute present with non-blank text
+    fn filled(&self, name: &str) -> bool {
+        self.attr(name).is_some_and(|v| !v.trim().is_empty())
+    }
+}
+
+enum Token {
+    Doctype,
+    Open(Tag),
+    Close { name: String, line: usize },
+    Text(String),
+}
+
+static ATTR: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r#"([^\s"'>/=]+)(?:\s*=\s*(?:"([^"]*)"|'([^']*)'|([^\s"'>]+)))?"#).unwrap()
+});
+
+/// Byte offsets where each line starts, for offset → line lookups
+fn line_starts(html: &str) -> Vec<usize> {
+    std::iter::once(0).chain(html.match_indices('\n').map(|(i, _)| i + 1)).collect()
+}
+
+fn line_of(starts: &[usize], offset: usize) -> usize {
+    starts.partition_point(|&s| s <= offset)
+}
+
+/// End of a tag starting at `start` (index just past `>`), skipping `>`
+/// inside quoted attribute values
+fn tag_end(html: &str, start: usize) -> usize {
+    let mut quote: Option<u8> = None;
+    for (i, b) in html.as_bytes()[start..].iter().enumerate() {
+        match (quote, *b) {
+            (Some(q), c) if c == q => quote = None,
+            (None, b'"') | (None, b'\'') => quote = Some(*b),
+            (None, b'>') => return start + i + 1,
+            _ => {}
+        }
+    }
+    html.len()
+}
+
+fn decode_entities(text: &str) -> String {
+    text.replace("&nbsp;", " ")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&amp;", "&")
+}
+
+fn snippet(raw: &str) -> String {
+    let flat: String = raw.split_whitespace().collect::<Vec<_>>().join(" ");
+    match flat.char_indices().nth(120) {
+        Some((cut, _)) => format!("{}…", &flat[..cut]),
+        None => flat,
+    }
+}
+
+fn tokenize(html: &str) -> Vec<Token> {
+    let starts = line_starts(html);
+    let lower = html.to_ascii_lowercase();
+    let mut tokens = Vec::new();
+    let mut pos = 0;
+
+    while pos < html.len() {
+        let Some(rel) = html[pos..].find('<') else {
+            tokens.push(Token::Text(decode_entities(&html[pos..])));
+            break;
+        };
+        let start = pos + rel;
+        if start > pos {
+            tokens.push(Token::Text(decode_entities(&html[pos..start])));
+        }
+        let rest = &lower[start..];
+
+        if rest.starts_with("<!--") {
+            pos = lower[start + 4..].find("-->").map(|i| start + 4 + i + 3).unwrap_or(html.len());
+            continue;
+        }
+        if rest.starts_with("<!doctype") {
+            tokens.push(Token::Doctype);
+            pos = tag_end(html, start);
+            continue;
+        }
+        if rest.starts_with("<!") || rest.starts_with("<?") {
+            pos = tag_end(html, start);
+            continue;
+        }
+
+        let closing = rest.starts_with("</");
+        let name_start = start + if closing { 2 } else { 1 };
+        let name_len = lower[name_start..]
+            .find(|c: char| !(c.is_ascii_alphanumeric() || c == '-'))
+            .unwrap_or(lower.len() - name_start);
+        if name_len == 0 || !lower[name_start..].starts_with(|c: char| c.is_ascii_alphabetic()) {
+            // A stray `<` in text
+            tokens.push(Token::Text("<".to_string()));
+            pos = start + 1;
+            continue;
+        }
+        let name = lower[name_start..name_start + name_len].to_string();
+        let end = tag_end(html, start);
+        let line = line_of(&starts, start);
+
+        if closing {
+            tokens.push(Token::Close { name, line });
+            pos = end;
+            continue;
+        }
+
+        // A tag cut off at the end of the file has no `>` to leave out
+        let inner_end = if html[..end].ends_with('>') { end - 1 } else { end };
+        let inner_end = inner_end.max(name_start + name_len);
+        let attrs = ATTR
+            .captures_iter(&html[name_start + name_len..inner_end])
+            .map(|caps| {
+                let value = caps.get(2).or(caps.get(3)).or(caps.get(4)).map(|m| m.as_str()).unwrap_or("");
+                (caps[1].to_ascii_lowercase(), decode_entities(value))
+            })
+            .collect();
+        let raw_text = matches!(name.as_str(), "script" | "style" | "textarea" | "title");
+        tokens.push(Token::Open(Tag {
+            name: name.clone(),
+            attrs,
+            line,
+            self_closing: html[..end].trim_end_matches('>').ends_with('/'),
+            snippet: snippet(&html[start..end]),
+        }));
+        pos = end;
+
+        // Raw text content runs to the matching end tag
+        if raw_text {
+            let close = format!("</{}", name);
+            let content_end = lower[pos..].find(&close).map(|i| pos + i).unwrap_or(html.len());
+            if name == "title" || name == "textarea" {
+                tokens.push(Token::Text(decode_entities(&html[pos..content_end])));
+            }
+            pos = content_end;
+        }
+    }
+
+    tokens
+}
+
+// ── Colors ────────────────────────────────────────────────────
+
+/// sRGB channels, 0–255
+type Rgb = (f64, f64, f64);
+
+fn parse_color(value: &str) -> Option<Rgb> {
+    let value = value.trim().trim_end_matches("!important").trim().to_ascii_lowercase();
+    if let Some(hex) = value.strip_prefix('#') {
+        // Also keeps the byte slicing below on char boundaries
+        if !hex.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return None;
+        }
+        let expand = |s: &str| u8::from_str_radix(s, 16).ok().map(f64::from);
+        return match hex.len() {
+            3 => {
+                let c: Vec<String> = hex.chars().map(|c| format!("{}{}", c, c)).collect();
+                Some((expand(&c[0])?, expand(&c[1])?, expand(&c[2])?))
+            }
+            6 => Some((expand(&hex[0..2])?, expand(&hex[2..4])?, expand(&hex[4..6])?)),
+            _ => None,
+        };
+    }
+    if let Some(args) = value.strip_prefix("rgb(").or_else(|| value.strip_prefix("rgba(")) {
+        let parts: Vec<f64> = args
+            .trim_end_matches(')')
+            .split([',', ' ', '/'])
+            .filter(|p| !p.is_empty())
+            .map(|p| p.trim().parse::<f64>())
+            .collect::<Result<_, _>>()
+            .ok()?;
+        // Translucent colors depend on what's behind them
+        if parts.len() < 3 || parts.get(3).is_some_and(|a| *a < 1.0) {
+            return None;
+        }
+        return Some((parts[0], parts[1], parts[2]));
+    }
+    match value.as_str() {
+        "black" => Some((0.0, 0.0, 0.0)),
+        "white" => Some((255.0, 255.0, 255.0)),
+        "red" => Some((255.0, 0.0, 0.0)),
+        "green" => Some((0.0, 128.0, 0.0)),
+        "blue" => Some((0.0, 0.0, 255.0)),
+        "yellow" => Some((255.0, 255.0, 0.0)),
+        "orange" => Some((255.0, 165.0, 0.0)),
+        "gray" | "grey" => Some((128.0, 128.0, 128.0)),
+        "silver" => Some((192.0, 192.0, 192.0)),
+        "lightgray" | "lightgrey" => Some((211.0, 211.0, 211.0)),
+        "darkgray" | "darkgrey" => Some((169.0, 169.0, 169.0)),
+        "navy" => Some((0.0, 0.0, 128.0)),
+        "purple" => Some((128.0, 0.0, 128.0)),
+        _ => None,
+    }
+}
+
+fn luminance((r, g, b): Rgb) -> f64 {
+    let channel = |c: f64| {
+        let c = c / 255.0;
+        if c <= 0.03928 {
+            c / 12.92
+        } else {
+            ((c + 0.055) / 1.055).powf(2.4)
+        }
+    };
+    0.2126 * channel(r) + 0.7152 * channel(g) + 0.0722 * channel(b)
+}
+
+fn contrast_ratio(a: Rgb, b: Rgb) -> f64 {
+    let (la, lb) = (luminance(a), luminance(b));
+    (la.max(lb) + 0.05) / (la.min(lb) + 0.05)
+}
+
+/// Text and background colour set inline on the same element
+fn inline_colors(style: &str) -> Option<(Rgb, Rgb)> {
+    let mut color = None;
+    let mut background = None;
+    for declaration in style.split(';') {
+        let Some((property, value)) = declaration.split_once(':') else {
+            continue;
+        };
+        match property.trim().to_ascii_lowercase().as_str() {
+            "color" => color = parse_color(value),
+            "background-color" | "background" => background = parse_color(value),
+            _ => {}
+        }
+    }
+    Some((color?, background?))
+}
+
+// ── Audit ─────────────────────────────────────────────────────
+
+/// Element whose text content is being collected (links, buttons,
+/// headings, labels, title)
+struct Collector {
+    tag: Tag,
+    text: String,
+}
+
+struct PageChecker {
+    issues: Vec<AuditIssue>,
+}
+
+impl PageChecker {
+    fn push(&mut self, category: AuditCategory, severity: AuditSeverity, rule: &str, message: String, tag: Option<&Tag>, line: usize) {
+        self.issues.push(AuditIssue {
+            category,
+            severity,
+            rule: rule.to_string(),
+            message,
+            line,
+            snippet: tag.map(|t| t.snippet.clone()),
+        });
+    }
+
+    fn tag(&mut self, category: AuditCategory, severity: AuditSeverity, rule: &str, message: impl Into<String>, tag: &Tag) {
+        self.push(category, severity, rule, message.into(), Some(tag), tag.line);
+    }
+}
+
+fn audit_page(html: &str) -> Vec<AuditIssue> {
+    use AuditCategory::*;
+    use AuditSeverity::*;
+
+    let mut check = PageChecker { issues: Vec::new() };
+    let mut doctype = false;
+    let mut html_tag: Option<Tag> = None;
+    let mut head_line = 1;
+    let mut titles: Vec<(Tag, String)> = Vec::new();
+    let mut metas: Vec<Tag> = Vec::new();
+    let mut canonical = false;
+    let mut ids: HashMap<String, usize> = HashMap::new();
+    let mut label_targets: HashSet<String> = HashSet::new();
+    // Form controls with their id and whether a <label> wraps them
+    let mut controls: Vec<(Tag, bool)> = Vec::new();
+    let mut headings: Vec<(u8, Tag)> = Vec::new();
+    let mut collectors: Vec<Collector> = Vec::new();
+    let mut open: Vec<(String, usize)> = Vec::new();
+
+    for token in tokenize(html) {
+        match token {
+            Token::Doctype => doctype = true,
+            Token::Text(text) => {
+                for collector in &mut collectors {
+                    collector.text.push_str(&text);
+                }
+            }
+            Token::Open(tag) => {
+                let name = tag.name.clone();
+
+                if let Some(id) = tag.attr("id").filter(|id| !id.is_empty()) {
+                    if let Some(first_line) = ids.get(id) {
+                        check.tag(Validity, Error, "duplicate-id", format!("Duplicate id \"{}\" (first used on line {})", id, first_line), &tag);
+                    } else {
+                        ids.insert(id.to_string(), tag.line);
+                    }
+                }
+                if OBSOLETE_ELEMENTS.contains(&name.as_str()) {
+                    check.tag(Validity, Notice, "obsolete-element", format!("<{}> is obsolete — use CSS instead", name), &tag);
+                }
+                if let Some((fg, bg)) = tag.attr("style").and_then(inline_colors) {
+                    let ratio = contrast_ratio(fg, bg);
+                    if ratio < MIN_CONTRAST {
+                        check.tag(Accessibility, Warning, "color-contrast", format!("Text contrast is {:.2}:1, below the {}:1 minimum", ratio, MIN_CONTRAST), &tag);
+                    }
+                }
+
+                match name.as_str() {
+                    "html" => html_tag = Some(tag.clone()),
+                    "head" => head_line = tag.line,
+                    "meta" => metas.push(tag.clone()),
+                    "link" if tag.attr("rel").is_some_and(|r| r.eq_ignore_ascii_case("canonical")) && tag.filled("href") => {
+                        canonical = true;
+                    }
+                    "img" => {
+                        let decorative = matches!(tag.attr("role"), Some("presentation") | Some("none")) || tag.attr("aria-hidden") == Some("true");
+                        if !tag.has_attr("alt") && !decorative {
+                            check.tag(Accessibility, Error, "img-alt", "Image has no alt text (use alt=\"\" if it's decorative)", &tag);
+                        }
+                        // Alt text counts as the text of a link or button around it
+                        if let Some(alt) = tag.attr("alt") {
+                            for collector in &mut collectors {
+                                collector.text.push_str(alt);
+                            }
+                        }
+                    }
+                    "area" if !tag.has_attr("alt") => {
+                        check.tag(Accessibility, Error, "area-alt", "Image map area has no alt text", &tag);
+                    }
+                    "iframe" if !tag.filled("title") => {
+                        check.tag(Accessibility, Warning, "frame-title", "Frame has no title describing its content", &tag);
+                    }
+                    "input" | "select" | "textarea" => {
+                        let kind = tag.attr("type").unwrap_or("text").to_ascii_lowercase();
+                        if name == "input" && kind == "image" && !tag.filled("alt") {
+                            check.tag(Accessibility, Error, "input-image-alt", "Image button has no alt text", &tag);
+                        } else if !(name == "input" && matches!(kind.as_str(), "hidden" | "submit" | "button" | "reset" | "image")) {
+                            let wrapped = collectors.iter().any(|c| c.tag.name == "label");
+                            controls.push((tag.clone(), wrapped));
+                        }
+                    }
+                    "label" => {
+                        if let Some(target) = tag.attr("for") {
+                            label_targets.insert(target.to_string());
+                        }
+                    }
+                    _ => {}
+                }
+
+                if let Some(level) = name.strip_prefix('h').and_then(|l| l.parse::<u8>().ok()).filter(|l| (1..=6).contains(l)) {
+                    headings.push((level, tag.clone()));
+                }
+
+                let collects = matches!(name.as_str(), "a" | "button" | "label" | "title" | "h1" | "h2" | "h3" | "h4" | "h5" | "h6");
+                if VOID_ELEMENTS.contains(&name.as_str()) || tag.self_closing {
+                    continue;
+                }
+                // Raw text elements are closed by the tokenizer's next token
+                if !matches!(name.as_str(), "script" | "style" | "textarea" | "title") {
+                    open.push((name.clone(), tag.line));
+                }
+                if collects {
+                    collectors.push(Collector { tag, text: String::new() });
+                }
+            }
+            Token::Close { name, line } => {
+                if let Some(index) = collectors.iter().rposition(|c| c.tag.name == name) {
+                    let collector = collectors.remove(index);
+                    let text = collector.text.trim().to_string();
+                    // Text of nested collectors belongs to the outer ones too
+                    for outer in &mut collectors[..index] {
+                        outer.text.push_str(&collector.text);
+                    }
+                    finish_collector(&mut check, collector.tag, text, &mut titles);
+                }
+
+                if matches!(name.as_str(), "script" | "style" | "textarea" | "title") || VOID_ELEMENTS.contains(&name.as_str()) {
+                    continue;
+                }
+                match open.iter().rposition(|(n, _)| *n == name) {
+                    Some(index) => {
+                        // Anything still open inside must have been closed implicitly
+                        for (inner, inner_line) in open.drain(index + 1..) {
+                            if !OPTIONAL_END.contains(&inner.as_str()) {
+                                check.push(Validity, Warning, "unclosed-tag", format!("<{}> opened on line {} is never closed", inner, inner_line), None, inner_line);
+                            }
+                        }
+                        open.pop();
+                    }
+                    None => {
+                        check.push(Validity, Warning, "stray-end-tag", format!("</{}> has no matching start tag", name), None, line);
+                    }
+                }
+            }
+        }
+    }
+
+    for (name, line) in open {
+        if !OPTIONAL_END.contains(&name.as_str()) {
+            check.push(Validity, Warning, "unclosed-tag", format!("<{}> opened on line {} is never closed", name, line), None, line);
+        }
+    }
+
+    // ── Document-level checks ──
+
+    if !doctype {
+        check.push(Validity, Warning, "doctype", "Missing <!DOCTYPE html> — browsers fall back to quirks mode".to_string(), None, 1);
+    }
+    match &html_tag {
+        Some(tag) if !tag.filled("lang") => {
+            check.tag(Accessibility, Error, "html-lang", "<html> has no lang attribute — screen readers can't pick a voice", tag);
+        }
+        None => check.push(Accessibility, Error, "html-lang", "No <html lang=\"...\"> element".to_string(), None, 1),
+        _ => {}
+    }
+
+    let meta = |name: &str| {
+        metas.iter().find(|m| {
+            m.attr("name").is_some_and(|n| n.eq_ignore_ascii_case(name))
+                || m.attr("property").is_some_and(|p| p.eq_ignore_ascii_case(name))
+        })
+    };
+    if !metas.iter().any(|m| m.has_attr("charset") || m.attr("http-equiv").is_some_and(|h| h.eq_ignore_ascii_case("content-type"))) {
+        check.push(Validity, Warning, "meta-charset", "Missing <meta charset=\"utf-8\">".to_string(), None, head_line);
+    }
+    if meta("viewport").is_none() {
+        check.push(Seo, Warning, "meta-viewport", "Missing <meta name=\"viewport\"> — the page won't scale on phones".to_string(), None, head_line);
+    }
+
+    match titles.as_slice() {
+        [] => check.push(Seo, Error, "title", "Page has no <title>".to_string(), None, head_line),
+        [(tag, text), rest @ ..] => {
+            if text.is_empty() {
+                check.tag(Seo, Error, "title", "Page title is empty", tag);
+            } else if text.chars().count() > MAX_TITLE_CHARS {
+                check.tag(Seo, Notice, "title-length", format!("Title is {} characters — search results cut it after about {}", text.chars().count(), MAX_TITLE_CHARS), tag);
+            }
+            for (tag, _) in rest {
+                check.tag(Validity, Error, "duplicate-title", "Page has more than one <title>", tag);
+            }
+        }
+    }
+
+    match meta("description") {
+        None => check.push(Seo, Warning, "meta-description", "Missing <meta name=\"description\">".to_string(), None, head_line),
+        Some(tag) => {
+            let length = tag.attr("content").map(|c| c.trim().chars().count()).unwrap_or(0);
+            if length == 0 {
+                check.tag(Seo, Warning, "meta-description", "Meta description is empty", tag);
+            } else if length > MAX_DESCRIPTION_CHARS {
+                check.tag(Seo, Notice, "meta-description-length", format!("Meta description is {} characters — search results cut it after about {}", length, MAX_DESCRIPTION_CHARS), tag);
+            }
+        }
+    }
+    if !canonical {
+        check.push(Seo, Notice, "canonical", "Missing <link rel=\"canonical\">".to_string(), None, head_line);
+    }
+    let missing_og: Vec<&str> = ["og:title", "og:description", "og:image"]
+        .into_iter()
+        .filter(|p| meta(p).is_none_or(|m| !m.filled("content")))
+        .collect();
+    if !missing_og.is_empty() {
+        check.push(Seo, Notice, "open-graph", format!("Missing Open Graph tags for link previews: {}", missing_og.join(", ")), None, head_line);
+    }
+
+    for (tag, wrapped) in &controls {
+        let labelled = *wrapped
+            || tag.attr("id").is_some_and(|id| label_targets.contains(id))
+            || tag.filled("aria-label")
+            || tag.filled("aria-labelledby")
+            || tag.filled("title");
+        if !labelled {
+            check.tag(Accessibility, Error, "form-label", format!("<{}> has no associated <label>", tag.name), tag);
+        }
+    }
+
+    match headings.first() {
+        None => {}
+        Some((level, tag)) if *level != 1 && !headings.iter().any(|(l, _)| *l == 1) => {
+            check.tag(Accessibility, Warning, "page-has-h1", "Page has headings but no <h1>", tag);
+        }
+        _ => {}
+    }
+    for pair in headings.windows(2) {
+        let ((previous, _), (level, tag)) = (&pair[0], &pair[1]);
+        if *level > previous + 1 {
+            check.tag(Accessibility, Warning, "heading-order", format!("<h{}> follows <h{}> — heading levels shouldn't be skipped", level, previous), tag);
+        }
+    }
+    let h1s: Vec<&Tag> = headings.iter().filter(|(l, _)| *l == 1).map(|(_, tag)| tag).collect();
+    if h1s.len() > 1 {
+        if let Some(tag) = h1s.get(1) {
+            check.tag(Seo, Notice, "single-h1", format!("Page has {} <h1> elements", h1s.len()), tag);
+        }
+    }
+
+    check.issues.sort_by_key(|i| i.line);
+    check.issues
+}
+
+/// Checks that need an element's text content
+fn finish_collector(check: &mut PageChecker, tag: Tag, text: String, titles: &mut Vec<(Tag, String)>) {
+    use AuditCategory::*;
+    use AuditSeverity::*;
+
+    let named = tag.filled("aria-label") || tag.filled("aria-labelledby") || tag.filled("title");
+    match tag.name.as_str() {
+        "title" => titles.push((tag, text)),
+        "a" if tag.has_attr("href") && text.is_empty() && !named => {
+            check.tag(Accessibility, Error, "link-name", "Link has no text — screen readers announce just \"link\"", &tag);
+        }
+        "button" if text.is_empty() && !named => {
+            check.tag(Accessibility, Error, "button-name", "Button has no text or aria-label", &tag);
+        }
+        h if h.len() == 2 && h.starts_with('h') && text.is_empty() && !named => {
+            check.tag(Accessibility, Warning, "empty-heading", "Heading is empty", &tag);
+        }
+        _ => {}
+    }
+}
+
+// ── Scoring ───────────────────────────────────────────────────
+
+fn score<'a>(issues: impl Iterator<Item = &'a AuditIssue>) -> u32 {
+    let cost: u32 = issues
+        .map(|issue| match issue.severity {
+            AuditSeverity::Error => ERROR_COST,
+            AuditSeverity::Warning => WARNING_COST,
+            AuditSeverity::Notice => NOTICE_COST,
+        })
+        .sum();
+    100u32.saturating_sub(cost)
+}
+
+fn html_files(dir: &Path, out: &mut Vec<PathBuf>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        if out.len() >= MAX_FILES {
+            return;
+        }
+        let path = entry.path();
+        let name = entry.file_name().to_string_lossy().to_string();
+        if path.is_dir() {
+            if !name.starts_with('.') && !SKIPPED_DIRS.contains(&name.as_str()) {
+                html_files(&path, out);
+            }
+        } else if path.extension().is_some_and(|e| e.eq_ignore_ascii_case("html") || e.eq_ignore_ascii_case("htm")) {
+            out.push(path);
+        }
+    }
+}
+
+/// Audit every HTML page under `project_path`
+pub fn audit(project_path: &str) -> Result<AuditReport, String> {
+    let root = PathBuf::from(project_path);
+    if !root.is_dir() {
+        return Err("Invalid project path".to_string());
+    }
+
+    let mut files = Vec::new();
+    html_files(&root, &mut files);
+    files.sort();
+
+    let pages: Vec<PageAudit> = files
+        .iter()
+        .filter_map(|path| {
+            let content = fs::read_to_string(path).ok()?;
+            let issues = audit_page(&content);
+            Some(PageAudit {
+                file: path.strip_prefix(&root).unwrap_or(path).to_string_lossy().replace('\\', "/"),
+                score: score(issues.iter()),
+                issues,
+            })
+        })
+        .collect();
+
+    let average = |scores: Vec<u32>| {
+        if scores.is_empty() {
+            100
+        } else {
+            (scores.iter().sum::<u32>() as f64 / scores.len() as f64).round() as u32
+        }
+    };
+    let category_scores = [AuditCategory::Accessibility, AuditCategory::Seo, AuditCategory::Validity]
+        .into_iter()
+        .map(|category| {
+            let scores = pages
+                .iter()
+                .map(|page| score(page.issues.iter().filter(|i| i.category == category)))
+                .collect();
+            (category, average(scores))
+        })
+        .collect();
+    let count = |severity: AuditSeverity| {
+        pages
+            .iter()
+            .flat_map(|p| &p.issues)
+            .filter(|i| i.severity == severity)
+            .count()
+    };
+
+    Ok(AuditReport {
+        score: average(pages.iter().map(|p| p.score).collect()),
+        category_scores,
+        errors: count(AuditSeverity::Error),
+        warnings: count(AuditSeverity::Warning),
+        notices: count(AuditSeverity::Notice),
+        pages,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn open_tags(html: &str) -> Vec<Tag> {
+        tokenize(html)
+            .into_iter()
+            .filter_map(|token| match token {
+                Token::Open(tag) => Some(tag),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn reads_tag_attributes() {
+        let tags = open_tags("<p>Hi</p><img src=\"a.png\" alt='Café &amp; bar' hidden>");
+        assert_eq!(tags[1].name, "img");
+        assert_eq!(tags[1].attr("src"), Some("a.png"));
+        assert_eq!(tags[1].attr("alt"), Some("Café & bar"));
+        assert!(tags[1].has_attr("hidden"));
+    }
+
+    #[test]
+    fn file_ending_inside_a_tag() {
+        for html in ["<p>Menu</p><img alt=\"café", "<p>Menu</p><img alt=é", "<a href=\"/x\">é</a><br"] {
+            let tags = open_tags(html);
+            assert_eq!(tags.len(), 2, "{}", html);
+            audit_page(html);
+        }
+    }
+
+    #[test]
+    fn colors_with_non_hex_digits_are_ignored() {
+        assert_eq!(parse_color("#aé123"), None);
+        assert_eq!(parse_color("#é1"), None);
+        assert_eq!(parse_color("#+f0000"), None);
+        assert_eq!(parse_color("#FFF"), Some((255.0, 255.0, 255.0)));
+        assert_eq!(parse_color("#00ff00 !important"), Some((0.0, 255.0, 0.0)));
+    }
+}
@@ -0,0 +1,594 @@
+// ── Dependency Installer ──────────────────────────────────────
+//
+// Picks the project's package manager from its lockfile/manifest and
+// runs the install as a cancellable background job. Output lines are
+// parsed into phase/percentage updates (`install-progress`), and the job
+// ends with `install-finished`. After a successful install we record a
+// hash of the lockfile, so later checks can tell whether node_modules
+// (or the venv, or the crate cache) is stale.
+// Records live in ~/.mydevify/data/installs.json, keyed by project path.
+
+use crate::background_job::{project_key, JobCommand, JobExit, JobRegistry};
+use chrono::Utc;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use tauri::Emitter;
+
+// ── Data Model ────────────────────────────────────────────────
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PackageManager {
+    Npm,
+    Pnpm,
+    Yarn,
+    Bun,
+    Pip,
+    Poetry,
+    Cargo,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InstallPhase {
+    Starting,
+    Resolving,
+    Fetching,
+    Linking,
+    Building,
+    Done,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct InstallStatus {
+    /// None when the project has no recognised manifest
+    pub manager: Option<PackageManager>,
+    pub install_command: Option<String>,
+    /// Dependencies are present (node_modules / .venv exist, or a previous install succeeded)
+    pub installed: bool,
+    /// The lockfile changed since the last install
+    pub stale: bool,
+    /// File whose hash decides staleness (lockfile, or manifest if there's none)
+    pub lockfile: Option<String>,
+    pub lockfile_hash: Option<String>,
+    pub installed_at: Option<String>,
+    /// ID of the install job currently running for this project
+    pub running_job: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct InstallJob {
+    pub job_id: String,
+    pub project: String,
+    pub manager: PackageManager,
+    pub command: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct InstallProgress {
+    pub job_id: String,
+    pub project: String,
+    pub phase: InstallPhase,
+    /// Estimated — most managers don't report a total up front
+    pub percent: Option<u8>,
+    /// The output line that caused this update
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct InstallFinished {
+    pub job_id: String,
+    pub project: String,
+    pub success: bool,
+    pub cancelled: bool,
+    pub exit_code: Option<i32>,
+    pub duration_ms: u64,
+    pub output_tail: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct InstallRecord {
+    manager: PackageManager,
+    lockfile_hash: String,
+    installed_at: String, // ISO 8601 string
+}
+
+static INSTALL_JOBS: once_cell::sync::Lazy<JobRegistry> =
+    once_cell::sync::Lazy::new(|| JobRegistry::new("install"));
+
+/// Serializes access to installs.json
+static RECORDS_LOCK: Mutex<()> = Mutex::new(());
+
+// ── Package Managers ──────────────────────────────────────────
+
+impl PackageManager {
+    /// Detect from the files in the project root. Lockfiles win over
+    /// bare manifests so a pnpm project isn't installed with npm.
+    pub fn detect(root: &Path) -> Option<PackageManager> {
+        let has = |name: &str| root.join(name).exists();
+
+        if has("pnpm-lock.yaml") {
+            Some(PackageManager::Pnpm)
+        } else if has("yarn.lock") {
+            Some(PackageManager::Yarn)
+        } else if has("bun.lockb") || has("bun.lock") {
+            Some(PackageManager::Bun)
+        } else if has("package.json") {
+            Some(PackageManager::Npm)
+        } else if has("poetry.lock") {
+            Some(PackageManager::Poetry)
+        } else if has("requirements.txt") {
+            Some(PackageManager::Pip)
+        } else if has("Cargo.toml") {
+            Some(PackageManager::Cargo)
+        } else {
+            None
+        }
+    }
+
+    fn install_command(self, root: &Path) -> String {
+        match self {
+            // --loglevel=http prints one line per fetch, which drives the progress estimate
+            PackageManager::Npm => "npm install --loglevel=http".to_string(),
+            PackageManager::Pnpm => "pnpm install --reporter=append-only".to_string(),
+            PackageManager::Yarn => "yarn install".to_string(),
+            PackageManager::Bun => "bun install".to_string(),
+            PackageManager::Pip => {
+                // Prefer the project's virtualenv when it has one
+                let venv_pip = if cfg!(target_os = "windows") {
+                    root.join(".venv").join("Scripts").join("pip.exe")
+                } else {
+                    root.join(".venv").join("bin").join("pip")
+                };
+                if venv_pip.exists() {
+                    format!("\"{}\" install -r requirements.txt", venv_pip.display())
+                } else if cfg!(target_os = "windows") {
+                    "python -m pip install -r requirements.txt".to_string()
+                } else {
+                    "python3 -m pip install -r requirements.txt".to_string()
+                }
+            }
+            PackageManager::Poetry => "poetry install --no-interaction".to_string(),
+            PackageManager::Cargo => "cargo fetch".to_string(),
+        }
+    }
+
+    /// Candidates for the staleness hash, most specific first
+    fn lockfiles(self) -> &'static [&'static str] {
+        match self {
+            PackageManager::Npm => &["package-lock.json", "npm-shrinkwrap.json", "package.json"],
+            PackageManager::Pnpm => &["pnpm-lock.yaml", "package.json"],
+            PackageManager::Yarn => &["yarn.lock", "package.json"],
+            PackageManager::Bun => &["bun.lock", "bun.lockb", "package.json"],
+            PackageManager::Pip => &["requirements.txt"],
+            PackageManager::Poetry => &["poetry.lock", "pyproject.toml"],
+            PackageManager::Cargo => &["Cargo.lock", "Cargo.toml"],
+        }
+    }
+
+    /// Directory the install populates, if it has a local one
+    fn install_dir(self, root: &Path) -> Option<PathBuf> {
+        match self {
+            PackageManager::Npm | PackageManager::Pnpm | PackageManager::Yarn | PackageManager::Bun => {
+                Some(root.join("node_modules"))
+            }
+            PackageManager::Pip => Some(root.join(".venv")),
+            PackageManager::Poetry | PackageManager::Cargo => None,
+        }
+    }
+}
+
+// ── Staleness ─────────────────────────────────────────────────
+
+fn get_records_file_path() -> PathBuf {
+    let home = dirs_next::home_dir().unwrap_or_else(|| PathBuf::from("."));
+    let dir = home.join(".mydevify").join("data");
+    fs::create_dir_all(&dir).ok();
+    dir.join("installs.json")
+}
+
+fn load_records() -> HashMap<String, InstallRecord> {
+    fs::read_to_string(get_records_file_path())
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn save_record(project: &str, record: InstallRecord) -> Result<(), String> {
+    let _guard = RECORDS_LOCK.lock().map_err(|e| e.to_string())?;
+    let mut records = load_records();
+    records.insert(project.to_string(), record);
+    let json = serde_json::to_string_pretty(&records).map_err(|e| e.to_string())?;
+    fs::write(get_records_file_path(), json).map_err(|e| e.to_string())
+}
+
+/// FNV-1a — stable across builds, unlike std's DefaultHasher,
+/// so recorded hashes stay comparable after an app update
+pub(crate) fn fnv1a(bytes: &[u8]) -> String {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for byte in bytes {
+        hash ^= *byte as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    format!("{:016x}", hash)
+}
+
+fn lockfile_hash(manager: PackageManager, root: &Path) -> Option<(String, String)> {
+    manager.lockfiles().iter().find_map(|name| {
+        let bytes = fs::read(root.join(name)).ok()?;
+        Some((name.to_string(), fnv1a(&bytes)))
+    })
+}
+
+/// Whether the project's dependencies are installed and up to date
+pub fn status(project: &str) -> Result<InstallStatus, String> {
+    let key = project_key(project)?;
+    let root = PathBuf::from(&key);
+
+    let manager = match PackageManager::detect(&root) {
+        Some(m) => m,
+        None => {
+            return Ok(InstallStatus {
+                manager: None,
+                install_command: None,
+                installed: false,
+                stale: false,
+                lockfile: None,
+                lockfile_hash: None,
+                installed_at: None,
+                running_job: INSTALL_JOBS.running_job_for(&key),
+            })
+        }
+    };
+
+    let record = {
+        let _guard = RECORDS_LOCK.lock().map_err(|e| e.to_string())?;
+        load_records().remove(&key)
+    };
+    let lock = lockfile_hash(manager, &root);
+    let install_dir = manager.install_dir(&root);
+
+    // node_modules is authoritative; pip may have installed into a global
+    // or external env, so a past successful install counts too
+    let installed = match install_dir {
+        Some(ref dir) if dir.is_dir() => true,
+        Some(_) if manager != PackageManager::Pip => false,
+        _ => record.is_some(),
+    };
+
+    let stale = installed
+        && match (&record, &lock) {
+            (Some(record), Some((_, hash))) => record.manager != manager || &record.lockfile_hash != hash,
+            // Installed outside Mydevify — fall back to comparing modification times
+            (None, Some((name, _))) => {
+                let modified = |p: &Path| fs::metadata(p).and_then(|m| m.modified()).ok();
+                match (modified(&root.join(name)), install_dir.as_deref().and_then(modified)) {
+                    (Some(lock_time), Some(dir_time)) => lock_time > dir_time,
+                    _ => false,
+                }
+            }
+            (_, None) => false,
+        };
+
+    Ok(InstallStatus {
+        manager: Some(manager),
+        install_command: Some(manager.install_command(&root)),
+        installed,
+        stale,
+        lockfile: lock.as_ref().map(|(name, _)| name.clone()),
+        lockfile_hash: lock.map(|(_, hash)| hash),
+        installed_at: record.map(|r| r.installed_at),
+        running_job: INSTALL_JOBS.running_job_for(&key),
+    })
+}
+
+// ── Progress Parsing ──────────────────────────────────────────
+
+struct ProgressPatterns {
+    pnpm: Regex,
+    yarn_step: Regex,
+    poetry_ops: Regex,
+}
+
+static PROGRESS_PATTERNS: once_cell::sync::Lazy<ProgressPatterns> =
+    once_cell::sync::Lazy::new(|| ProgressPatterns {
+        // Progress: resolved 312, reused 300, downloaded 12, added 120
+        pnpm: Regex::new(r"Progress: resolved (\d+), reused \d+, downloaded \d+, added (\d+)(, done)?").unwrap(),
+        // [2/4] 🚚  Fetching packages...
+        yarn_step: Regex::new(r"^\[(\d)/(\d)\]\s*\S*\s*(Resolving|Fetching|Linking|Building)").unwrap(),
+        // Package operations: 12 installs, 1 update, 0 removals
+        poetry_ops: Regex::new(r"Package operations: (\d+) installs?, (\d+) updates?").unwrap(),
+    });
+
+/// Turns one manager's output into phase/percent updates
+struct ProgressParser {
+    manager: PackageManager,
+    /// Packages we expect to fetch, when the lockfile tells us
+    expected: Option<usize>,
+    seen: usize,
+    phase: InstallPhase,
+    percent: Option<u8>,
+}
+
+impl ProgressParser {
+    fn new(manager: PackageManager, root: &Path) -> Self {
+        Self {
+            manager,
+            expected: expected_packages(manager, root),
+            seen: 0,
+            phase: InstallPhase::Starting,
+            percent: None,
+        }
+    }
+
+    fn count_percent(&mut self) -> Option<u8> {
+        self.seen += 1;
+        self.expected
+            .filter(|&total| total > 0)
+            .map(|total| ((self.seen * 100 / total).min(99)) as u8)
+    }
+
+    /// Returns true when the phase or percentage changed
+    fn feed(&mut self, line: &str) -> bool {
+        let p = &*PROGRESS_PATTERNS;
+        let line = line.trim();
+        let before = (self.phase, self.percent);
+
+        let update: Option<(InstallPhase, Option<u8>)> = match self.manager {
+            PackageManager::Npm => {
+                if line.starts_with("npm http fetch") {
+                    Some((InstallPhase::Fetching, self.count_percent()))
+                } else if line.starts_with("added ") || line.starts_with("up to date") {
+                    Some((InstallPhase::Done, Some(100)))
+                } else if line.starts_with("> ") && line.contains("install") {
+                    Some((InstallPhase::Building, self.percent))
+                } else if self.phase == InstallPhase::Starting && line.starts_with("npm") {
+                    Some((InstallPhase::Resolving, None))
+                } else {
+                    None
+                }
+            }
+            PackageManager::Pnpm => {
+                if let Some(c) = p.pnpm.captures(line) {
+                    let resolved: usize = c[1].parse().unwrap_or(0);
+                    let added: usize = c[2].parse().unwrap_or(0);
+                    let percent = (resolved > 0).then(|| ((added * 100 / resolved).min(99)) as u8);
+                    let phase = if c.get(3).is_some() { InstallPhase::Linking } else { InstallPhase::Fetching };
+                    Some((phase, percent))
+                } else if line.contains("postinstall") || line.contains("Running ") {
+                    Some((InstallPhase::Building, self.percent))
+                } else if line.starts_with("Done in") {
+                    Some((InstallPhase::Done, Some(100)))
+                } else {
+                    None
+                }
+            }
+            PackageManager::Yarn => {
+                if let Some(c) = p.yarn_step.captures(line) {
+                    let step: u32 = c[1].parse().unwrap_or(1);
+                    let total: u32 = c[2].parse().unwrap_or(4).max(1);
+                    let percent = (step.saturating_sub(1) * 100 / total).min(99) as u8;
+                    Some((phase_named(&c[3]), Some(percent)))
+                } else if line.contains("Resolution step") {
+                    // Yarn 2+ ("➤ YN0000: ┌ Resolution step")
+                    Some((InstallPhase::Resolving, Some(0)))
+                } else if line.contains("Fetch step") {
+                    Some((InstallPhase::Fetching, Some(33)))
+                } else if line.contains("Link step") {
+                    Some((InstallPhase::Linking, Some(66)))
+                } else if line.contains("Done in") || line.ends_with("Done") {
+                    Some((InstallPhase::Done, Some(100)))
+                } else {
+                    None
+                }
+            }
+            PackageManager::Bun => {
+                if line.starts_with("Resolving") {
+                    Some((InstallPhase::Resolving, None))
+                } else if line.starts_with("Resolved, downloaded and extracted") {
+                    Some((InstallPhase::Linking, Some(60)))
+                } else if line.contains("packages installed") || line.contains("package installed") {
+                    Some((InstallPhase::Done, Some(100)))
+                } else {
+                    None
+                }
+            }
+            PackageManager::Pip => {
+                if line.starts_with("Collecting") || line.starts_with("Requirement already satisfied") {
+                    Some((InstallPhase::Resolving, None))
+                } else if line.starts_with("Downloading") || line.starts_with("Using cached") {
+                    Some((InstallPhase::Fetching, None))
+                } else if line.starts_with("Installing collected packages") {
+                    Some((InstallPhase::Linking, Some(90)))
+                } else if line.starts_with("Building wheel") {
+                    Some((InstallPhase::Building, self.percent))
+                } else if line.starts_with("Successfully installed") {
+                    Some((InstallPhase::Done, Some(100)))
+                } else {
+                    None
+                }
+            }
+            PackageManager::Poetry => {
+                if let Some(c) = p.poetry_ops.captures(line) {
+                    let installs: usize = c[1].parse().unwrap_or(0);
+                    let updates: usize = c[2].parse().unwrap_or(0);
+                    self.expected = Some(installs + updates);
+                    self.seen = 0;
+                    Some((InstallPhase::Fetching, Some(0)))
+                } else if line.starts_with("- Installing") || line.starts_with("- Updating") {
+                    Some((InstallPhase::Fetching, self.count_percent()))
+                } else if line.starts_with("Updating dependencies") || line.starts_with("Resolving dependencies") {
+                    Some((InstallPhase::Resolving, None))
+                } else if line.starts_with("Installing the current project") {
+                    Some((InstallPhase::Building, Some(99)))
+                } else {
+                    None
+                }
+            }
+            PackageManager::Cargo => {
+                if line.starts_with("Downloaded ") && line.contains(" crates") {
+                    Some((InstallPhase::Done, Some(100)))
+                } else if line.starts_with("Downloaded ") {
+                    Some((InstallPhase::Fetching, self.count_percent()))
+                } else if line.starts_with("Updating") || line.starts_with("Locking") {
+                    Some((InstallPhase::Resolving, None))
+                } else {
+                    None
+                }
+            }
+        };
+
+        if let Some((phase, percent)) = update {
+            self.phase = phase;
+            self.percent = percent;
+        }
+        (self.phase, self.percent) != before
+    }
+}
+
+fn phase_named(name: &str) -> InstallPhase {
+    match name {
+        "Resolving" => InstallPhase::Resolving,
+        "Fetching" => InstallPhase::Fetching,
+        "Linking" => InstallPhase::Linking,
+        _ => InstallPhase::Building,
+    }
+}
+
+/// Package count from the lockfile, used as the denominator for percentages
+fn expected_packages(manager: PackageManager, root: &Path) -> Option<usize> {
+    match manager {
+        PackageManager::Npm => {
+            let content = fs::read_to_string(root.join("package-lock.json")).ok()?;
+            let lock: serde_json::Value = serde_json::from_str(&content).ok()?;
+            // "packages" includes the root project under the "" key
+            let count = lock.get("packages")?.as_object()?.len();
+            Some(count.saturating_sub(1))
+        }
+        PackageManager::Cargo => {
+            let content = fs::read_to_string(root.join("Cargo.lock")).ok()?;
+            Some(content.matches("[[package]]").count())
+        }
+        _ => None,
+    }
+}
+
+// ── Jobs ──────────────────────────────────────────────────────
+
+/// Start installing dependencies. Returns immediately; progress and the
+/// result arrive as events. Only one install runs per project.
+pub fn start(
+    app_handle: &tauri::AppHandle,
+    project: &str,
+    manager: Option<PackageManager>,
+) -> Result<InstallJob, String> {
+    let key = project_key(project)?;
+    let root = PathBuf::from(&key);
+    let manager = manager
+        .or_else(|| PackageManager::detect(&root))
+        .ok_or_else(|| "No package.json, requirements.txt, pyproject.toml or Cargo.toml found".to_string())?;
+
+    let job = InstallJob {
+        job_id: uuid::Uuid::new_v4().to_string(),
+        project: key.clone(),
+        manager,
+        command: manager.install_command(&root),
+    };
+
+    let parser = Arc::new(Mutex::new(ProgressParser::new(manager, &root)));
+    let emit_progress = {
+        let app_handle = app_handle.clone();
+        let job_id = job.job_id.clone();
+        let key = key.clone();
+        move |phase: InstallPhase, percent: Option<u8>, message: &str| {
+            let _ = app_handle.emit(
+                "install-progress",
+                InstallProgress {
+                    job_id: job_id.clone(),
+                    project: key.clone(),
+                    phase,
+                    percent,
+                    message: message.to_string(),
+                },
+            );
+        }
+    };
+
+    // Both streams carry progress — npm logs to stderr, pip to stdout
+    let on_line = {
+        let parser = parser.clone();
+        let emit_progress = emit_progress.clone();
+        move |line: &str| {
+            let mut parser = parser.lock().unwrap_or_else(|e| e.into_inner());
+            if parser.feed(line) {
+                emit_progress(parser.phase, parser.percent, line.trim());
+            }
+        }
+    };
+
+    // Records the lockfile hash on success
+    let on_exit = {
+        let parser = parser.clone();
+        let root = root.clone();
+        let job_id = job.job_id.clone();
+        let key = key.clone();
+        let emit_progress = emit_progress.clone();
+        move |exit: JobExit| {
+            if exit.success {
+                // Hash after the install — it may have rewritten the lockfile
+                if let Some((_, hash)) = lockfile_hash(manager, &root) {
+                    let _ = save_record(
+                        &key,
+                        InstallRecord {
+                            manager,
+                            lockfile_hash: hash,
+                            installed_at: Utc::now().to_rfc3339(),
+                        },
+                    );
+                }
+                let parser = parser.lock().unwrap_or_else(|e| e.into_inner());
+                if parser.phase != InstallPhase::Done {
+                    emit_progress(InstallPhase::Done, Some(100), "Install finished");
+                }
+            }
+            InstallFinished {
+                job_id,
+                project: key,
+                success: exit.success,
+                cancelled: exit.cancelled,
+                exit_code: exit.exit_code,
+                duration_ms: exit.duration_ms,
+                output_tail: exit.output_tail,
+            }
+        }
+    };
+
+    // Starting goes out before any parsed line, which the parser lock holds back
+    let parser_guard = parser.lock().map_err(|e| e.to_string())?;
+    INSTALL_JOBS.start(
+        app_handle,
+        JobCommand {
+            job_id: &job.job_id,
+            project: &key,
+            root: &root,
+            command: &job.command,
+            env: &[],
+            finished_event: "install-finished",
+        },
+        on_line,
+        on_exit,
+    )?;
+    emit_progress(InstallPhase::Starting, None, &job.command);
+    drop(parser_guard);
+
+    Ok(job)
+}
+
+/// Cancel a running install (kills its process group). Blocks until it's gone.
+pub fn cancel(job_id: &str) -> Result<(), String> {
+    INSTALL_JOBS.cancel(job_id)
+}
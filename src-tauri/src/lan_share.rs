@@ -0,0 +1,285 @@
+// ── LAN Sharing — Preview on Other Devices ────────────────────
+//
+// Opt-in mode for opening a preview on a phone or another machine on
+// the same network. A second listener serves a running preview (static
+// or proxied) on a LAN address, guarded by a random access token: the
+// shared URL carries it in the query string, the first request swaps it
+// for a cookie and redirects to the clean URL. Sharing stops by itself
+// after a set duration or when its preview server stops. Every request
+// from another host is logged so the user can see who opened the link.
+
+use axum::extract::{ConnectInfo, Request, State};
+use axum::http::{header, HeaderValue, StatusCode};
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use std::collections::{HashMap, VecDeque};
+use std::net::{IpAddr, SocketAddr};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::watch;
+
+const TOKEN_PARAM: &str = "mydevify_token";
+const TOKEN_COOKIE: &str = "mydevify_share";
+const DEFAULT_DURATION: Duration = Duration::from_secs(30 * 60);
+const MAX_DURATION: Duration = Duration::from_secs(24 * 60 * 60);
+/// Remote requests kept for the activity list
+const MAX_LOGGED_REQUESTS: usize = 200;
+
+// ── Data Model ────────────────────────────────────────────────
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LanInterface {
+    pub name: String,
+    pub address: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RemoteRequest {
+    pub timestamp: String,
+    pub remote: String,
+    pub method: String,
+    pub path: String,
+    pub status: u16,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LanShareInfo {
+    /// Open one of these on the other device — they include the token
+    pub urls: Vec<String>,
+    pub port: u16,
+    pub token: String,
+    pub expires_at: String,
+    /// Newest last
+    pub requests: Vec<RemoteRequest>,
+}
+
+struct ShareGuard {
+    token: String,
+    expires: Instant,
+    /// Addresses of this machine — requests from them aren't "remote"
+    local_ips: Vec<IpAddr>,
+    requests: Mutex<VecDeque<RemoteRequest>>,
+}
+
+struct ActiveShare {
+    guard: Arc<ShareGuard>,
+    urls: Vec<String>,
+    port: u16,
+    expires_at: chrono::DateTime<chrono::Utc>,
+    /// Dropping it closes the LAN listener
+    _shutdown: watch::Sender<()>,
+}
+
+// Active shares keyed by the preview server they expose
+static LAN_SHARES: Lazy<Mutex<HashMap<String, ActiveShare>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+// ── Interfaces ────────────────────────────────────────────────
+
+/// Non-loopback IPv4 interfaces a phone on the same network could reach
+pub fn interfaces() -> Vec<LanInterface> {
+    if_addrs::get_if_addrs()
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|iface| !iface.is_loopback() && iface.ip().is_ipv4())
+        .map(|iface| LanInterface {
+            address: iface.ip().to_string(),
+            name: iface.name,
+        })
+        .collect()
+}
+
+// ── Start / Stop ──────────────────────────────────────────────
+
+/// Share the preview server running under `key` on `address` (all LAN
+/// interfaces if None) for `duration_minutes` (30 by default, at most a day).
+pub async fn start(key: &str, address: Option<String>, duration_minutes: Option<u64>) -> Result<LanShareInfo, String> {
+    stop(key);
+
+    let served = crate::server::served_app(key).ok_or("Start the preview first")?;
+    let lan = interfaces();
+
+    let (bind_ip, url_ips): (IpAddr, Vec<IpAddr>) = match address {
+        Some(address) => {
+            let ip: IpAddr = address.parse().map_err(|_| format!("Invalid address: {}", address))?;
+            if !lan.iter().any(|iface| iface.address == address) {
+                return Err(format!("{} is not a network interface of this machine", address));
+            }
+            (ip, vec![ip])
+        }
+        None => {
+            let ips: Vec<IpAddr> = lan.iter().filter_map(|iface| iface.address.parse().ok()).collect();
+            if ips.is_empty() {
+                return Err("No network interface found — is this machine connected to a network?".to_string());
+            }
+            (IpAddr::from([0, 0, 0, 0]), ips)
+        }
+    };
+
+    let duration = duration_minutes
+        .map(|m| Duration::from_secs(m * 60))
+        .unwrap_or(DEFAULT_DURATION)
+        .clamp(Duration::from_secs(60), MAX_DURATION);
+
+    let guard = Arc::new(ShareGuard {
+        token: uuid::Uuid::new_v4().simple().to_string(),
+        expires: Instant::now() + duration,
+        local_ips: lan.iter().filter_map(|iface| iface.address.parse().ok()).collect(),
+        requests: Mutex::new(VecDeque::new()),
+    });
+
+    // The certificate must name the LAN addresses for HTTPS previews
+    let tls = if served.https {
+        Some(crate::local_ca::server_config(&url_ips)?)
+    } else {
+        None
+    };
+
+    let app = served
+        .router
+        .layer(axum::middleware::from_fn_with_state(guard.clone(), check_access));
+    let (port, shutdown) = crate::server::serve(app, bind_ip, served.port, tls).await?;
+
+    let scheme = if served.https { "https" } else { "http" };
+    let urls = url_ips
+        .iter()
+        .map(|ip| format!("{}://{}:{}/?{}={}", scheme, ip, port, TOKEN_PARAM, guard.token))
+        .collect();
+    let expires_at = chrono::Utc::now()
+        + chrono::Duration::from_std(duration).unwrap_or_else(|_| chrono::Duration::minutes(30));
+
+    LAN_SHARES.lock().unwrap_or_else(|e| e.into_inner()).insert(key.to_string(), ActiveShare {
+        guard: guard.clone(),
+        urls,
+        port,
+        expires_at,
+        _shutdown: shutdown,
+    });
+
+    // Auto-expire — unless the share was replaced or stopped meanwhile
+    let expire_key = key.to_string();
+    tokio::spawn(async move {
+        tokio::time::sleep(duration).await;
+        let mut shares = LAN_SHARES.lock().unwrap_or_else(|e| e.into_inner());
+        if shares.get(&expire_key).is_some_and(|s| Arc::ptr_eq(&s.guard, &guard)) {
+            shares.remove(&expire_key);
+        }
+    });
+
+    status(key).ok_or_else(|| "LAN sharing stopped unexpectedly".to_string())
+}
+
+/// Stop sharing the preview server running under `key`
+pub fn stop(key: &str) {
+    LAN_SHARES.lock().unwrap_or_else(|e| e.into_inner()).remove(key);
+}
+
+/// Current share of `key` with the remote requests seen so far, if sharing
+pub fn status(key: &str) -> Option<LanShareInfo> {
+    let shares = LAN_SHARES.lock().unwrap_or_else(|e| e.into_inner());
+    let share = shares.get(key)?;
+    Some(LanShareInfo {
+        urls: share.urls.clone(),
+        port: share.port,
+        token: share.guard.token.clone(),
+        expires_at: share.expires_at.to_rfc3339(),
+        requests: share
+            .guard
+            .requests
+            .lock()
+            .map(|r| r.iter().cloned().collect())
+            .unwrap_or_default(),
+    })
+}
+
+// ── Access Check ──────────────────────────────────────────────
+
+/// Middleware on the LAN listener: token in the query → cookie, cookie →
+/// pass, anything else → 401. Logs requests from other hosts.
+async fn check_access(
+    State(guard): State<Arc<ShareGuard>>,
+    ConnectInfo(peer): ConnectInfo<SocketAddr>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let method = request.method().to_string();
+    let path = request.uri().path().to_string();
+
+    let response = if Instant::now() >= guard.expires {
+        (StatusCode::GONE, "This shared preview link has expired").into_response()
+    } else if let Some(clean_url) = token_in_query(&request, &guard.token) {
+        // Swap the token for a cookie so it doesn't linger in the address bar
+        let remaining = guard.expires.saturating_duration_since(Instant::now()).as_secs();
+        let cookie = format!(
+            "{}={}; Path=/; HttpOnly; SameSite=Lax; Max-Age={}",
+            TOKEN_COOKIE, guard.token, remaining
+        );
+        let mut response = StatusCode::FOUND.into_response();
+        if let (Ok(location), Ok(cookie)) = (HeaderValue::from_str(&clean_url), HeaderValue::from_str(&cookie)) {
+            response.headers_mut().insert(header::LOCATION, location);
+            response.headers_mut().insert(header::SET_COOKIE, cookie);
+        }
+        response
+    } else if has_token_cookie(&request, &guard.token) {
+        next.run(request).await
+    } else {
+        (
+            StatusCode::UNAUTHORIZED,
+            "This preview is private. Open the full shared link, including its access token.",
+        )
+            .into_response()
+    };
+
+    let remote = peer.ip();
+    if !remote.is_loopback() && !guard.local_ips.contains(&remote) {
+        if let Ok(mut requests) = guard.requests.lock() {
+            if requests.len() >= MAX_LOGGED_REQUESTS {
+                requests.pop_front();
+            }
+            requests.push_back(RemoteRequest {
+                timestamp: chrono::Utc::now().to_rfc3339(),
+                remote: remote.to_string(),
+                method,
+                path,
+                status: response.status().as_u16(),
+            });
+        }
+    }
+
+    response
+}
+
+/// If the query carries the right token, the same URL without it
+fn token_in_query(request: &Request, token: &str) -> Option<String> {
+    let query = request.uri().query()?;
+    let mut found = false;
+    let rest: Vec<&str> = query
+        .split('&')
+        .filter(|pair| {
+            let is_token = pair.split_once('=').map(|(k, v)| k == TOKEN_PARAM && v == token);
+            found |= is_token == Some(true);
+            is_token != Some(true)
+        })
+        .collect();
+    if !found {
+        return None;
+    }
+    let path = request.uri().path();
+    Some(if rest.is_empty() {
+        path.to_string()
+    } else {
+        format!("{}?{}", path, rest.join("&"))
+    })
+}
+
+fn has_token_cookie(request: &Request, token: &str) -> bool {
+    request
+        .headers()
+        .get_all(header::COOKIE)
+        .iter()
+        .filter_map(|v| v.to_str().ok())
+        .flat_map(|v| v.split(';'))
+        .filter_map(|pair| pair.trim().split_once('='))
+        .any(|(name, value)| name == TOKEN_COOKIE && value == token)
+}
@@ -1,13 +1,35 @@
 use std::fs;
 use std::path::PathBuf;
-use std::sync::{Arc, Mutex};
 use serde::{Deserialize, Serialize};
 
 #[cfg(target_os = "windows")]
 use std::os::windows::process::CommandExt;
 
+mod background_job;
+mod build_job;
+mod bundle_analysis;
+mod command_history;
+mod console_capture;
+mod dev_server;
+mod diagnostics;
+mod html_audit;
+mod installer;
+mod lan_share;
+mod link_checker;
+mod live_reload;
+mod local_ca;
+mod log_buffer;
+mod mock_api;
+mod port_discovery;
+mod process_registry;
+mod request_log;
+mod routing_rules;
+mod run_config;
 mod server;
+mod sandbox;
 mod scheduler;
+mod shell_env;
+mod supervisor;
 mod task_runner;
 
 #[derive(Serialize, Deserialize)]
@@ -23,6 +45,9 @@ pub struct CommandResult {
     stdout: String,
     stderr: String,
     exit_code: i32,
+    /// Present only when the command ran inside the sandbox
+    #[serde(skip_serializing_if = "Option::is_none")]
+    sandbox: Option<sandbox::SandboxReport>,
 }
 
 /// Strip ANSI escape codes from a string.
@@ -36,14 +61,6 @@ fn strip_ansi_codes(s: &str) -> String {
 // Store the allowed project path
 static mut PROJECT_PATH: Option<PathBuf> = None;
 
-// Store the dev server child process PID so we can kill it later
-static DEV_SERVER_PROCESS: Mutex<Option<u32>> = Mutex::new(None);
-
-// Buffer for dev server output (captured after port is found)
-// Frontend can poll this to check for build errors
-static DEV_SERVER_OUTPUT: once_cell::sync::Lazy<Arc<Mutex<String>>> =
-    once_cell::sync::Lazy::new(|| Arc::new(Mutex::new(String::new())));
-
 #[tauri::command]
 fn set_project_path(path: String) -> Result<(), String> {
     let path_buf = PathBuf::from(&path);
@@ -199,8 +216,14 @@ fn delete_path(path: String) -> Result<(), String> {
 
 // ── Terminal Commands ──────────────────────────────────────────
 
+/// Run a shell command and record it in the project's command history.
+/// `initiator` tells the history who ran it (defaults to the user).
 #[tauri::command]
-fn execute_command(command: String, cwd: String) -> Result<CommandResult, String> {
+fn execute_command(
+    command: String,
+    cwd: String,
+    initiator: Option<command_history::Initiator>,
+) -> Result<CommandResult, String> {
     let cwd_path = PathBuf::from(&cwd);
 
     // Verify cwd exists
@@ -213,32 +236,72 @@ fn execute_command(command: String, cwd: String) -> Result<CommandResult, String
     // Build the shell command based on OS
     // On Windows: /D disables AutoRun, /S strips outer quotes so
     // Rust's argument quoting doesn't break multi-word commands
-    let output = if cfg!(target_os = "windows") {
-        std::process::Command::new("cmd.exe")
-            .arg("/D")
-            .arg("/S")
-            .arg("/C")
-            .arg(&trimmed)
-            .current_dir(&cwd_path)
-            .output()
+    let mut cmd = if cfg!(target_os = "windows") {
+        let mut c = std::process::Command::new("cmd.exe");
+        c.arg("/D").arg("/S").arg("/C").arg(&trimmed);
+        c
     } else {
-        std::process::Command::new("/bin/sh")
-            .arg("-c")
-            .arg(&trimmed)
-            .current_dir(&cwd_path)
-            .output()
+        let mut c = std::process::Command::new("/bin/sh");
+        c.arg("-c").arg(&trimmed);
+        c
     };
+    cmd.current_dir(&cwd_path);
+    shell_env::apply(&mut cmd);
+
+    // Confine writes to the project (or cwd) if the sandbox is enabled
+    let project_root = project_root_for(&cwd_path);
+    let mut sandbox_report = sandbox::apply(&mut cmd, &project_root)?;
+
+    let started_at = chrono::Utc::now();
+    match cmd.output() {
+        Ok(out) => {
+            let stdout = String::from_utf8_lossy(&out.stdout).to_string();
+            let stderr = String::from_utf8_lossy(&out.stderr).to_string();
+            let exit_code = out.status.code().unwrap_or(-1);
+            if let Some(ref mut report) = sandbox_report {
+                sandbox::collect_violations(report, &stderr);
+            }
 
-    match output {
-        Ok(out) => Ok(CommandResult {
-            stdout: String::from_utf8_lossy(&out.stdout).to_string(),
-            stderr: String::from_utf8_lossy(&out.stderr).to_string(),
-            exit_code: out.status.code().unwrap_or(-1),
-        }),
+            // History is best-effort — never fail the command over it
+            let _ = command_history::record(
+                &project_root,
+                command_history::FinishedCommand {
+                    command: &trimmed,
+                    cwd: &cwd_path,
+                    initiator: initiator.unwrap_or(command_history::Initiator::User),
+                    exit_code,
+                    stdout: &stdout,
+                    stderr: &stderr,
+                    started_at,
+                },
+            );
+
+            Ok(CommandResult {
+                stdout,
+                stderr,
+                exit_code,
+                sandbox: sandbox_report,
+            })
+        }
         Err(e) => Err(format!("Failed to execute command: {}", e)),
     }
 }
 
+/// Project a command belongs to: the open project if `cwd` is inside it,
+/// otherwise the working directory itself. Used as the sandbox's writable
+/// root and as the command history key.
+fn project_root_for(cwd: &std::path::Path) -> PathBuf {
+    let canonical = cwd.canonicalize().unwrap_or_else(|_| cwd.to_path_buf());
+    unsafe {
+        if let Some(ref project) = PROJECT_PATH {
+            if canonical.starts_with(project) {
+                return project.clone();
+            }
+        }
+    }
+    canonical
+}
+
 #[tauri::command]
 fn resolve_path(cwd: String, target: String) -> Result<String, String> {
     let target_path = if PathBuf::from(&target).is_absolute() {
@@ -260,21 +323,145 @@ fn resolve_path(cwd: String, target: String) -> Result<String, String> {
 }
 
 // ── Preview Server Commands ─────────────────────────────────────
+//
+// Each preview server is keyed by project path or window id (`key`), so
+// several projects can be previewed side by side on their own ports.
 
 #[tauri::command]
-async fn start_preview_server(path: String) -> Result<u16, String> {
-    server::start(&path, 3456).await
+async fn start_preview_server(
+    app_handle: tauri::AppHandle,
+    path: String,
+    key: Option<String>,
+    options: Option<server::PreviewOptions>,
+) -> Result<u16, String> {
+    let key = key.unwrap_or_else(|| path.clone());
+    server::start(&app_handle, &key, &path, 3456, &options.unwrap_or_default()).await
 }
 
+/// Serve the running dev server through the preview port, so framework
+/// previews keep a stable URL across dev server restarts.
 #[tauri::command]
-fn stop_preview_server() -> Result<(), String> {
-    server::stop();
+async fn start_preview_proxy(
+    app_handle: tauri::AppHandle,
+    key: String,
+    options: Option<server::PreviewOptions>,
+) -> Result<u16, String> {
+    server::start_proxy(&app_handle, &key, 3456, &options.unwrap_or_default()).await
+}
+
+/// Stop the preview server under `key`, or every one when omitted
+#[tauri::command]
+fn stop_preview_server(key: Option<String>) -> Result<(), String> {
+    match key {
+        Some(key) => {
+            server::stop(&key);
+        }
+        None => server::stop_all(),
+    }
     Ok(())
 }
 
 #[tauri::command]
-fn get_preview_port() -> Option<u16> {
-    server::get_port()
+fn get_preview_port(key: String) -> Option<u16> {
+    server::get_port(&key)
+}
+
+/// Every running preview server with its port, root and options
+#[tauri::command]
+fn list_preview_servers() -> Vec<server::PreviewServerInfo> {
+    server::list()
+}
+
+/// Requests answered by the preview servers — status, resolved file,
+/// size and latency. Follow new ones by passing back `cursor` as `since`.
+#[tauri::command]
+fn get_preview_requests(options: Option<request_log::RequestLogOptions>) -> request_log::RequestLogRead {
+    request_log::read(&options.unwrap_or_default())
+}
+
+#[tauri::command]
+fn clear_preview_requests(key: Option<String>) {
+    request_log::clear(key.as_deref());
+}
+
+/// Console messages, uncaught errors and failed fetches reported by
+/// preview pages. Follow new ones by passing back `cursor` as `since`.
+#[tauri::command]
+fn get_preview_console(options: Option<console_capture::ConsoleReadOptions>) -> console_capture::ConsoleRead {
+    console_capture::read(&options.unwrap_or_default())
+}
+
+#[tauri::command]
+fn clear_preview_console(key: Option<String>) {
+    console_capture::clear(key.as_deref());
+}
+
+/// Crawl a static site from index.html for broken links, missing
+/// assets, case-mismatched paths and orphaned pages
+#[tauri::command]
+async fn check_links(path: String) -> Result<link_checker::LinkReport, String> {
+    tauri::async_runtime::spawn_blocking(move || link_checker::check(&path))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+/// Accessibility, SEO and validity audit of every HTML page in a
+/// project, scored per page and overall
+#[tauri::command]
+async fn audit_html(path: String) -> Result<html_audit::AuditReport, String> {
+    tauri::async_runtime::spawn_blocking(move || html_audit::audit(&path))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+/// Mock API routes defined in the project's `.mydevify/mocks/`, plus any
+/// files that failed to parse
+#[tauri::command]
+fn list_mock_routes(project_path: String) -> mock_api::MockSummary {
+    mock_api::MockRoutes::load(std::path::Path::new(&project_path)).summary()
+}
+
+/// Network interfaces the preview can be shared on
+#[tauri::command]
+fn list_lan_interfaces() -> Vec<lan_share::LanInterface> {
+    lan_share::interfaces()
+}
+
+/// Share the preview under `key` on the LAN behind an access token.
+/// `address` picks one interface (all when omitted).
+#[tauri::command]
+async fn start_lan_share(
+    key: String,
+    address: Option<String>,
+    duration_minutes: Option<u64>,
+) -> Result<lan_share::LanShareInfo, String> {
+    lan_share::start(&key, address, duration_minutes).await
+}
+
+#[tauri::command]
+fn stop_lan_share(key: String) {
+    lan_share::stop(&key);
+}
+
+/// Share URLs, expiry and requests from other devices, if sharing
+#[tauri::command]
+fn get_lan_share(key: String) -> Option<lan_share::LanShareInfo> {
+    lan_share::status(&key)
+}
+
+/// The local CA behind HTTPS previews, for adding to a trust store
+#[tauri::command]
+async fn get_local_ca() -> Result<local_ca::LocalCaInfo, String> {
+    tauri::async_runtime::spawn_blocking(local_ca::info)
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+#[tauri::command]
+async fn export_local_ca(destination: String) -> Result<(), String> {
+    tauri::async_runtime::spawn_blocking(move || local_ca::export(&destination))
+        .await
+        .map_err(|e| e.to_string())?
 }
 
 // ── Dev Server Commands (for framework projects) ────────────────
@@ -293,6 +480,9 @@ fn build_hidden_shell_command(command: &str, cwd: &PathBuf) -> std::process::Com
     }
 
     cmd.current_dir(cwd);
+    shell_env::apply(&mut cmd);
+    // Own process group, so stopping it takes the whole tree down
+    process_registry::isolate(&mut cmd);
 
     // Hide console window on Windows
     #[cfg(target_os = "windows")]
@@ -301,233 +491,285 @@ fn build_hidden_shell_command(command: &str, cwd: &PathBuf) -> std::process::Com
     cmd
 }
 
-/// Start a long-running dev server process (e.g. `npm run dev`).
-/// Captures stdout/stderr, watches for a localhost port in the output,
-/// and returns the port once detected (or errors after timeout).
-/// After port is found, keeps capturing output into DEV_SERVER_OUTPUT buffer
-/// so the frontend can poll for build errors.
+/// Start the dev server and return its port once it's serving,
+/// along with how the port was found.
+/// See dev_server.rs for crash detection and auto-restart.
 #[tauri::command]
-async fn start_dev_server(command: String, cwd: String, port_pattern: String) -> Result<u16, String> {
-    use std::io::{BufRead, BufReader};
-    use std::process::Stdio;
+async fn start_dev_server(
+    app_handle: tauri::AppHandle,
+    command: String,
+    cwd: String,
+    port_pattern: String,
+    options: Option<dev_server::DevServerOptions>,
+) -> Result<dev_server::DevServerStarted, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        dev_server::start(&app_handle, command, cwd, port_pattern, options.unwrap_or_default())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
 
-    // Kill any existing dev server first
-    stop_dev_server_internal();
+/// Get dev server output newer than `options.since`.
+/// Frontend polls this after AI finishes writing files to check for build errors;
+/// each caller keeps its own cursor, so reads don't interfere.
+#[tauri::command]
+fn get_dev_server_output(options: Option<log_buffer::LogReadOptions>) -> log_buffer::LogRead {
+    dev_server::read_output(&options.unwrap_or_default())
+}
 
-    // Clear the output buffer
+/// Structured build errors parsed from the dev server output.
+/// Changes are also pushed as `dev-server-diagnostics` events.
+#[tauri::command]
+fn get_dev_server_diagnostics() -> Vec<diagnostics::Diagnostic> {
+    dev_server::diagnostics()
+}
+
+/// Kill a process and all its children.
+/// On Unix this is graceful (SIGTERM, then SIGKILL after a grace period) and blocks.
+fn kill_process_tree(pid: u32) {
+    #[cfg(target_os = "windows")]
     {
-        let mut buf = DEV_SERVER_OUTPUT.lock().unwrap_or_else(|e| e.into_inner());
-        buf.clear();
+        // taskkill /T kills the tree, /F forces it
+        let mut cmd = std::process::Command::new("taskkill");
+        cmd.args(["/PID", &pid.to_string(), "/T", "/F"]);
+        cmd.creation_flags(0x08000000);
+        let _ = cmd.output();
     }
 
-    let cwd_path = PathBuf::from(&cwd);
-    if !cwd_path.exists() || !cwd_path.is_dir() {
-        return Err(format!("Directory not found: {}", cwd));
-    }
+    // The PID leads its own process group (see process_registry.rs)
+    #[cfg(unix)]
+    process_registry::terminate(pid);
 
-    // Spawn the dev server process with piped stdout and stderr
-    let mut child = {
-        let mut cmd = build_hidden_shell_command(&command, &cwd_path);
-        cmd.stdout(Stdio::piped()).stderr(Stdio::piped());
-        cmd.spawn()
-    }
-    .map_err(|e| format!("Failed to start dev server: {}", e))?;
+    process_registry::untrack(pid);
+}
 
-    // Store the PID so we can kill it later
-    let pid = child.id();
-    {
-        let mut proc = DEV_SERVER_PROCESS.lock().map_err(|e| e.to_string())?;
-        *proc = Some(pid);
-    }
+/// Stop the dev server — exposed to frontend.
+/// Async because stopping waits for the process group to exit.
+#[tauri::command]
+async fn stop_dev_server() -> Result<(), String> {
+    tauri::async_runtime::spawn_blocking(|| {
+        dev_server::stop_internal();
+    })
+    .await
+    .map_err(|e| e.to_string())
+}
 
-    // Compile the port pattern regex
-    let re = regex::Regex::new(&port_pattern)
-        .map_err(|e| format!("Invalid port pattern: {}", e))?;
-
-    // Take stdout and stderr handles
-    let stdout = child.stdout.take();
-    let stderr = child.stderr.take();
-
-    // Read both stdout and stderr in separate threads,
-    // looking for the port pattern in either stream.
-    // Dev servers vary — some print to stdout, some to stderr.
-    let (tx, rx) = std::sync::mpsc::channel::<Result<u16, String>>();
-
-    // Shared flag so both threads know when port has been found
-    let port_found = Arc::new(std::sync::atomic::AtomicBool::new(false));
-
-    // Spawn stdout reader
-    if let Some(out) = stdout {
-        let re_clone = re.clone();
-        let tx_clone = tx.clone();
-        let port_found_clone = port_found.clone();
-        let output_buf = DEV_SERVER_OUTPUT.clone();
-        std::thread::spawn(move || {
-            let reader = BufReader::new(out);
-            for line in reader.lines() {
-                if let Ok(line) = line {
-                    // Before port is found, check for port pattern
-                    if !port_found_clone.load(std::sync::atomic::Ordering::Relaxed) {
-                        let clean = strip_ansi_codes(&line);
-                        if let Some(caps) = re_clone.captures(&clean) {
-                            if let Some(port_str) = caps.get(1) {
-                                if let Ok(port) = port_str.as_str().parse::<u16>() {
-                                    port_found_clone.store(true, std::sync::atomic::Ordering::Relaxed);
-                                    let _ = tx_clone.send(Ok(port));
-                                }
-                            }
-                        }
-                    }
-
-                    // Always capture output after port is found (for error detection)
-                    if port_found_clone.load(std::sync::atomic::Ordering::Relaxed) {
-                        if let Ok(mut buf) = output_buf.lock() {
-                            // Keep buffer under 10KB — drop oldest lines
-                            if buf.len() > 10_000 {
-                                let cutoff = buf.len() - 5_000;
-                                if let Some(pos) = buf[cutoff..].find('\n') {
-                                    let new_start = cutoff + pos + 1;
-                                    *buf = buf[new_start..].to_string();
-                                }
-                            }
-                            buf.push_str(&line);
-                            buf.push('\n');
-                        }
-                    }
-                }
-            }
-            // Stream closed — if port was never found, report it
-            if !port_found_clone.load(std::sync::atomic::Ordering::Relaxed) {
-                let _ = tx_clone.send(Err("Dev server stdout closed without printing a port".to_string()));
-            }
-        });
-    }
+// ── Dependency Install Commands ───────────────────────────────
 
-    // Spawn stderr reader
-    if let Some(err_stream) = stderr {
-        let re_clone = re.clone();
-        let tx_clone = tx.clone();
-        let port_found_clone = port_found.clone();
-        let output_buf = DEV_SERVER_OUTPUT.clone();
-        std::thread::spawn(move || {
-            let reader = BufReader::new(err_stream);
-            let mut captured = String::new();
-            for line in reader.lines() {
-                if let Ok(line) = line {
-                    // Before port is found, keep capturing for error reporting
-                    if !port_found_clone.load(std::sync::atomic::Ordering::Relaxed) {
-                        if captured.len() < 2000 {
-                            captured.push_str(&line);
-                            captured.push('\n');
-                        }
-                        if let Some(caps) = re_clone.captures(&strip_ansi_codes(&line)) {
-                            if let Some(port_str) = caps.get(1) {
-                                if let Ok(port) = port_str.as_str().parse::<u16>() {
-                                    port_found_clone.store(true, std::sync::atomic::Ordering::Relaxed);
-                                    let _ = tx_clone.send(Ok(port));
-                                }
-                            }
-                        }
-                    }
-
-                    // Always capture output after port is found (for error detection)
-                    if port_found_clone.load(std::sync::atomic::Ordering::Relaxed) {
-                        if let Ok(mut buf) = output_buf.lock() {
-                            if buf.len() > 10_000 {
-                                let cutoff = buf.len() - 5_000;
-                                if let Some(pos) = buf[cutoff..].find('\n') {
-                                    let new_start = cutoff + pos + 1;
-                                    *buf = buf[new_start..].to_string();
-                                }
-                            }
-                            buf.push_str(&line);
-                            buf.push('\n');
-                        }
-                    }
-                }
-            }
-            // Stream closed — if port was never found, report it
-            if !port_found_clone.load(std::sync::atomic::Ordering::Relaxed) {
-                let _ = tx_clone.send(Err(format!(
-                    "Dev server exited without starting. Output:\n{}",
-                    if captured.is_empty() { "(no output)".to_string() } else { captured }
-                )));
-            }
-        });
-    }
+/// Detected package manager and whether dependencies are missing or stale
+#[tauri::command]
+fn get_install_status(project: String) -> Result<installer::InstallStatus, String> {
+    installer::status(&project)
+}
 
-    // Drop the extra sender so rx doesn't hang forever if both threads exit
-    drop(tx);
+/// Start installing dependencies in the background.
+/// Progress arrives as `install-progress`, the result as `install-finished`.
+#[tauri::command]
+fn start_install(
+    app_handle: tauri::AppHandle,
+    project: String,
+    manager: Option<installer::PackageManager>,
+) -> Result<installer::InstallJob, String> {
+    installer::start(&app_handle, &project, manager)
+}
 
-    // Wait for a port with a 30-second timeout
-    match rx.recv_timeout(std::time::Duration::from_secs(30)) {
-        Ok(Ok(port)) => Ok(port),
-        Ok(Err(e)) => {
-            stop_dev_server_internal();
-            Err(e)
-        }
-        Err(_) => {
-            stop_dev_server_internal();
-            Err("Dev server timed out after 30 seconds without printing a port. Try running the command manually in the terminal.".to_string())
-        }
-    }
+/// Async because cancelling waits for the install's process group to exit
+#[tauri::command]
+async fn cancel_install(job_id: String) -> Result<(), String> {
+    tauri::async_runtime::spawn_blocking(move || installer::cancel(&job_id))
+        .await
+        .map_err(|e| e.to_string())?
 }
 
-/// Get buffered dev server output and clear the buffer.
-/// Frontend polls this after AI finishes writing files to check for build errors.
+// ── Production Build Commands ─────────────────────────────────
+
+/// Run the project's build script in the background, then serve its
+/// output in production mode. Output lines arrive as `build-output`, the
+/// result (artifacts, sizes, preview port) as `build-finished`.
 #[tauri::command]
-fn get_dev_server_output() -> String {
-    let mut buf = DEV_SERVER_OUTPUT.lock().unwrap_or_else(|e| e.into_inner());
-    let output = buf.clone();
-    buf.clear();
-    output
+fn build_project(
+    app_handle: tauri::AppHandle,
+    project: String,
+    options: Option<build_job::BuildOptions>,
+) -> Result<build_job::BuildJob, String> {
+    build_job::start(&app_handle, &project, options.unwrap_or_default())
 }
 
-/// Internal helper to kill the dev server process tree
-fn stop_dev_server_internal() {
-    let pid = {
-        let mut proc = DEV_SERVER_PROCESS.lock().unwrap_or_else(|e| e.into_inner());
-        proc.take()
-    };
+/// Async because cancelling waits for the build's process group to exit
+#[tauri::command]
+async fn cancel_build(job_id: String) -> Result<(), String> {
+    tauri::async_runtime::spawn_blocking(move || build_job::cancel(&job_id))
+        .await
+        .map_err(|e| e.to_string())?
+}
 
-    if let Some(pid) = pid {
-        kill_process_tree(pid);
-    }
+/// Result of the project's last build, or null if it hasn't been built
+#[tauri::command]
+fn get_last_build(project: String) -> Result<Option<build_job::BuildFinished>, String> {
+    build_job::last_build(&project)
+}
 
-    // Clear the output buffer
-    if let Ok(mut buf) = DEV_SERVER_OUTPUT.lock() {
-        buf.clear();
-    }
+/// Raw, gzip and brotli sizes of the build output, grouped by entry and
+/// broken down by module, compared with the previous build against `budget`
+#[tauri::command]
+async fn analyze_bundle(
+    project: String,
+    options: Option<bundle_analysis::BundleOptions>,
+) -> Result<bundle_analysis::BundleReport, String> {
+    tauri::async_runtime::spawn_blocking(move || bundle_analysis::analyze(&project, &options.unwrap_or_default()))
+        .await
+        .map_err(|e| e.to_string())?
 }
 
-/// Kill a process and all its children
-fn kill_process_tree(pid: u32) {
-    if cfg!(target_os = "windows") {
-        // taskkill /T kills the tree, /F forces it
-        let mut cmd = std::process::Command::new("taskkill");
-        cmd.args(["/PID", &pid.to_string(), "/T", "/F"]);
+// ── Run Configuration Commands ────────────────────────────────
 
-        #[cfg(target_os = "windows")]
-        cmd.creation_flags(0x08000000);
+/// The project's .mydevify/services.toml, or null if it has none
+#[tauri::command]
+fn get_run_config(project: String) -> Result<Option<run_config::RunConfigInfo>, String> {
+    run_config::describe(&project)
+}
 
-        let _ = cmd.output();
-    } else {
-        // Kill the process group on Unix
-        let _ = std::process::Command::new("kill")
-            .args(["-9", &format!("-{}", pid)])
-            .output();
-        // Fallback: kill just the process
-        let _ = std::process::Command::new("kill")
-            .args(["-9", &pid.to_string()])
-            .output();
-    }
+/// Start every service in services.toml in dependency order. The preview
+/// service runs as the dev server; returns once all are ready.
+#[tauri::command]
+async fn start_run_config(
+    app_handle: tauri::AppHandle,
+    project: String,
+) -> Result<run_config::RunConfigStarted, String> {
+    tauri::async_runtime::spawn_blocking(move || run_config::start(&app_handle, &project))
+        .await
+        .map_err(|e| e.to_string())?
 }
 
-/// Stop the dev server — exposed to frontend
 #[tauri::command]
-fn stop_dev_server() -> Result<(), String> {
-    stop_dev_server_internal();
-    Ok(())
+async fn stop_run_config(project: String) -> Result<(), String> {
+    tauri::async_runtime::spawn_blocking(move || run_config::stop(&project))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+// ── Project Services Commands ─────────────────────────────────
+
+#[tauri::command]
+fn get_project_services(project: String) -> Result<Vec<supervisor::ServiceDefinition>, String> {
+    supervisor::get_definitions(&project)
+}
+
+#[tauri::command]
+fn save_project_services(
+    project: String,
+    services: Vec<supervisor::ServiceDefinition>,
+) -> Result<Vec<supervisor::ServiceDefinition>, String> {
+    supervisor::save_definitions(&project, services)
+}
+
+#[tauri::command]
+fn start_service(
+    app_handle: tauri::AppHandle,
+    project: String,
+    name: String,
+) -> Result<supervisor::ServiceStatus, String> {
+    supervisor::start(&app_handle, &project, &name)
+}
+
+/// Async because stopping waits for the process group to exit
+#[tauri::command]
+async fn stop_service(project: String, name: String) -> Result<(), String> {
+    tauri::async_runtime::spawn_blocking(move || supervisor::stop(&project, &name))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+/// Async because it waits (up to 5s) for the old process to exit
+#[tauri::command]
+async fn restart_service(
+    app_handle: tauri::AppHandle,
+    project: String,
+    name: String,
+) -> Result<supervisor::ServiceStatus, String> {
+    tauri::async_runtime::spawn_blocking(move || supervisor::restart(&app_handle, &project, &name))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+#[tauri::command]
+fn list_services(project: String) -> Result<Vec<supervisor::ServiceStatus>, String> {
+    supervisor::list(&project)
+}
+
+#[tauri::command]
+fn get_service_logs(
+    project: String,
+    name: String,
+    tail: Option<usize>,
+) -> Result<Vec<supervisor::ServiceLogLine>, String> {
+    supervisor::get_logs(&project, &name, tail)
+}
+
+// ── Command History Commands ──────────────────────────────────
+
+#[tauri::command]
+fn query_command_history(
+    project: String,
+    query: Option<command_history::HistoryQuery>,
+) -> Result<Vec<command_history::HistoryEntry>, String> {
+    let root = project_root_for(&PathBuf::from(&project));
+    command_history::query(&root, &query.unwrap_or_default())
+}
+
+#[tauri::command]
+fn clear_command_history(project: String) -> Result<(), String> {
+    command_history::clear(&project_root_for(&PathBuf::from(&project)))
+}
+
+#[tauri::command]
+fn get_command_history_config() -> command_history::HistoryConfig {
+    command_history::get_config()
+}
+
+#[tauri::command]
+fn set_command_history_config(
+    config: command_history::HistoryConfig,
+) -> Result<command_history::HistoryConfig, String> {
+    command_history::set_config(config)
+}
+
+// ── Sandbox Commands ──────────────────────────────────────────
+
+#[tauri::command]
+fn get_sandbox_config() -> sandbox::SandboxConfig {
+    sandbox::get_config()
+}
+
+#[tauri::command]
+fn set_sandbox_config(config: sandbox::SandboxConfig) -> Result<sandbox::SandboxConfig, String> {
+    sandbox::set_config(config)
+}
+
+#[tauri::command]
+fn get_sandbox_support() -> sandbox::SandboxSupport {
+    sandbox::get_support()
+}
+
+// ── Shell Environment Commands ────────────────────────────────
+
+/// Show which shell environment is in use and where common tools resolve
+#[tauri::command]
+async fn get_shell_env_diagnostics() -> Result<shell_env::ShellEnvDiagnostics, String> {
+    tauri::async_runtime::spawn_blocking(shell_env::diagnose)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Re-run the login shell (e.g. after installing a new Node version)
+#[tauri::command]
+async fn reload_shell_env() -> Result<shell_env::ShellEnvDiagnostics, String> {
+    tauri::async_runtime::spawn_blocking(|| {
+        shell_env::reload();
+        shell_env::diagnose()
+    })
+    .await
+    .map_err(|e| e.to_string())
 }
 
 // ── Scheduled Tasks IPC Commands ──────────────────────────────
@@ -577,6 +819,10 @@ pub fn run() {
         .plugin(tauri_plugin_http::init())
         .plugin(tauri_plugin_sql::Builder::default().build())
         .setup(|app| {
+            // Kill dev servers left running by a previous session that crashed
+            std::thread::spawn(process_registry::reap_orphans);
+            // Resolve the login-shell PATH before the first command needs it
+            shell_env::warm_up();
             // Start the background scheduler on app launch
             scheduler::start_scheduler(app.handle().clone());
             Ok(())
@@ -592,11 +838,60 @@ pub fn run() {
             execute_command,
             resolve_path,
             start_preview_server,
+            start_preview_proxy,
             stop_preview_server,
             get_preview_port,
+            list_preview_servers,
+            list_mock_routes,
+            get_preview_requests,
+            clear_preview_requests,
+            get_preview_console,
+            clear_preview_console,
+            check_links,
+            audit_html,
+            get_local_ca,
+            export_local_ca,
+            list_lan_interfaces,
+            start_lan_share,
+            stop_lan_share,
+            get_lan_share,
             start_dev_server,
             stop_dev_server,
             get_dev_server_output,
+            get_dev_server_diagnostics,
+            // Dependency installs
+            get_install_status,
+            start_install,
+            cancel_install,
+            // Production builds
+            build_project,
+            cancel_build,
+            get_last_build,
+            analyze_bundle,
+            // Run configuration
+            get_run_config,
+            start_run_config,
+            stop_run_config,
+            // Project services
+            get_project_services,
+            save_project_services,
+            start_service,
+            stop_service,
+            restart_service,
+            list_services,
+            get_service_logs,
+            // Command history
+            query_command_history,
+            clear_command_history,
+            get_command_history_config,
+            set_command_history_config,
+            // Sandbox
+            get_sandbox_config,
+            set_sandbox_config,
+            get_sandbox_support,
+            // Shell environment
+            get_shell_env_diagnostics,
+            reload_shell_env,
             // Scheduled tasks
             create_task,
             update_task,
@@ -605,6 +900,14 @@ pub fn run() {
             get_tasks,
             run_task_now
         ])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application")
+        .run(|_app_handle, event| {
+            // Don't leave dev servers holding ports after the window closes
+            if let tauri::RunEvent::Exit = event {
+                dev_server::stop_internal();
+                server::stop_all();
+                process_registry::reap_all();
+            }
+        });
 }
\ No newline at end of file
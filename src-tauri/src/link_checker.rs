@@ -0,0 +1,421 @@
+// ── Link Checker — Broken Links in Static Sites ───────────────
+//
+// Crawls a site from `/` the way the preview server would serve it (same
+// routing rules, clean URLs, redirects and 404 page — but no guessed SPA
+// fallback, only rewrites the project declares), following links and
+// asset references in HTML (`href`, `src`, `srcset`, `poster`, inline
+// and embedded CSS) and in stylesheets (`url()`, `@import`). Reports:
+//
+//   - broken links: pages linked from the site that don't resolve
+//   - missing assets: images, scripts, stylesheets or fonts that don't
+//   - case mismatches: references whose case differs from the file on
+//     disk — fine on Windows and macOS, a 404 on Linux hosting
+//   - orphaned pages: HTML files nothing links to
+//
+// External URLs are not fetched.
+
+use crate::routing_rules::{Resolution, RoutingRules};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::Serialize;
+use std::collections::{HashSet, VecDeque};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Stops runaway crawls of generated sites
+const MAX_FILES: usize = 5_000;
+/// Redirect hops followed before a link counts as broken
+const MAX_REDIRECTS: usize = 5;
+/// Directories never reported as holding orphaned pages
+const SKIPPED_DIRS: &[&str] = &["node_modules", ".git", ".mydevify"];
+
+// ── Data Model ────────────────────────────────────────────────
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LinkIssueKind {
+    BrokenLink,
+    MissingAsset,
+    CaseMismatch,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LinkIssue {
+    pub kind: LinkIssueKind,
+    /// File containing the reference, relative to the root
+    pub file: String,
+    pub line: usize,
+    /// The reference as written
+    pub reference: String,
+    /// URL path it resolves to
+    pub path: String,
+    /// Why it's broken (status, redirect loop, ...)
+    pub detail: String,
+    /// For case mismatches: the path as it is on disk
+    pub actual_path: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LinkReport {
+    pub pages_crawled: usize,
+    pub assets_checked: usize,
+    pub issues: Vec<LinkIssue>,
+    /// HTML files nothing links to, relative to the root
+    pub orphaned_pages: Vec<String>,
+}
+
+/// A reference found in a file
+struct Reference {
+    value: String,
+    line: usize,
+    /// `<a href>` / `<iframe src>` — a page rather than an asset
+    is_link: bool,
+}
+
+// ── Extraction ────────────────────────────────────────────────
+
+static TAG: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?is)<([a-z][a-z0-9]*)\b([^>]*)>").unwrap());
+static ATTR: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r#"(?is)\b(href|src|srcset|poster|data)\s*=\s*(?:"([^"]*)"|'([^']*)'|([^\s"'>]+))"#).unwrap()
+});
+static CSS_URL: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r#"(?i)url\(\s*(?:"([^"]*)"|'([^']*)'|([^)\s]*))\s*\)|@import\s+(?:"([^"]*)"|'([^']*)')"#).unwrap()
+});
+static COMMENT: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?s)<!--.*?-->").unwrap());
+
+fn line_at(content: &str, offset: usize) -> usize {
+    content[..offset].matches('\n').count() + 1
+}
+
+fn decode_entities(value: &str) -> String {
+    value
+        .replace("&amp;", "&")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&apos;", "'")
+}
+
+/// Blank out comments, keeping offsets (and so line numbers) intact
+fn without_comments(html: &str) -> String {
+    COMMENT
+        .replace_all(html, |caps: &regex::Captures| {
+            caps[0].chars().map(|c| if c == '\n' { '\n' } else { ' ' }).collect::<String>()
+        })
+        .into_owned()
+}
+
+fn html_references(html: &str) -> Vec<Reference> {
+    let html = without_comments(html);
+    let mut refs = Vec::new();
+
+    for tag in TAG.captures_iter(&html) {
+        let name = tag[1].to_lowercase();
+        let attrs = tag.get(2).unwrap();
+        let line = line_at(&html, tag.get(0).unwrap().start());
+
+        // <link rel="preconnect/dns-prefetch"> point at origins, not files
+        if name == "link" {
+            let lower = attrs.as_str().to_lowercase();
+            if lower.contains("preconnect") || lower.contains("dns-prefetch") {
+                continue;
+            }
+        }
+
+        for attr in ATTR.captures_iter(attrs.as_str()) {
+            let attr_name = attr[1].to_lowercase();
+            // `data=` only means a URL on <object>
+            if attr_name == "data" && name != "object" {
+                continue;
+            }
+            let value = attr.get(2).or(attr.get(3)).or(attr.get(4)).map(|m| m.as_str()).unwrap_or("");
+            let value = decode_entities(value.trim());
+            let is_link = (attr_name == "href" && (name == "a" || name == "area")) || name == "iframe";
+
+            if attr_name == "srcset" {
+                // "a.png 1x, b.png 2x" — the URL is the first word of each candidate
+                for candidate in value.split(',') {
+                    if let Some(url) = candidate.split_whitespace().next() {
+                        refs.push(Reference { value: url.to_string(), line, is_link: false });
+                    }
+                }
+            } else {
+                refs.push(Reference { value, line, is_link });
+            }
+        }
+    }
+
+    // style="" attributes and <style> blocks
+    refs.extend(css_references(&html));
+    refs
+}
+
+fn css_references(css: &str) -> Vec<Reference> {
+    CSS_URL
+        .captures_iter(css)
+        .filter_map(|caps| {
+            let value = (1..=5).find_map(|i| caps.get(i)).map(|m| m.as_str().trim())?;
+            Some(Reference {
+                value: decode_entities(value),
+                line: line_at(css, caps.get(0).unwrap().start()),
+                is_link: false,
+            })
+        })
+        .collect()
+}
+
+// ── Resolution ────────────────────────────────────────────────
+
+/// URL path a reference points to from a page at `base` (a URL path), or
+/// None for external, data, anchor-only and script URLs
+fn to_url_path(base: &str, reference: &str) -> Option<String> {
+    let reference = reference.trim();
+    let lower = reference.to_lowercase();
+    if reference.is_empty()
+        || reference.starts_with('#')
+        || reference.starts_with("//")
+        || reference.contains("{{")
+        || lower.contains("://")
+        || ["mailto:", "tel:", "javascript:", "data:", "blob:", "sms:"]
+            .iter()
+            .any(|scheme| lower.starts_with(scheme))
+    {
+        return None;
+    }
+
+    let path = reference.split(['#', '?']).next().unwrap_or("");
+    if path.is_empty() {
+        // "?page=2" points back at the same page
+        return Some(base.to_string());
+    }
+
+    let joined = if path.starts_with('/') {
+        path.to_string()
+    } else {
+        let dir = &base[..base.rfind('/').map(|i| i + 1).unwrap_or(0)];
+        format!("{}{}", dir, path)
+    };
+
+    // Normalize `.` and `..` segments
+    let mut segments: Vec<&str> = Vec::new();
+    for segment in joined.split('/') {
+        match segment {
+            "" | "." => {}
+            ".." => {
+                segments.pop();
+            }
+            s => segments.push(s),
+        }
+    }
+    let mut normalized = format!("/{}", segments.join("/"));
+    if joined.ends_with('/') && normalized != "/" {
+        normalized.push('/');
+    }
+    Some(normalized)
+}
+
+enum Outcome {
+    /// Canonical file that answers, and the URL path it was reached at
+    Found { file: PathBuf, url: String },
+    Broken(String),
+}
+
+/// Resolve a URL path like the preview server, following redirects
+fn resolve(rules: &RoutingRules, root: &Path, url: &str) -> Outcome {
+    let mut url = url.to_string();
+    for _ in 0..=MAX_REDIRECTS {
+        match rules.resolve(root, &url, None) {
+            Resolution::File { path, status: 200 } => return Outcome::Found { file: path, url },
+            Resolution::File { status, .. } => return Outcome::Broken(format!("Serves the {} page", status)),
+            Resolution::NotFound => return Outcome::Broken("Not found".to_string()),
+            Resolution::Redirect { location, .. } => {
+                if location.contains("://") || location.starts_with("//") {
+                    return Outcome::Found { file: PathBuf::new(), url: location };
+                }
+                url = location.split(['#', '?']).next().unwrap_or("/").to_string();
+            }
+        }
+    }
+    Outcome::Broken(format!("More than {} redirects", MAX_REDIRECTS))
+}
+
+/// If `url` only exists on disk with different letter case, the path as
+/// it is on disk. Checks the path itself, `.html` and `/index.html`.
+fn case_mismatch(root: &Path, url: &str) -> Option<String> {
+    let rel = url.trim_matches('/');
+    let mut candidates = vec![rel.to_string()];
+    if !rel.is_empty() {
+        candidates.push(format!("{}.html", rel));
+        candidates.push(format!("{}/index.html", rel));
+    }
+
+    for candidate in &candidates {
+        match walk_case_insensitive(root, candidate) {
+            Some((actual, true)) => return Some(actual),
+            // Exists exactly as written
+            Some((_, false)) => return None,
+            None => {}
+        }
+    }
+    None
+}
+
+/// Follow `rel` from `root` ignoring case. Returns the on-disk path and
+/// whether any component differed, or None if there's no such path.
+fn walk_case_insensitive(root: &Path, rel: &str) -> Option<(String, bool)> {
+    let mut dir = root.to_path_buf();
+    let mut actual = Vec::new();
+    let mut mismatched = false;
+    for component in rel.split('/').filter(|c| !c.is_empty()) {
+        let names: Vec<String> = fs::read_dir(&dir)
+            .ok()?
+            .flatten()
+            .map(|e| e.file_name().to_string_lossy().to_string())
+            .collect();
+        let name = if names.iter().any(|n| n == component) {
+            component.to_string()
+        } else {
+            mismatched = true;
+            names.into_iter().find(|n| n.eq_ignore_ascii_case(component))?
+        };
+        dir.push(&name);
+        actual.push(name);
+    }
+    Some((format!("/{}", actual.join("/")), mismatched))
+}
+
+fn relative(root: &Path, file: &Path) -> String {
+    file.strip_prefix(root)
+        .unwrap_or(file)
+        .to_string_lossy()
+        .replace('\\', "/")
+}
+
+fn is_html(path: &Path) -> bool {
+    path.extension()
+        .map(|e| e.eq_ignore_ascii_case("html") || e.eq_ignore_ascii_case("htm"))
+        .unwrap_or(false)
+}
+
+fn is_css(path: &Path) -> bool {
+    path.extension().map(|e| e.eq_ignore_ascii_case("css")).unwrap_or(false)
+}
+
+// ── Crawl ─────────────────────────────────────────────────────
+
+pub fn check(project_path: &str) -> Result<LinkReport, String> {
+    let root = PathBuf::from(project_path)
+        .canonicalize()
+        .map_err(|_| "Invalid project path".to_string())?;
+    if !root.is_dir() {
+        return Err("Invalid project path".to_string());
+    }
+    // A guessed SPA fallback would answer every missing page with index.html
+    let rules = RoutingRules::load(&root).without_spa_fallback();
+
+    let start = match resolve(&rules, &root, "/") {
+        Outcome::Found { file, url } if is_html(&file) => (file, url),
+        _ => return Err("No index.html to start from".to_string()),
+    };
+
+    let mut visited: HashSet<PathBuf> = HashSet::new();
+    let mut queue: VecDeque<(PathBuf, String)> = VecDeque::new();
+    let mut issues = Vec::new();
+    let mut pages_crawled = 0;
+    let mut assets_checked = 0;
+    visited.insert(start.0.clone());
+    queue.push_back(start);
+
+    while let Some((file, url)) = queue.pop_front() {
+        let Ok(content) = fs::read_to_string(&file) else {
+            continue;
+        };
+        let refs = if is_html(&file) {
+            pages_crawled += 1;
+            html_references(&content)
+        } else {
+            css_references(&content)
+        };
+
+        // Report each broken reference once per file
+        let mut seen: HashSet<String> = HashSet::new();
+        for reference in refs {
+            let Some(target) = to_url_path(&url, &reference.value) else {
+                continue;
+            };
+            if !seen.insert(target.clone()) {
+                continue;
+            }
+            if !reference.is_link {
+                assets_checked += 1;
+            }
+
+            let issue = |kind, detail: String, actual_path| LinkIssue {
+                kind,
+                file: relative(&root, &file),
+                line: reference.line,
+                reference: reference.value.clone(),
+                path: target.clone(),
+                detail,
+                actual_path,
+            };
+
+            if let Some(actual) = case_mismatch(&root, &target) {
+                issues.push(issue(
+                    LinkIssueKind::CaseMismatch,
+                    "Letter case differs from the file on disk".to_string(),
+                    Some(actual),
+                ));
+                continue;
+            }
+
+            match resolve(&rules, &root, &target) {
+                Outcome::Found { file: found, url: found_url } => {
+                    let crawlable = is_html(&found) || is_css(&found);
+                    if crawlable && visited.len() < MAX_FILES && visited.insert(found.clone()) {
+                        queue.push_back((found, found_url));
+                    }
+                }
+                Outcome::Broken(detail) => {
+                    let kind = if reference.is_link {
+                        LinkIssueKind::BrokenLink
+                    } else {
+                        LinkIssueKind::MissingAsset
+                    };
+                    issues.push(issue(kind, detail, None));
+                }
+            }
+        }
+    }
+
+    let mut orphaned_pages: Vec<String> = Vec::new();
+    collect_orphans(&root, &root, &visited, &mut orphaned_pages);
+    orphaned_pages.sort();
+
+    Ok(LinkReport {
+        pages_crawled,
+        assets_checked,
+        issues,
+        orphaned_pages,
+    })
+}
+
+/// HTML files under `dir` that the crawl never reached
+fn collect_orphans(root: &Path, dir: &Path, visited: &HashSet<PathBuf>, out: &mut Vec<String>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let name = entry.file_name().to_string_lossy().to_string();
+        if path.is_dir() {
+            if !name.starts_with('.') && !SKIPPED_DIRS.contains(&name.as_str()) {
+                collect_orphans(root, &path, visited, out);
+            }
+        } else if is_html(&path) && name != "404.html" {
+            let canonical = path.canonicalize().unwrap_or(path.clone());
+            if !visited.contains(&canonical) {
+                out.push(relative(root, &canonical));
+            }
+        }
+    }
+}
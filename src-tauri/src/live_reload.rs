@@ -0,0 +1,194 @@
+// ── Live Reload — Static Preview Server ───────────────────────
+//
+// Watches the preview root and tells connected pages to reload when
+// files change, so the iframe updates by itself after every AI write.
+// HTML responses get a small client script injected; it listens on an
+// SSE endpoint. When every changed file is a stylesheet, the client
+// swaps the matching <link> hrefs instead of reloading the page.
+
+use notify::{RecursiveMode, Watcher};
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::sync::{mpsc, Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::broadcast;
+
+/// SSE endpoint the injected client connects to
+pub const EVENTS_PATH: &str = "/__mydevify/livereload";
+/// Changes closer together than this are sent as one notice
+const DEBOUNCE: Duration = Duration::from_millis(150);
+/// Directories whose changes never affect the page
+const IGNORED_DIRS: &[&str] = &["node_modules", ".git", ".mydevify"];
+/// inotify watches one directory at a time against a per-user limit
+/// (fs.inotify.max_user_watches), so on Linux we walk the tree ourselves
+/// and leave out ignored directories. Elsewhere recursive watching is
+/// native and cheap.
+const WATCH_PER_DIR: bool = cfg!(target_os = "linux");
+
+// ── Data Model ────────────────────────────────────────────────
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ReloadNotice {
+    /// Reload the whole page
+    Reload { paths: Vec<String> },
+    /// Only stylesheets changed — swap them in place
+    Css { paths: Vec<String> },
+    /// The server is stopping; end the event stream
+    #[serde(skip)]
+    Shutdown,
+}
+
+/// Watcher plus the channel connected pages subscribe to.
+/// Dropping it stops watching.
+pub struct LiveReload {
+    tx: broadcast::Sender<ReloadNotice>,
+    /// The debounce thread only holds a weak reference, to watch new directories
+    _watcher: Arc<Mutex<notify::RecommendedWatcher>>,
+}
+
+impl LiveReload {
+    /// Start watching `root` recursively
+    pub fn watch(root: &Path) -> Result<Self, String> {
+        let (tx, _) = broadcast::channel(16);
+        let (raw_tx, raw_rx) = mpsc::channel::<PathBuf>();
+
+        let watcher = notify::recommended_watcher(move |res: notify::Result<notify::Event>| {
+            if let Ok(event) = res {
+                if event.kind.is_access() {
+                    return;
+                }
+                for path in event.paths {
+                    let _ = raw_tx.send(path);
+                }
+            }
+        })
+        .map_err(|e| format!("Failed to start file watcher: {}", e))?;
+        let watcher = Arc::new(Mutex::new(watcher));
+        watch_tree(&mut watcher.lock().unwrap_or_else(|e| e.into_inner()), root)
+            .map_err(|e| format!("Failed to watch {}: {}", root.display(), e))?;
+
+        // Debounce thread — exits when the watcher (and its sender) is dropped
+        let notice_tx = tx.clone();
+        let root = root.to_path_buf();
+        let weak_watcher = Arc::downgrade(&watcher);
+        std::thread::spawn(move || {
+            while let Ok(first) = raw_rx.recv() {
+                let mut changed = vec![first];
+                while let Ok(path) = raw_rx.recv_timeout(DEBOUNCE) {
+                    changed.push(path);
+                }
+                if WATCH_PER_DIR {
+                    let new_dirs = changed.iter().filter(|p| p.is_dir() && !is_ignored(&root, p));
+                    for dir in new_dirs {
+                        if let Some(watcher) = weak_watcher.upgrade() {
+                            let _ = watch_tree(&mut watcher.lock().unwrap_or_else(|e| e.into_inner()), dir);
+                        }
+                    }
+                }
+                if let Some(notice) = classify(&root, changed) {
+                    // No subscribers is fine — nobody has the page open
+                    let _ = notice_tx.send(notice);
+                }
+            }
+        });
+
+        Ok(Self { tx, _watcher: watcher })
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<ReloadNotice> {
+        self.tx.subscribe()
+    }
+
+    /// Push a notice to every connected page
+    pub fn notify(&self, notice: ReloadNotice) {
+        let _ = self.tx.send(notice);
+    }
+}
+
+/// Watch `dir` — recursively, or directory by directory without the
+/// ignored ones (see WATCH_PER_DIR). Watching a directory twice is harmless.
+fn watch_tree(watcher: &mut notify::RecommendedWatcher, dir: &Path) -> notify::Result<()> {
+    if !WATCH_PER_DIR {
+        return watcher.watch(dir, RecursiveMode::Recursive);
+    }
+    watcher.watch(dir, RecursiveMode::NonRecursive)?;
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Ok(());
+    };
+    for entry in entries.flatten() {
+        // file_type() doesn't follow symlinks, so link cycles aren't walked
+        let is_dir = entry.file_type().is_ok_and(|t| t.is_dir());
+        if is_dir && !IGNORED_DIRS.iter().any(|d| entry.file_name() == *d) {
+            watch_tree(watcher, &entry.path())?;
+        }
+    }
+    Ok(())
+}
+
+fn is_ignored(root: &Path, path: &Path) -> bool {
+    match path.strip_prefix(root) {
+        Ok(rel) => rel.components().any(|c| IGNORED_DIRS.iter().any(|d| c.as_os_str() == *d)),
+        Err(_) => true,
+    }
+}
+
+/// Turn a batch of changed paths into a notice (None if all were ignored)
+fn classify(root: &Path, changed: Vec<PathBuf>) -> Option<ReloadNotice> {
+    let mut paths: Vec<String> = changed
+        .iter()
+        .filter(|p| !is_ignored(root, p))
+        .filter_map(|p| p.strip_prefix(root).ok())
+        .map(|rel| rel.to_string_lossy().replace('\\', "/"))
+        .collect();
+    paths.sort();
+    paths.dedup();
+
+    if paths.is_empty() {
+        return None;
+    }
+    if paths.iter().all(|p| p.to_lowercase().ends_with(".css")) {
+        Some(ReloadNotice::Css { paths })
+    } else {
+        Some(ReloadNotice::Reload { paths })
+    }
+}
+
+// ── Client Script ─────────────────────────────────────────────
+
+const CLIENT_SCRIPT: &str = r#"<script data-mydevify-livereload>
+(function () {
+  if (window.__mydevifyLiveReload) return;
+  window.__mydevifyLiveReload = true;
+  var source = new EventSource("/__mydevify/livereload");
+  source.onmessage = function (e) {
+    var notice;
+    try { notice = JSON.parse(e.data); } catch (_) { return; }
+    if (notice.kind !== "css") { location.reload(); return; }
+    var links = document.querySelectorAll('link[rel="stylesheet"]');
+    var swapped = false;
+    links.forEach(function (link) {
+      var url = new URL(link.href, location.href);
+      if (url.origin !== location.origin) return;
+      var path = url.pathname.replace(/^\//, "");
+      var matches = notice.paths.some(function (p) { return path === p || path.endsWith("/" + p); });
+      if (!matches) return;
+      url.searchParams.set("__mydevify", Date.now());
+      link.href = url.pathname + url.search;
+      swapped = true;
+    });
+    // The stylesheet may be @imported or built from elsewhere — fall back to a reload
+    if (!swapped) location.reload();
+  };
+})();
+</script>"#;
+
+/// Insert the client script before </body> (or at the end if there's none)
+pub fn inject_client(html: &str) -> String {
+    // ASCII-only lowercasing keeps byte offsets valid for slicing `html`
+    let lower = html.to_ascii_lowercase();
+    match lower.rfind("</body>") {
+        Some(pos) => format!("{}{}{}", &html[..pos], CLIENT_SCRIPT, &html[pos..]),
+        None => format!("{}{}", html, CLIENT_SCRIPT),
+    }
+}
@@ -0,0 +1,205 @@
+// ── Local Certificate Authority — HTTPS Preview ───────────────
+//
+// Service workers, `crypto.subtle`, geolocation and secure cookies only
+// behave like production in a secure context. For HTTPS previews we keep
+// a local CA in ~/.mydevify/data/ca/ (created on first use, reused after)
+// and issue a short-lived leaf certificate for localhost each time a
+// server starts. Users can export the CA certificate and add it to their
+// OS or browser trust store once; every later leaf chains to it.
+//
+// Only the CA key and certificate are persisted. Leaves are signed by an
+// in-memory copy of the CA rebuilt from the same key and subject, which
+// yields the same issuer name and key identifier as the trusted file.
+
+use rcgen::{
+    BasicConstraints, CertificateParams, DistinguishedName, DnType, ExtendedKeyUsagePurpose,
+    IsCa, KeyPair, KeyUsagePurpose, SanType, SerialNumber,
+};
+use rustls::pki_types::{PrivateKeyDer, PrivatePkcs8KeyDer};
+use serde::Serialize;
+use std::fs;
+use std::net::IpAddr;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+const CA_COMMON_NAME: &str = "Mydevify Local Development CA";
+const CA_VALID_YEARS: i64 = 10;
+/// Leaves live for one preview session; a month covers long-running ones
+const LEAF_VALID_DAYS: i64 = 30;
+
+/// Serializes CA creation so two servers starting at once share one CA
+static CA_LOCK: Mutex<()> = Mutex::new(());
+
+// ── Data Model ────────────────────────────────────────────────
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LocalCaInfo {
+    /// PEM file to import into a trust store
+    pub certificate_path: String,
+    pub certificate_pem: String,
+}
+
+struct LocalCa {
+    key: KeyPair,
+    /// In-memory issuer rebuilt from the persisted key
+    issuer: rcgen::Certificate,
+    pem: String,
+}
+
+fn get_ca_dir() -> PathBuf {
+    let home = dirs_next::home_dir().unwrap_or_else(|| PathBuf::from("."));
+    let dir = home.join(".mydevify").join("data").join("ca");
+    fs::create_dir_all(&dir).ok();
+    dir
+}
+
+fn cert_path() -> PathBuf {
+    get_ca_dir().join("mydevify-ca.pem")
+}
+
+fn key_path() -> PathBuf {
+    get_ca_dir().join("mydevify-ca.key")
+}
+
+/// (year, month, day) in UTC, `days` from today
+fn ymd_from_today(days: i64) -> (i32, u8, u8) {
+    use chrono::Datelike;
+    let date = (chrono::Utc::now() + chrono::Duration::days(days)).date_naive();
+    (date.year(), date.month() as u8, date.day() as u8)
+}
+
+/// Validity window from `from_days` to `to_days` relative to today
+fn set_validity(params: &mut CertificateParams, from_days: i64, to_days: i64) {
+    let (y, m, d) = ymd_from_today(from_days);
+    params.not_before = rcgen::date_time_ymd(y, m, d);
+    let (y, m, d) = ymd_from_today(to_days);
+    params.not_after = rcgen::date_time_ymd(y, m, d);
+}
+
+fn random_serial() -> SerialNumber {
+    let mut bytes = uuid::Uuid::new_v4().into_bytes();
+    // Keep the DER integer positive
+    bytes[0] &= 0x7f;
+    SerialNumber::from_slice(&bytes)
+}
+
+fn ca_params() -> CertificateParams {
+    let mut params = CertificateParams::default();
+    let mut name = DistinguishedName::new();
+    name.push(DnType::CommonName, CA_COMMON_NAME);
+    name.push(DnType::OrganizationName, "Mydevify");
+    params.distinguished_name = name;
+    params.is_ca = IsCa::Ca(BasicConstraints::Constrained(0));
+    params.key_usages = vec![
+        KeyUsagePurpose::KeyCertSign,
+        KeyUsagePurpose::CrlSign,
+        KeyUsagePurpose::DigitalSignature,
+    ];
+    params
+}
+
+// ── CA Lifecycle ──────────────────────────────────────────────
+
+/// Load the persisted CA, creating it on first use
+fn load_or_create() -> Result<LocalCa, String> {
+    let _guard = CA_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+
+    if let (Ok(key_pem), Ok(pem)) = (fs::read_to_string(key_path()), fs::read_to_string(cert_path())) {
+        if let Ok(key) = KeyPair::from_pem(&key_pem) {
+            let issuer = ca_params()
+                .self_signed(&key)
+                .map_err(|e| format!("Failed to load local CA: {}", e))?;
+            return Ok(LocalCa { key, issuer, pem });
+        }
+    }
+
+    let key = KeyPair::generate().map_err(|e| format!("Failed to generate CA key: {}", e))?;
+    let mut params = ca_params();
+    set_validity(&mut params, -1, 365 * CA_VALID_YEARS);
+    params.serial_number = Some(random_serial());
+    let issuer = params
+        .self_signed(&key)
+        .map_err(|e| format!("Failed to create local CA: {}", e))?;
+    let pem = issuer.pem();
+
+    write_private(&key_path(), &key.serialize_pem())?;
+    fs::write(cert_path(), &pem).map_err(|e| format!("Failed to save CA certificate: {}", e))?;
+
+    Ok(LocalCa { key, issuer, pem })
+}
+
+/// Write a file readable only by the current user
+fn write_private(path: &Path, content: &str) -> Result<(), String> {
+    fs::write(path, content).map_err(|e| format!("Failed to save CA key: {}", e))?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(path, fs::Permissions::from_mode(0o600))
+            .map_err(|e| format!("Failed to protect CA key: {}", e))?;
+    }
+    Ok(())
+}
+
+/// Location and contents of the CA certificate (creating the CA if needed)
+pub fn info() -> Result<LocalCaInfo, String> {
+    let ca = load_or_create()?;
+    Ok(LocalCaInfo {
+        certificate_path: cert_path().to_string_lossy().to_string(),
+        certificate_pem: ca.pem,
+    })
+}
+
+/// Copy the CA certificate to a user-chosen file
+pub fn export(destination: &str) -> Result<(), String> {
+    let ca = load_or_create()?;
+    fs::write(destination, ca.pem).map_err(|e| format!("Failed to export certificate: {}", e))
+}
+
+// ── Leaf Certificates ─────────────────────────────────────────
+
+/// Issue a fresh leaf for localhost (plus `extra_ips`) and build a TLS
+/// server config around it.
+pub fn server_config(extra_ips: &[IpAddr]) -> Result<Arc<rustls::ServerConfig>, String> {
+    let ca = load_or_create()?;
+
+    let mut params = CertificateParams::default();
+    let mut name = DistinguishedName::new();
+    name.push(DnType::CommonName, "localhost");
+    params.distinguished_name = name;
+    params.subject_alt_names = vec![
+        SanType::DnsName("localhost".try_into().map_err(|e| format!("{}", e))?),
+        SanType::IpAddress(IpAddr::from([127, 0, 0, 1])),
+        SanType::IpAddress(IpAddr::from([0u16, 0, 0, 0, 0, 0, 0, 1])),
+    ];
+    params
+        .subject_alt_names
+        .extend(extra_ips.iter().map(|ip| SanType::IpAddress(*ip)));
+    params.is_ca = IsCa::ExplicitNoCa;
+    params.key_usages = vec![KeyUsagePurpose::DigitalSignature, KeyUsagePurpose::KeyEncipherment];
+    params.extended_key_usages = vec![ExtendedKeyUsagePurpose::ServerAuth];
+    params.use_authority_key_identifier_extension = true;
+    set_validity(&mut params, -1, LEAF_VALID_DAYS);
+    // Browsers reject two certificates with the same issuer and serial
+    params.serial_number = Some(random_serial());
+
+    let leaf_key = KeyPair::generate().map_err(|e| format!("Failed to generate key: {}", e))?;
+    let leaf = params
+        .signed_by(&leaf_key, &ca.issuer, &ca.key)
+        .map_err(|e| format!("Failed to issue certificate: {}", e))?;
+
+    // The CA itself comes from the client's trust store, not the chain
+    let chain = vec![leaf.der().clone()];
+    let key = PrivateKeyDer::Pkcs8(PrivatePkcs8KeyDer::from(leaf_key.serialize_der()));
+
+    let mut config = rustls::ServerConfig::builder_with_provider(Arc::new(
+        rustls::crypto::ring::default_provider(),
+    ))
+    .with_safe_default_protocol_versions()
+    .map_err(|e| e.to_string())?
+    .with_no_client_auth()
+    .with_single_cert(chain, key)
+    .map_err(|e| format!("Invalid certificate: {}", e))?;
+    config.alpn_protocols = vec![b"http/1.1".to_vec()];
+
+    Ok(Arc::new(config))
+}
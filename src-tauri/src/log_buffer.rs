@@ -0,0 +1,140 @@
+// ── Log Buffer — Cursor-Based Ring Buffer ─────────────────────
+//
+// Keeps the last N lines of a process's output, each tagged with its
+// stream, a timestamp and a sequence number that only ever grows.
+// Readers pass the last sequence number they saw and get everything
+// newer, so the UI and the AI can both follow the same output without
+// stealing lines from each other. Lines are stored raw; ANSI colour
+// is stripped per read when the caller asks for it.
+
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+/// Longer lines (minified bundles, base64 blobs) are cut to this many chars
+const MAX_LINE_CHARS: usize = 4_000;
+
+// ── Data Model ────────────────────────────────────────────────
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LogStream {
+    Stdout,
+    Stderr,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LogLine {
+    pub seq: u64,
+    pub stream: LogStream,
+    pub text: String,
+    pub timestamp: String, // ISO 8601 string
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct LogReadOptions {
+    /// Return lines with seq greater than this (None = from the oldest kept)
+    pub since: Option<u64>,
+    /// Max lines to return, oldest first (None = all available)
+    pub limit: Option<usize>,
+    /// Remove ANSI colour codes from the returned text
+    #[serde(default)]
+    pub strip_ansi: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LogRead {
+    pub lines: Vec<LogLine>,
+    /// Pass back as `since` on the next read
+    pub cursor: u64,
+    /// Lines newer than `since` that were evicted before this read
+    pub dropped: u64,
+}
+
+// ── Buffer ────────────────────────────────────────────────────
+
+pub struct LogBuffer {
+    lines: VecDeque<LogLine>,
+    capacity: usize,
+    /// Seq of the last line pushed (0 = nothing yet)
+    last_seq: u64,
+}
+
+impl LogBuffer {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            lines: VecDeque::new(),
+            capacity: capacity.max(1),
+            last_seq: 0,
+        }
+    }
+
+    pub fn push(&mut self, stream: LogStream, text: &str) -> u64 {
+        self.last_seq += 1;
+        let text = if text.chars().count() > MAX_LINE_CHARS {
+            let mut cut: String = text.chars().take(MAX_LINE_CHARS).collect();
+            cut.push('…');
+            cut
+        } else {
+            text.to_string()
+        };
+
+        while self.lines.len() >= self.capacity {
+            self.lines.pop_front();
+        }
+        self.lines.push_back(LogLine {
+            seq: self.last_seq,
+            stream,
+            text,
+            timestamp: Utc::now().to_rfc3339(),
+        });
+        self.last_seq
+    }
+
+    pub fn read(&self, options: &LogReadOptions) -> LogRead {
+        let since = options.since.unwrap_or(0);
+        let oldest = self.lines.front().map(|l| l.seq).unwrap_or(self.last_seq + 1);
+        let dropped = if options.since.is_some() {
+            oldest.saturating_sub(since + 1)
+        } else {
+            0
+        };
+
+        let mut lines: Vec<LogLine> = self
+            .lines
+            .iter()
+            .filter(|l| l.seq > since)
+            .take(options.limit.unwrap_or(usize::MAX))
+            .cloned()
+            .collect();
+
+        if options.strip_ansi {
+            for line in &mut lines {
+                line.text = crate::strip_ansi_codes(&line.text);
+            }
+        }
+
+        // A cursor from before a clear (or from the future) restarts at the end
+        let cursor = lines.last().map(|l| l.seq).unwrap_or(since.min(self.last_seq));
+        LogRead { lines, cursor, dropped }
+    }
+
+    /// Last `count` lines as plain text
+    pub fn tail(&self, count: usize) -> Vec<String> {
+        let skip = self.lines.len().saturating_sub(count);
+        self.lines.iter().skip(skip).map(|l| l.text.clone()).collect()
+    }
+
+    /// Drop all lines. Sequence numbers keep counting so cursors held
+    /// by readers stay meaningful.
+    pub fn clear(&mut self) {
+        self.lines.clear();
+    }
+
+    pub fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = capacity.max(1);
+        while self.lines.len() > self.capacity {
+            self.lines.pop_front();
+        }
+    }
+}
@@ -0,0 +1,389 @@
+// ── Mock API — Static Preview Server ──────────────────────────
+//
+// Static prototypes often `fetch('/api/...')` an API that doesn't exist
+// yet. The preview server answers those requests from mock definitions
+// in `.mydevify/mocks/*.json`, so the page behaves as if a backend were
+// there. Each file holds a list of routes (or `{ "routes": [...] }`):
+//
+//   { "method": "GET", "path": "/api/users/:id", "status": 200,
+//     "headers": { "x-total": "1" },
+//     "body": { "id": "{{params.id}}", "page": "{{query.page}}" },
+//     "delay_ms": 300, "error_rate": 0.1, "error_status": 503 }
+//
+// Paths use the same `:name` / `*` patterns as the routing rules; the
+// first matching route across files (sorted by name) wins. Strings in
+// the body can reference `{{params.x}}`, `{{query.x}}`, `{{method}}` and
+// `{{path}}`. Files are re-read whenever one is added, removed or edited,
+// so mocks change without restarting the server.
+
+use crate::request_log::ResolvedFile;
+use crate::routing_rules::Pattern;
+use axum::http::{header, HeaderName, HeaderValue, Method, StatusCode};
+use axum::response::{IntoResponse, Response};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime};
+
+/// Where mock definitions live, relative to the served root
+const MOCKS_DIR: &str = ".mydevify/mocks";
+/// Keeps a typo'd delay from hanging the page forever
+const MAX_DELAY: Duration = Duration::from_secs(30);
+
+// ── Data Model ────────────────────────────────────────────────
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+enum MockFile {
+    List(Vec<MockRouteConfig>),
+    Object { routes: Vec<MockRouteConfig> },
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct MockRouteConfig {
+    /// Any method when omitted or "*"
+    #[serde(default)]
+    method: Option<String>,
+    path: String,
+    #[serde(default)]
+    status: Option<u16>,
+    #[serde(default)]
+    headers: HashMap<String, String>,
+    #[serde(default)]
+    body: serde_json::Value,
+    #[serde(default)]
+    delay_ms: u64,
+    /// Chance (0–1) of answering with `error_status` instead
+    #[serde(default)]
+    error_rate: f64,
+    #[serde(default)]
+    error_status: Option<u16>,
+    #[serde(default)]
+    error_body: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Clone)]
+struct MockRoute {
+    file: String,
+    /// Full path of the definition file, reported by the request log
+    source: PathBuf,
+    method: Option<Method>,
+    pattern: Pattern,
+    config: MockRouteConfig,
+}
+
+/// One mock route, for listing in the UI
+#[derive(Debug, Clone, Serialize)]
+pub struct MockRouteInfo {
+    pub file: String,
+    pub method: String,
+    pub path: String,
+    pub status: u16,
+    pub delay_ms: u64,
+    pub error_rate: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MockFileError {
+    pub file: String,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MockSummary {
+    pub routes: Vec<MockRouteInfo>,
+    /// Files that couldn't be parsed — their routes are ignored
+    pub errors: Vec<MockFileError>,
+}
+
+/// All mock routes of one served root
+#[derive(Debug, Default)]
+pub struct MockRoutes {
+    routes: Vec<MockRoute>,
+    errors: Vec<MockFileError>,
+}
+
+// ── Loading ───────────────────────────────────────────────────
+
+/// Mock definition files, sorted by name
+fn mock_files(root: &Path) -> Vec<PathBuf> {
+    let mut files: Vec<PathBuf> = fs::read_dir(root.join(MOCKS_DIR))
+        .map(|entries| {
+            entries
+                .flatten()
+                .map(|entry| entry.path())
+                .filter(|path| path.is_file() && path.extension().is_some_and(|ext| ext == "json"))
+                .collect()
+        })
+        .unwrap_or_default();
+    files.sort();
+    files
+}
+
+impl MockRoutes {
+    pub fn load(root: &Path) -> Self {
+        let mut mocks = Self::default();
+
+        for path in mock_files(root) {
+            let file = path
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_default();
+            let parsed = fs::read_to_string(&path)
+                .map_err(|e| e.to_string())
+                .and_then(|content| serde_json::from_str::<MockFile>(&content).map_err(|e| e.to_string()));
+
+            let configs = match parsed {
+                Ok(MockFile::List(routes)) | Ok(MockFile::Object { routes }) => routes,
+                Err(message) => {
+                    mocks.errors.push(MockFileError { file, message });
+                    continue;
+                }
+            };
+
+            for config in configs {
+                let method = match config.method.as_deref().map(str::trim) {
+                    None | Some("") | Some("*") => None,
+                    Some(m) => match Method::from_bytes(m.to_uppercase().as_bytes()) {
+                        Ok(method) => Some(method),
+                        Err(_) => {
+                            mocks.errors.push(MockFileError {
+                                file: file.clone(),
+                                message: format!("Invalid method \"{}\" for {}", m, config.path),
+                            });
+                            continue;
+                        }
+                    },
+                };
+                mocks.routes.push(MockRoute {
+                    file: file.clone(),
+                    source: path.clone(),
+                    method,
+                    pattern: Pattern::parse(&config.path),
+                    config,
+                });
+            }
+        }
+
+        mocks
+    }
+
+    pub fn summary(&self) -> MockSummary {
+        MockSummary {
+            routes: self
+                .routes
+                .iter()
+                .map(|route| MockRouteInfo {
+                    file: route.file.clone(),
+                    method: route.method.as_ref().map(|m| m.to_string()).unwrap_or_else(|| "*".to_string()),
+                    path: route.config.path.clone(),
+                    status: route.config.status.unwrap_or(200),
+                    delay_ms: route.config.delay_ms,
+                    error_rate: route.config.error_rate,
+                })
+                .collect(),
+            errors: self.errors.clone(),
+        }
+    }
+
+    /// Answer the request if a mock route matches it
+    pub async fn respond(&self, method: &Method, path: &str, query: Option<&str>) -> Option<Response> {
+        let (route, params) = self.routes.iter().find_map(|route| {
+            if route.method.as_ref().is_some_and(|m| m != method) {
+                return None;
+            }
+            route.pattern.matches(path).map(|params| (route, params))
+        })?;
+        let config = &route.config;
+
+        if config.delay_ms > 0 {
+            tokio::time::sleep(Duration::from_millis(config.delay_ms).min(MAX_DELAY)).await;
+        }
+
+        let vars = TemplateVars {
+            params,
+            query: parse_query(query),
+            method: method.to_string(),
+            path: path.to_string(),
+        };
+
+        let (status, body) = if config.error_rate > 0.0 && random_unit() < config.error_rate {
+            let body = config
+                .error_body
+                .clone()
+                .unwrap_or_else(|| serde_json::json!({ "error": "Injected mock error" }));
+            (config.error_status.unwrap_or(500), body)
+        } else {
+            (config.status.unwrap_or(200), config.body.clone())
+        };
+
+        let mut response = build_response(status, &config.headers, render(body, &vars));
+        response.extensions_mut().insert(ResolvedFile(route.source.clone()));
+        Some(response)
+    }
+}
+
+// ── Responses ─────────────────────────────────────────────────
+
+struct TemplateVars {
+    params: HashMap<String, String>,
+    query: HashMap<String, String>,
+    method: String,
+    path: String,
+}
+
+impl TemplateVars {
+    fn lookup(&self, name: &str) -> Option<&str> {
+        match name {
+            "method" => Some(&self.method),
+            "path" => Some(&self.path),
+            _ => {
+                if let Some(param) = name.strip_prefix("params.") {
+                    self.params.get(param).map(String::as_str)
+                } else {
+                    name.strip_prefix("query.").and_then(|q| self.query.get(q)).map(String::as_str)
+                }
+            }
+        }
+    }
+}
+
+/// Fill `{{...}}` placeholders in every string of the body. Unknown ones
+/// become empty, like a missing query parameter would on a real API.
+fn render(value: serde_json::Value, vars: &TemplateVars) -> serde_json::Value {
+    use serde_json::Value;
+    match value {
+        Value::String(s) => Value::String(render_str(&s, vars)),
+        Value::Array(items) => Value::Array(items.into_iter().map(|v| render(v, vars)).collect()),
+        Value::Object(map) => Value::Object(map.into_iter().map(|(k, v)| (k, render(v, vars))).collect()),
+        other => other,
+    }
+}
+
+fn render_str(template: &str, vars: &TemplateVars) -> String {
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        let Some(len) = rest[start + 2..].find("}}") else {
+            break;
+        };
+        out.push_str(&rest[..start]);
+        out.push_str(vars.lookup(rest[start + 2..start + 2 + len].trim()).unwrap_or(""));
+        rest = &rest[start + 2 + len + 2..];
+    }
+    out.push_str(rest);
+    out
+}
+
+fn parse_query(query: Option<&str>) -> HashMap<String, String> {
+    query
+        .unwrap_or("")
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+            (percent_decode(key), percent_decode(value))
+        })
+        .collect()
+}
+
+fn percent_decode(s: &str) -> String {
+    let bytes = s.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'+' => out.push(b' '),
+            b'%' if i + 2 < bytes.len() => {
+                let hex = std::str::from_utf8(&bytes[i + 1..i + 3]).ok();
+                match hex.and_then(|h| u8::from_str_radix(h, 16).ok()) {
+                    Some(b) => {
+                        out.push(b);
+                        i += 2;
+                    }
+                    None => out.push(b'%'),
+                }
+            }
+            b => out.push(b),
+        }
+        i += 1;
+    }
+    String::from_utf8_lossy(&out).to_string()
+}
+
+/// Uniform value in [0, 1) — error injection doesn't need a real RNG
+fn random_unit() -> f64 {
+    let bytes = uuid::Uuid::new_v4().into_bytes();
+    u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as f64 / (u32::MAX as f64 + 1.0)
+}
+
+fn build_response(status: u16, headers: &HashMap<String, String>, body: serde_json::Value) -> Response {
+    let status = StatusCode::from_u16(status).unwrap_or(StatusCode::OK);
+    let (content_type, bytes) = match body {
+        serde_json::Value::Null => (None, Vec::new()),
+        // Plain strings are sent as-is (HTML fragments, CSV, ...)
+        serde_json::Value::String(s) => (Some("text/plain; charset=utf-8"), s.into_bytes()),
+        other => (
+            Some("application/json"),
+            serde_json::to_vec_pretty(&other).unwrap_or_default(),
+        ),
+    };
+
+    let mut response = (status, bytes).into_response();
+    let response_headers = response.headers_mut();
+    if let Some(content_type) = content_type {
+        response_headers.insert(header::CONTENT_TYPE, HeaderValue::from_static(content_type));
+    }
+    response_headers.insert(header::CACHE_CONTROL, HeaderValue::from_static("no-store"));
+    for (name, value) in headers {
+        if let (Ok(name), Ok(value)) = (HeaderName::from_bytes(name.as_bytes()), HeaderValue::from_str(value)) {
+            response_headers.insert(name, value);
+        }
+    }
+    response
+}
+
+// ── Cache ─────────────────────────────────────────────────────
+
+/// Mocks for one served root, reloaded whenever a mock file changes
+pub struct MockCache {
+    root: PathBuf,
+    cached: Mutex<Option<CachedMocks>>,
+}
+
+struct CachedMocks {
+    /// Mock files and their modified times when the mocks were loaded
+    stamps: Vec<(PathBuf, Option<SystemTime>)>,
+    mocks: Arc<MockRoutes>,
+}
+
+impl MockCache {
+    pub fn new(root: &Path) -> Self {
+        Self {
+            root: root.to_path_buf(),
+            cached: Mutex::new(None),
+        }
+    }
+
+    pub fn current(&self) -> Arc<MockRoutes> {
+        let stamps: Vec<(PathBuf, Option<SystemTime>)> = mock_files(&self.root)
+            .into_iter()
+            .map(|path| {
+                let modified = fs::metadata(&path).and_then(|m| m.modified()).ok();
+                (path, modified)
+            })
+            .collect();
+
+        let mut cached = self.cached.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(entry) = cached.as_ref() {
+            if entry.stamps == stamps {
+                return entry.mocks.clone();
+            }
+        }
+        let mocks = Arc::new(MockRoutes::load(&self.root));
+        *cached = Some(CachedMocks { stamps, mocks: mocks.clone() });
+        mocks
+    }
+}
@@ -0,0 +1,128 @@
+// ── Listening Port Discovery ──────────────────────────────────
+//
+// Fallback for dev servers that never print a URL (Django, Rails,
+// custom Express apps with their own banner). On Linux we walk the
+// child's process tree, collect the socket inodes each process holds
+// (/proc/<pid>/fd → "socket:[inode]"), and match them against LISTEN
+// entries in /proc/<pid>/net/tcp{,6} to find the bound ports.
+// Other platforms return nothing and callers rely on output matching.
+
+/// All TCP ports the process tree rooted at `root_pid` is listening on,
+/// sorted and deduplicated.
+#[cfg(target_os = "linux")]
+pub fn listening_ports(root_pid: u32) -> Vec<u16> {
+    use std::collections::HashSet;
+
+    let mut inodes: HashSet<u64> = HashSet::new();
+    for pid in process_tree(root_pid) {
+        inodes.extend(socket_inodes(pid));
+    }
+    if inodes.is_empty() {
+        return Vec::new();
+    }
+
+    // Every process in the tree shares the root's network namespace,
+    // so its view of the socket tables is enough
+    let mut ports: Vec<u16> = ["tcp", "tcp6"]
+        .iter()
+        .filter_map(|table| {
+            std::fs::read_to_string(format!("/proc/{}/net/{}", root_pid, table)).ok()
+        })
+        .flat_map(|content| parse_listening(&content))
+        .filter(|(_, inode)| inodes.contains(inode))
+        .map(|(port, _)| port)
+        .collect();
+
+    ports.sort_unstable();
+    ports.dedup();
+    ports
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn listening_ports(_root_pid: u32) -> Vec<u16> {
+    Vec::new()
+}
+
+/// `root_pid` plus all of its descendants
+#[cfg(target_os = "linux")]
+fn process_tree(root_pid: u32) -> Vec<u32> {
+    use std::collections::HashMap;
+
+    let mut children: HashMap<u32, Vec<u32>> = HashMap::new();
+    if let Ok(entries) = std::fs::read_dir("/proc") {
+        for entry in entries.flatten() {
+            let pid = match entry.file_name().to_string_lossy().parse::<u32>() {
+                Ok(pid) => pid,
+                Err(_) => continue,
+            };
+            if let Some(ppid) = parent_pid(pid) {
+                children.entry(ppid).or_default().push(pid);
+            }
+        }
+    }
+
+    let mut tree = vec![root_pid];
+    let mut i = 0;
+    while i < tree.len() {
+        if let Some(kids) = children.get(&tree[i]) {
+            tree.extend(kids);
+        }
+        i += 1;
+    }
+    tree
+}
+
+/// Parent PID from /proc/<pid>/stat. The command name (field 2) can
+/// contain spaces and parens, so parse from after the last ')'.
+#[cfg(target_os = "linux")]
+fn parent_pid(pid: u32) -> Option<u32> {
+    let stat = std::fs::read_to_string(format!("/proc/{}/stat", pid)).ok()?;
+    let rest = &stat[stat.rfind(')')? + 1..];
+    // rest = " S 1234 ..." → state, then ppid
+    rest.split_whitespace().nth(1)?.parse().ok()
+}
+
+/// Inodes of all sockets a process has open
+#[cfg(target_os = "linux")]
+fn socket_inodes(pid: u32) -> Vec<u64> {
+    let entries = match std::fs::read_dir(format!("/proc/{}/fd", pid)) {
+        Ok(entries) => entries,
+        Err(_) => return Vec::new(),
+    };
+
+    entries
+        .flatten()
+        .filter_map(|entry| std::fs::read_link(entry.path()).ok())
+        .filter_map(|target| {
+            let target = target.to_string_lossy().to_string();
+            target
+                .strip_prefix("socket:[")?
+                .strip_suffix(']')?
+                .parse::<u64>()
+                .ok()
+        })
+        .collect()
+}
+
+/// Parse a /proc/net/tcp table into (port, inode) pairs for LISTEN sockets.
+/// Row format: `sl local_address rem_address st tx:rx tr:when retrnsmt uid timeout inode`
+#[cfg(target_os = "linux")]
+fn parse_listening(content: &str) -> Vec<(u16, u64)> {
+    const TCP_LISTEN: &str = "0A";
+
+    content
+        .lines()
+        .skip(1)
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            if fields.len() < 10 || fields[3] != TCP_LISTEN {
+                return None;
+            }
+            // local_address is "ADDR:PORT" with the port in hex
+            let port_hex = fields[1].rsplit(':').next()?;
+            let port = u16::from_str_radix(port_hex, 16).ok()?;
+            let inode = fields[9].parse::<u64>().ok()?;
+            Some((port, inode))
+        })
+        .collect()
+}
@@ -0,0 +1,242 @@
+// ── Process Registry — Long-Running Child Processes ───────────
+//
+// Dev servers and services are started in their own session (setsid),
+// so on Unix the child's PID is also its process group ID and the whole
+// tree — npm, node, esbuild workers — can be signalled at once.
+// Stopping sends SIGTERM to the group, waits a grace period, then
+// SIGKILLs whatever is left. Every spawned group is recorded in
+// ~/.mydevify/data/processes.json so groups that outlive the app (crash,
+// force quit) are reaped on the next startup; a normal exit reaps them
+// directly.
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// How long a group gets to exit after SIGTERM before SIGKILL
+#[cfg(unix)]
+const GRACE_PERIOD: Duration = Duration::from_secs(3);
+
+// ── Data Model ────────────────────────────────────────────────
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct TrackedProcess {
+    /// Also the process group ID on Unix
+    pid: u32,
+    command: String,
+    /// `ps -o lstart=` of the leader — guards against PID reuse
+    start_time: Option<String>,
+    /// PID of the Mydevify instance that spawned it
+    owner: u32,
+}
+
+/// Serializes access to the PID file
+static REGISTRY_LOCK: Mutex<()> = Mutex::new(());
+
+fn get_registry_file_path() -> PathBuf {
+    let home = dirs_next::home_dir().unwrap_or_else(|| PathBuf::from("."));
+    let dir = home.join(".mydevify").join("data");
+    fs::create_dir_all(&dir).ok();
+    dir.join("processes.json")
+}
+
+fn load_tracked() -> Vec<TrackedProcess> {
+    fs::read_to_string(get_registry_file_path())
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn save_tracked(processes: &[TrackedProcess]) {
+    if let Ok(json) = serde_json::to_string_pretty(processes) {
+        fs::write(get_registry_file_path(), json).ok();
+    }
+}
+
+// ── Spawning ──────────────────────────────────────────────────
+
+/// Start the command in a new session so it leads its own process group
+/// and has no controlling terminal. No-op on Windows, where taskkill /T
+/// walks the tree instead.
+pub fn isolate(cmd: &mut std::process::Command) {
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+        // SAFETY: setsid is async-signal-safe and touches no parent state
+        unsafe {
+            cmd.pre_exec(|| {
+                if libc::setsid() == -1 {
+                    return Err(std::io::Error::last_os_error());
+                }
+                Ok(())
+            });
+        }
+    }
+    #[cfg(not(unix))]
+    let _ = cmd;
+}
+
+/// Record a freshly spawned process group in the PID file
+pub fn track(pid: u32, command: &str) {
+    let _guard = REGISTRY_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let mut tracked = load_tracked();
+    tracked.retain(|p| p.pid != pid);
+    tracked.push(TrackedProcess {
+        pid,
+        command: command.to_string(),
+        start_time: start_time(pid),
+        owner: std::process::id(),
+    });
+    save_tracked(&tracked);
+}
+
+/// Forget a process group (after it was killed)
+pub fn untrack(pid: u32) {
+    let _guard = REGISTRY_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let mut tracked = load_tracked();
+    let before = tracked.len();
+    tracked.retain(|p| p.pid != pid);
+    if tracked.len() != before {
+        save_tracked(&tracked);
+    }
+}
+
+/// The group leader exited on its own. Keep tracking the group if
+/// children are still running so they get reaped later.
+pub fn exited(pid: u32) {
+    if !group_alive(pid) {
+        untrack(pid);
+    }
+}
+
+// ── Termination ───────────────────────────────────────────────
+
+/// SIGTERM the process group, wait up to the grace period, then SIGKILL.
+/// Falls back to signalling just the PID if it doesn't lead a group
+/// (processes spawned before they were isolated). Blocks.
+#[cfg(unix)]
+pub fn terminate(pid: u32) {
+    let target = if group_alive(pid) { -(pid as i32) } else { pid as i32 };
+
+    // SAFETY: plain kill(2) calls
+    unsafe {
+        libc::kill(target, libc::SIGTERM);
+    }
+
+    let deadline = std::time::Instant::now() + GRACE_PERIOD;
+    while std::time::Instant::now() < deadline {
+        if !signal_target_alive(target) {
+            return;
+        }
+        std::thread::sleep(Duration::from_millis(100));
+    }
+
+    unsafe {
+        libc::kill(target, libc::SIGKILL);
+    }
+}
+
+#[cfg(unix)]
+fn signal_target_alive(target: i32) -> bool {
+    // Signal 0 only checks existence; EPERM still means it exists
+    unsafe { libc::kill(target, 0) == 0 || std::io::Error::last_os_error().raw_os_error() == Some(libc::EPERM) }
+}
+
+/// Whether any process is left in the group led by `pgid`
+#[cfg(unix)]
+fn group_alive(pgid: u32) -> bool {
+    signal_target_alive(-(pgid as i32))
+}
+
+#[cfg(not(unix))]
+fn group_alive(_pgid: u32) -> bool {
+    false
+}
+
+/// Leader start time as reported by ps, e.g. "Sat Oct 18 10:02:11 2026"
+#[cfg(unix)]
+fn start_time(pid: u32) -> Option<String> {
+    let output = std::process::Command::new("ps")
+        .args(["-o", "lstart=", "-p", &pid.to_string()])
+        .output()
+        .ok()?;
+    let text = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    if text.is_empty() {
+        None
+    } else {
+        Some(text)
+    }
+}
+
+#[cfg(not(unix))]
+fn start_time(_pid: u32) -> Option<String> {
+    None
+}
+
+// ── Reaping ───────────────────────────────────────────────────
+
+/// Kill every group this instance spawned. Called when the app exits.
+pub fn reap_all() {
+    let own: Vec<TrackedProcess> = {
+        let _guard = REGISTRY_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        load_tracked()
+            .into_iter()
+            .filter(|p| p.owner == std::process::id())
+            .collect()
+    };
+
+    // Terminate in parallel so shutdown waits one grace period, not one per group
+    let handles: Vec<_> = own
+        .iter()
+        .map(|p| {
+            let pid = p.pid;
+            std::thread::spawn(move || crate::kill_process_tree(pid))
+        })
+        .collect();
+    for handle in handles {
+        let _ = handle.join();
+    }
+}
+
+/// Kill groups left behind by a previous run that didn't exit cleanly.
+/// Skips entries owned by another running instance and PIDs that have
+/// since been reused by an unrelated process.
+#[cfg(unix)]
+pub fn reap_orphans() {
+    let _guard = REGISTRY_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let me = std::process::id();
+    let mut keep = Vec::new();
+
+    for process in load_tracked() {
+        let owner_running = process.owner != me && signal_target_alive(process.owner as i32);
+        if owner_running {
+            keep.push(process);
+            continue;
+        }
+        if !group_alive(process.pid) {
+            continue;
+        }
+        // A live leader must be the one we started. If the leader is gone
+        // the PGID can't have been reused while the group still exists.
+        let leader_alive = signal_target_alive(process.pid as i32);
+        if leader_alive && start_time(process.pid) != process.start_time {
+            continue;
+        }
+        terminate(process.pid);
+    }
+
+    save_tracked(&keep);
+}
+
+#[cfg(not(unix))]
+pub fn reap_orphans() {
+    // Without process groups there's no safe way to tell our orphans
+    // from reused PIDs — just drop stale entries
+    let _guard = REGISTRY_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let me = std::process::id();
+    let mut tracked = load_tracked();
+    tracked.retain(|p| p.owner == me);
+    save_tracked(&tracked);
+}
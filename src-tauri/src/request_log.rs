@@ -0,0 +1,185 @@
+// ── Request Log — Preview Server Inspector ────────────────────
+//
+// Records every request a preview server answers (static files, mocks
+// and proxied dev server traffic) with its status, the file that
+// answered it, response size and latency. A page that fails to load an
+// asset usually shows nothing; here the 404 shows up with the file the
+// server looked for, so users and the AI can see what's missing.
+//
+// Records live in one bounded log shared by all preview servers, tagged
+// with the server key. Reads are cursor-based like the dev server log,
+// and each record is also emitted as a `preview-request` event.
+
+use axum::body::HttpBody;
+use axum::extract::{Request, State};
+use axum::http::header;
+use axum::middleware::Next;
+use axum::response::Response;
+use axum::Router;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+use tauri::Emitter;
+
+/// Records kept across all preview servers
+const MAX_RECORDS: usize = 1_000;
+
+// ── Data Model ────────────────────────────────────────────────
+
+/// Response extension: the file that answered the request
+#[derive(Debug, Clone)]
+pub struct ResolvedFile(pub PathBuf);
+
+/// Response extension: the file a 404 was looking for
+#[derive(Debug, Clone)]
+pub struct MissingFile(pub PathBuf);
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RequestRecord {
+    pub seq: u64,
+    /// Preview server key (project path or window id)
+    pub server: String,
+    pub timestamp: String, // ISO 8601 string
+    pub method: String,
+    pub path: String,
+    pub query: Option<String>,
+    pub status: u16,
+    pub resolved_file: Option<String>,
+    /// Set on 404s for paths that map into the served root
+    pub missing_file: Option<String>,
+    /// Body bytes before compression, when known up front
+    pub size: Option<u64>,
+    /// Time until the response headers were ready
+    pub latency_ms: u64,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct RequestLogOptions {
+    /// Only requests to this preview server (None = all)
+    pub server: Option<String>,
+    /// Return records with seq greater than this (None = from the oldest kept)
+    pub since: Option<u64>,
+    /// Max records to return, oldest first (None = all available)
+    pub limit: Option<usize>,
+    /// Only 4xx/5xx responses
+    #[serde(default)]
+    pub failed_only: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RequestLogRead {
+    pub records: Vec<RequestRecord>,
+    /// Pass back as `since` on the next read
+    pub cursor: u64,
+}
+
+struct RequestLog {
+    records: VecDeque<RequestRecord>,
+    last_seq: u64,
+}
+
+static REQUEST_LOG: Mutex<RequestLog> = Mutex::new(RequestLog {
+    records: VecDeque::new(),
+    last_seq: 0,
+});
+
+// ── Recording ─────────────────────────────────────────────────
+
+/// Which server a recorder layer belongs to and where to send events
+struct Recorder {
+    server: String,
+    app_handle: tauri::AppHandle,
+}
+
+/// Record every request `router` answers under the server key `server`
+pub fn layer(router: Router, server: &str, app_handle: &tauri::AppHandle) -> Router {
+    let recorder = Arc::new(Recorder {
+        server: server.to_string(),
+        app_handle: app_handle.clone(),
+    });
+    router.layer(axum::middleware::from_fn_with_state(recorder, record))
+}
+
+async fn record(State(recorder): State<Arc<Recorder>>, request: Request, next: Next) -> Response {
+    // Mydevify's own endpoints (live reload stream, console reports) are
+    // not the page's requests
+    if request.uri().path().starts_with("/__mydevify/") {
+        return next.run(request).await;
+    }
+
+    let started = Instant::now();
+    let method = request.method().to_string();
+    let path = request.uri().path().to_string();
+    let query = request.uri().query().map(str::to_string);
+
+    let response = next.run(request).await;
+
+    let size = response
+        .headers()
+        .get(header::CONTENT_LENGTH)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.parse().ok())
+        .or_else(|| response.body().size_hint().exact());
+    let extensions = response.extensions();
+    let as_string = |p: &PathBuf| p.to_string_lossy().to_string();
+
+    let mut record = RequestRecord {
+        seq: 0,
+        server: recorder.server.clone(),
+        timestamp: chrono::Utc::now().to_rfc3339(),
+        method,
+        path,
+        query,
+        status: response.status().as_u16(),
+        resolved_file: extensions.get::<ResolvedFile>().map(|f| as_string(&f.0)),
+        missing_file: extensions.get::<MissingFile>().map(|f| as_string(&f.0)),
+        size,
+        latency_ms: started.elapsed().as_millis() as u64,
+    };
+
+    {
+        let mut log = REQUEST_LOG.lock().unwrap_or_else(|e| e.into_inner());
+        log.last_seq += 1;
+        record.seq = log.last_seq;
+        if log.records.len() >= MAX_RECORDS {
+            log.records.pop_front();
+        }
+        log.records.push_back(record.clone());
+    }
+    let _ = recorder.app_handle.emit("preview-request", record);
+
+    response
+}
+
+// ── Reading ───────────────────────────────────────────────────
+
+pub fn read(options: &RequestLogOptions) -> RequestLogRead {
+    let log = REQUEST_LOG.lock().unwrap_or_else(|e| e.into_inner());
+    let since = options.since.unwrap_or(0);
+    let limit = options.limit.unwrap_or(usize::MAX);
+    let mut matching = log
+        .records
+        .iter()
+        .filter(|r| r.seq > since)
+        .filter(|r| options.server.as_ref().is_none_or(|s| &r.server == s))
+        .filter(|r| !options.failed_only || r.status >= 400);
+    let records: Vec<RequestRecord> = matching.by_ref().take(limit).cloned().collect();
+
+    // Skip past filtered-out records too, unless the limit cut the read short
+    let cursor = match (matching.next(), records.last()) {
+        (Some(_), Some(last)) => last.seq,
+        _ => log.last_seq,
+    };
+    RequestLogRead { records, cursor }
+}
+
+/// Forget the requests of one preview server (all when None)
+pub fn clear(server: Option<&str>) {
+    let mut log = REQUEST_LOG.lock().unwrap_or_else(|e| e.into_inner());
+    match server {
+        Some(server) => log.records.retain(|r| r.server != server),
+        None => log.records.clear(),
+    }
+}
@@ -0,0 +1,751 @@
+// ── Routing Rules — Netlify / Vercel Emulation ────────────────
+//
+// Static sites headed for Netlify or Vercel depend on host routing:
+// `_redirects`, `_headers`, `netlify.toml` and `vercel.json`. The
+// preview server reads those from the served root and applies
+// redirects, rewrites, custom headers, clean URLs, trailing-slash
+// rules, custom 404 pages and SPA fallback, so client-side routes work
+// in preview the way they will in production.
+//
+// Rules are evaluated in file order, first match wins. Like Netlify,
+// a rule that isn't forced (`!`) is shadowed by a file that exists at
+// the requested path; Vercel redirects are always applied, rewrites
+// only when no file matches. Rules with conditions (query, country,
+// `has`/`missing`) and rewrites to external URLs are skipped.
+
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
+
+const CONFIG_FILES: &[&str] = &["_redirects", "_headers", "netlify.toml", "vercel.json"];
+
+// ── Patterns ──────────────────────────────────────────────────
+
+#[derive(Debug, Clone, PartialEq)]
+enum Segment {
+    Literal(String),
+    /// `:name` — exactly one segment
+    Param(String),
+    /// `*`, `:name*`, `(.*)` — the rest of the path, possibly empty
+    Splat(String),
+}
+
+/// A source path pattern in either Netlify or Vercel (path-to-regexp) syntax
+#[derive(Debug, Clone)]
+pub(crate) struct Pattern {
+    segments: Vec<Segment>,
+}
+
+impl Pattern {
+    pub(crate) fn parse(source: &str) -> Self {
+        let mut regex_groups = 0;
+        let segments = split_path(source)
+            .map(|seg| {
+                if seg == "*" {
+                    Segment::Splat("splat".to_string())
+                } else if seg == "(.*)" {
+                    regex_groups += 1;
+                    Segment::Splat(regex_groups.to_string())
+                } else if let Some(name) = seg.strip_prefix(':') {
+                    let name = name.trim_end_matches("(.*)");
+                    if let Some(name) = name.strip_suffix('*').or_else(|| name.strip_suffix('+')) {
+                        Segment::Splat(name.to_string())
+                    } else if seg.ends_with("(.*)") {
+                        Segment::Splat(name.to_string())
+                    } else {
+                        Segment::Param(name.trim_end_matches('?').to_string())
+                    }
+                } else {
+                    Segment::Literal(seg.to_string())
+                }
+            })
+            .collect();
+        Self { segments }
+    }
+
+    /// Match a request path, returning the captured placeholders
+    pub(crate) fn matches(&self, path: &str) -> Option<HashMap<String, String>> {
+        let parts: Vec<&str> = split_path(path).collect();
+        let mut captures = HashMap::new();
+
+        for (i, segment) in self.segments.iter().enumerate() {
+            match segment {
+                Segment::Splat(name) => {
+                    captures.insert(name.clone(), parts.get(i..).unwrap_or_default().join("/"));
+                    return Some(captures);
+                }
+                Segment::Param(name) => {
+                    captures.insert(name.clone(), parts.get(i)?.to_string());
+                }
+                Segment::Literal(lit) => {
+                    if parts.get(i) != Some(&lit.as_str()) {
+                        return None;
+                    }
+                }
+            }
+        }
+
+        (parts.len() == self.segments.len()).then_some(captures)
+    }
+}
+
+fn split_path(path: &str) -> impl Iterator<Item = &str> {
+    path.split('/').filter(|s| !s.is_empty())
+}
+
+/// Fill `:name` / `$1` placeholders in a destination
+fn substitute(destination: &str, captures: &HashMap<String, String>) -> String {
+    // Longest names first so `:id` doesn't eat the start of `:identifier`
+    let mut names: Vec<&String> = captures.keys().collect();
+    names.sort_by_key(|n| std::cmp::Reverse(n.len()));
+
+    let mut out = destination.to_string();
+    for name in names {
+        let value = &captures[name];
+        if name.chars().all(|c| c.is_ascii_digit()) {
+            out = out.replace(&format!("${}", name), value);
+        } else {
+            // Vercel repeats the modifier in destinations (`/new/:path*`)
+            for suffix in ["*", "+", ""] {
+                out = out.replace(&format!(":{}{}", name, suffix), value);
+            }
+        }
+    }
+    out
+}
+
+// ── Rules ─────────────────────────────────────────────────────
+
+#[derive(Debug, Clone)]
+struct RouteRule {
+    from: Pattern,
+    to: String,
+    /// 200 rewrites, 3xx redirects, 404 serves `to` as a not-found page
+    status: u16,
+    /// Apply even when a file exists at the requested path
+    force: bool,
+}
+
+#[derive(Debug, Clone)]
+struct HeaderRule {
+    path: Pattern,
+    values: Vec<(String, String)>,
+}
+
+#[derive(Debug, Default)]
+pub struct RoutingRules {
+    routes: Vec<RouteRule>,
+    headers: Vec<HeaderRule>,
+    /// Whether any routing config exists at all
+    configured: bool,
+    /// Vercel `cleanUrls`: redirect `/about.html` to `/about`
+    strip_html_extension: bool,
+    /// Vercel `trailingSlash`: Some(true) adds, Some(false) removes
+    trailing_slash: Option<bool>,
+    /// `/about` serves `about.html` — Netlify always, Vercel with cleanUrls
+    clean_urls: bool,
+    /// Guess a single-page app when no config exists (see `resolve`)
+    spa_fallback: bool,
+}
+
+/// What the server should send for a request
+#[derive(Debug)]
+pub enum Resolution {
+    Redirect { location: String, status: u16 },
+    /// A file inside the root (canonical path) with the status to send it with
+    File { path: PathBuf, status: u16 },
+    NotFound,
+}
+
+impl RoutingRules {
+    pub fn load(root: &Path) -> Self {
+        let mut rules = RoutingRules {
+            clean_urls: true,
+            spa_fallback: true,
+            ..Default::default()
+        };
+
+        if let Ok(content) = fs::read_to_string(root.join("_redirects")) {
+            rules.configured = true;
+            rules.routes.extend(parse_netlify_redirects(&content));
+        }
+        if let Ok(content) = fs::read_to_string(root.join("_headers")) {
+            rules.configured = true;
+            rules.headers.extend(parse_netlify_headers(&content));
+        }
+        if let Ok(content) = fs::read_to_string(root.join("netlify.toml")) {
+            if let Ok(config) = toml::from_str::<NetlifyToml>(&content) {
+                rules.configured = true;
+                rules.apply_netlify_toml(config);
+            }
+        }
+        if let Ok(content) = fs::read_to_string(root.join("vercel.json")) {
+            if let Ok(config) = serde_json::from_str::<VercelJson>(&content) {
+                rules.configured = true;
+                rules.apply_vercel_json(config);
+            }
+        }
+
+        rules
+    }
+
+    /// Only what the project declares: no guessed SPA fallback, so a
+    /// missing page isn't answered with index.html. Rewrites to
+    /// index.html in the host config still apply.
+    pub fn without_spa_fallback(mut self) -> Self {
+        self.spa_fallback = false;
+        self
+    }
+
+    /// Decide how to answer a request path (raw, as sent) and query
+    pub fn resolve(&self, root: &Path, path: &str, query: Option<&str>) -> Resolution {
+        let with_query = |location: String| match query {
+            Some(q) if !location.contains('?') => format!("{}?{}", location, q),
+            _ => location,
+        };
+
+        // Vercel URL normalization happens before anything else
+        if self.strip_html_extension {
+            if let Some(stripped) = path.strip_suffix(".html") {
+                let stripped = stripped.strip_suffix("/index").unwrap_or(stripped);
+                let location = if stripped.is_empty() { "/" } else { stripped };
+                return Resolution::Redirect { location: with_query(location.to_string()), status: 308 };
+            }
+        }
+        let last_segment = path.rsplit('/').next().unwrap_or("");
+        match self.trailing_slash {
+            Some(true) if path != "/" && !path.ends_with('/') && !last_segment.contains('.') => {
+                return Resolution::Redirect { location: with_query(format!("{}/", path)), status: 308 };
+            }
+            Some(false) if path != "/" && path.ends_with('/') => {
+                let location = path.trim_end_matches('/');
+                let location = if location.is_empty() { "/" } else { location };
+                return Resolution::Redirect { location: with_query(location.to_string()), status: 308 };
+            }
+            _ => {}
+        }
+
+        let existing = self.lookup(root, path);
+
+        for rule in &self.routes {
+            let Some(captures) = rule.from.matches(path) else {
+                continue;
+            };
+            if existing.is_some() && !rule.force {
+                continue;
+            }
+            let to = substitute(&rule.to, &captures);
+            match rule.status {
+                301 | 302 | 303 | 307 | 308 => {
+                    return Resolution::Redirect { location: with_query(to), status: rule.status };
+                }
+                _ if to.starts_with("http://") || to.starts_with("https://") => continue,
+                status => {
+                    let (to_path, _) = to.split_once('?').unwrap_or((&to, ""));
+                    if let Some(file) = self.lookup(root, to_path) {
+                        return Resolution::File { path: file, status };
+                    }
+                }
+            }
+        }
+
+        if let Some(file) = existing {
+            return Resolution::File { path: file, status: 200 };
+        }
+
+        // Custom 404 page, as both hosts serve it
+        if let Some(file) = self.lookup(root, "/404.html") {
+            return Resolution::File { path: file, status: 404 };
+        }
+
+        // Without any host config or 404 page, assume a single-page app:
+        // extensionless paths fall back to index.html
+        if self.spa_fallback && !self.configured && !last_segment.contains('.') {
+            if let Some(file) = self.lookup(root, "/index.html") {
+                return Resolution::File { path: file, status: 200 };
+            }
+        }
+
+        Resolution::NotFound
+    }
+
+    /// Custom headers for a request path, in rule order (later wins)
+    pub fn headers_for(&self, path: &str) -> Vec<(String, String)> {
+        self.headers
+            .iter()
+            .filter(|rule| rule.path.matches(path).is_some())
+            .flat_map(|rule| rule.values.iter().cloned())
+            .collect()
+    }
+
+    /// Find the file a URL path maps to: exact file, directory index,
+    /// then `path.html` when clean URLs are on. Only files inside the
+    /// root are returned.
+    fn lookup(&self, root: &Path, path: &str) -> Option<PathBuf> {
+        let rel = path.trim_start_matches('/');
+        let joined = root.join(rel);
+
+        let mut candidates = Vec::new();
+        if rel.is_empty() || joined.is_dir() {
+            candidates.push(joined.join("index.html"));
+        } else {
+            candidates.push(joined.clone());
+            if self.clean_urls {
+                let trimmed = rel.trim_end_matches('/');
+                candidates.push(root.join(format!("{}.html", trimmed)));
+            }
+        }
+
+        let root_canonical = root.canonicalize().ok()?;
+        candidates.into_iter().find_map(|candidate| {
+            let canonical = candidate.canonicalize().ok()?;
+            (canonical.starts_with(&root_canonical) && canonical.is_file()).then_some(canonical)
+        })
+    }
+
+    fn apply_netlify_toml(&mut self, config: NetlifyToml) {
+        for redirect in config.redirects {
+            if !redirect.conditions.is_empty() || !redirect.query.is_empty() {
+                continue;
+            }
+            self.routes.push(RouteRule {
+                from: Pattern::parse(&redirect.from),
+                to: redirect.to,
+                status: redirect.status.unwrap_or(301),
+                force: redirect.force,
+            });
+        }
+        for header in config.headers {
+            self.headers.push(HeaderRule {
+                path: Pattern::parse(&header.path),
+                values: header.values.into_iter().collect(),
+            });
+        }
+    }
+
+    fn apply_vercel_json(&mut self, config: VercelJson) {
+        if config.clean_urls {
+            self.strip_html_extension = true;
+        } else {
+            // Vercel only maps /about to about.html with cleanUrls on
+            self.clean_urls = false;
+        }
+        self.trailing_slash = config.trailing_slash;
+
+        // Vercel order: redirects, filesystem, rewrites
+        for redirect in config.redirects.into_iter().filter(|r| r.has.is_none() && r.missing.is_none()) {
+            let status = redirect.status_code.unwrap_or(if redirect.permanent.unwrap_or(true) { 308 } else { 307 });
+            self.routes.push(RouteRule {
+                from: Pattern::parse(&redirect.source),
+                to: redirect.destination,
+                status,
+                force: true,
+            });
+        }
+        for rewrite in config.rewrites.into_iter().filter(|r| r.has.is_none() && r.missing.is_none()) {
+            self.routes.push(RouteRule {
+                from: Pattern::parse(&rewrite.source),
+                to: rewrite.destination,
+                status: 200,
+                force: false,
+            });
+        }
+        for header in config.headers.into_iter().filter(|h| h.has.is_none() && h.missing.is_none()) {
+            self.headers.push(HeaderRule {
+                path: Pattern::parse(&header.source),
+                values: header.headers.into_iter().map(|h| (h.key, h.value)).collect(),
+            });
+        }
+    }
+}
+
+// ── Netlify ───────────────────────────────────────────────────
+
+/// `_redirects`: `from to [status][!]`, one rule per line
+fn parse_netlify_redirects(content: &str) -> Vec<RouteRule> {
+    content
+        .lines()
+        .map(|line| line.split('#').next().unwrap_or("").trim())
+        .filter(|line| !line.is_empty())
+        .filter_map(|line| {
+            let parts: Vec<&str> = line.split_whitespace().collect();
+            // Query and country/language conditions can't be emulated;
+            // a query string in the destination is fine
+            let is_destination = |p: &str| p.starts_with('/') || p.contains("://");
+            let conditional = parts
+                .iter()
+                .enumerate()
+                .any(|(i, p)| p.contains('=') && !(i == 1 && is_destination(p)));
+            if parts.len() < 2 || conditional {
+                return None;
+            }
+            let (status, force) = match parts.get(2) {
+                Some(s) => {
+                    let force = s.ends_with('!');
+                    (s.trim_end_matches('!').parse().ok()?, force)
+                }
+                None => (301, false),
+            };
+            Some(RouteRule {
+                from: Pattern::parse(parts[0]),
+                to: parts[1].to_string(),
+                status,
+                force,
+            })
+        })
+        .collect()
+}
+
+/// `_headers`: a path line followed by indented `Name: value` lines
+fn parse_netlify_headers(content: &str) -> Vec<HeaderRule> {
+    let mut rules: Vec<HeaderRule> = Vec::new();
+    for line in content.lines() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+        let indented = line.starts_with(' ') || line.starts_with('\t');
+        if !indented {
+            rules.push(HeaderRule {
+                path: Pattern::parse(trimmed),
+                values: Vec::new(),
+            });
+        } else if let (Some(rule), Some((name, value))) = (rules.last_mut(), trimmed.split_once(':')) {
+            rule.values.push((name.trim().to_string(), value.trim().to_string()));
+        }
+    }
+    rules
+}
+
+#[derive(Deserialize, Default)]
+struct NetlifyToml {
+    #[serde(default)]
+    redirects: Vec<NetlifyRedirect>,
+    #[serde(default)]
+    headers: Vec<NetlifyHeaders>,
+}
+
+#[derive(Deserialize)]
+struct NetlifyRedirect {
+    from: String,
+    to: String,
+    status: Option<u16>,
+    #[serde(default)]
+    force: bool,
+    #[serde(default)]
+    query: HashMap<String, String>,
+    #[serde(default)]
+    conditions: HashMap<String, toml::Value>,
+}
+
+#[derive(Deserialize)]
+struct NetlifyHeaders {
+    #[serde(rename = "for")]
+    path: String,
+    #[serde(default)]
+    values: HashMap<String, String>,
+}
+
+// ── Vercel ────────────────────────────────────────────────────
+
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+struct VercelJson {
+    #[serde(default)]
+    clean_urls: bool,
+    trailing_slash: Option<bool>,
+    #[serde(default)]
+    redirects: Vec<VercelRedirect>,
+    #[serde(default)]
+    rewrites: Vec<VercelRewrite>,
+    #[serde(default)]
+    headers: Vec<VercelHeaders>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct VercelRedirect {
+    source: String,
+    destination: String,
+    permanent: Option<bool>,
+    status_code: Option<u16>,
+    has: Option<serde_json::Value>,
+    missing: Option<serde_json::Value>,
+}
+
+#[derive(Deserialize)]
+struct VercelRewrite {
+    source: String,
+    destination: String,
+    has: Option<serde_json::Value>,
+    missing: Option<serde_json::Value>,
+}
+
+#[derive(Deserialize)]
+struct VercelHeaders {
+    source: String,
+    headers: Vec<VercelHeader>,
+    has: Option<serde_json::Value>,
+    missing: Option<serde_json::Value>,
+}
+
+#[derive(Deserialize)]
+struct VercelHeader {
+    key: String,
+    value: String,
+}
+
+// ── Cache ─────────────────────────────────────────────────────
+
+/// Rules for one served root, reloaded whenever a config file changes
+pub struct RulesCache {
+    root: PathBuf,
+    cached: Mutex<Option<CachedRules>>,
+}
+
+struct CachedRules {
+    /// Modified times of CONFIG_FILES when the rules were loaded
+    stamps: Vec<Option<SystemTime>>,
+    rules: Arc<RoutingRules>,
+}
+
+impl RulesCache {
+    pub fn new(root: &Path) -> Self {
+        Self {
+            root: root.to_path_buf(),
+            cached: Mutex::new(None),
+        }
+    }
+
+    pub fn current(&self) -> Arc<RoutingRules> {
+        let stamps: Vec<Option<SystemTime>> = CONFIG_FILES
+            .iter()
+            .map(|name| fs::metadata(self.root.join(name)).and_then(|m| m.modified()).ok())
+            .collect();
+
+        let mut cached = self.cached.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(entry) = cached.as_ref() {
+            if entry.stamps == stamps {
+                return entry.rules.clone();
+            }
+        }
+        let rules = Arc::new(RoutingRules::load(&self.root));
+        *cached = Some(CachedRules { stamps, rules: rules.clone() });
+        rules
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A throwaway site root, removed on drop
+    struct Site(PathBuf);
+
+    impl Site {
+        fn new(files: &[(&str, &str)]) -> Self {
+            let root = std::env::temp_dir().join(format!("mydevify-routing-{}", uuid::Uuid::new_v4()));
+            for (path, content) in files {
+                let path = root.join(path);
+                fs::create_dir_all(path.parent().unwrap()).unwrap();
+                fs::write(path, content).unwrap();
+            }
+            fs::create_dir_all(&root).unwrap();
+            Site(root.canonicalize().unwrap())
+        }
+
+        fn resolve(&self, path: &str) -> String {
+            self.resolve_with(&RoutingRules::load(&self.0), path)
+        }
+
+        /// The outcome as "200 index.html" or "301 -> /new"
+        fn resolve_with(&self, rules: &RoutingRules, path: &str) -> String {
+            match rules.resolve(&self.0, path, None) {
+                Resolution::File { path, status } => {
+                    format!("{} {}", status, path.strip_prefix(&self.0).unwrap().to_string_lossy().replace('\\', "/"))
+                }
+                Resolution::Redirect { location, status } => format!("{} -> {}", status, location),
+                Resolution::NotFound => "not found".to_string(),
+            }
+        }
+    }
+
+    impl Drop for Site {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    #[test]
+    fn patterns_capture_params_and_splats() {
+        let pattern = Pattern::parse("/blog/:year/:slug");
+        let captures = pattern.matches("/blog/2024/hello").unwrap();
+        assert_eq!(captures["year"], "2024");
+        assert_eq!(captures["slug"], "hello");
+        assert!(pattern.matches("/blog/2024").is_none());
+        assert!(pattern.matches("/blog/2024/hello/extra").is_none());
+
+        assert_eq!(Pattern::parse("/docs/*").matches("/docs/a/b").unwrap()["splat"], "a/b");
+        assert_eq!(Pattern::parse("/docs/*").matches("/docs").unwrap()["splat"], "");
+        assert_eq!(Pattern::parse("/old/:path*").matches("/old/x/y").unwrap()["path"], "x/y");
+        assert_eq!(Pattern::parse("/api/(.*)").matches("/api/users/1").unwrap()["1"], "users/1");
+    }
+
+    #[test]
+    fn destinations_are_filled_in() {
+        let captures = Pattern::parse("/:id/:identifier/*").matches("/1/abc/rest/of").unwrap();
+        assert_eq!(substitute("/x/:identifier/:id/:splat", &captures), "/x/abc/1/rest/of");
+        let captures = Pattern::parse("/old/:path*").matches("/old/a/b").unwrap();
+        assert_eq!(substitute("/new/:path*", &captures), "/new/a/b");
+        let captures = Pattern::parse("/api/(.*)").matches("/api/v1").unwrap();
+        assert_eq!(substitute("/backend/$1", &captures), "/backend/v1");
+    }
+
+    #[test]
+    fn netlify_redirects_file() {
+        let rules = parse_netlify_redirects(
+            "# comment\n\
+             /home   /            301\n\
+             /app/*  /index.html  200\n\
+             /forced /other       302!\n\
+             /short  /target\n\
+             /geo    /de          302  Country=de\n\
+             /store  id=:id       /items/:id  301\n\
+             /broken\n",
+        );
+        let summary: Vec<(String, u16, bool)> = rules.iter().map(|r| (r.to.clone(), r.status, r.force)).collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("/".to_string(), 301, false),
+                ("/index.html".to_string(), 200, false),
+                ("/other".to_string(), 302, true),
+                ("/target".to_string(), 301, false),
+            ]
+        );
+    }
+
+    #[test]
+    fn netlify_headers_file() {
+        let rules = parse_netlify_headers(
+            "/*\n\
+             \x20 X-Frame-Options: DENY\n\
+             \x20 Content-Security-Policy: default-src 'self'; img-src *\n\
+             # comment\n\
+             /assets/*\n\
+             \tCache-Control: public, max-age=31536000, immutable\n",
+        );
+        let routing = RoutingRules {
+            headers: rules,
+            ..Default::default()
+        };
+        assert_eq!(
+            routing.headers_for("/assets/app.js"),
+            vec![
+                ("X-Frame-Options".to_string(), "DENY".to_string()),
+                ("Content-Security-Policy".to_string(), "default-src 'self'; img-src *".to_string()),
+                ("Cache-Control".to_string(), "public, max-age=31536000, immutable".to_string()),
+            ]
+        );
+        assert_eq!(routing.headers_for("/index.html").len(), 2);
+    }
+
+    #[test]
+    fn files_shadow_rules_unless_forced() {
+        let site = Site::new(&[
+            ("index.html", ""),
+            ("about.html", ""),
+            ("other.html", ""),
+            ("_redirects", "/about  /other.html  200\n/index.html  /other.html  200!\n/old  /about  301\n"),
+        ]);
+        assert_eq!(site.resolve("/about"), "200 about.html");
+        assert_eq!(site.resolve("/index.html"), "200 other.html");
+        assert_eq!(site.resolve("/old"), "301 -> /about");
+    }
+
+    #[test]
+    fn redirects_keep_the_query() {
+        let site = Site::new(&[("_redirects", "/old  /new  302\n/kept  /new?x=1  302\n")]);
+        let rules = RoutingRules::load(&site.0);
+        let location = |path, query| match rules.resolve(&site.0, path, query) {
+            Resolution::Redirect { location, .. } => location,
+            other => panic!("{:?}", other),
+        };
+        assert_eq!(location("/old", Some("a=1")), "/new?a=1");
+        assert_eq!(location("/kept", Some("a=1")), "/new?x=1");
+    }
+
+    #[test]
+    fn netlify_toml_rules() {
+        let site = Site::new(&[
+            ("index.html", ""),
+            (
+                "netlify.toml",
+                "[[redirects]]\nfrom = \"/blog/:slug\"\nto = \"/posts/:slug\"\nstatus = 301\n\n\
+                 [[redirects]]\nfrom = \"/de/*\"\nto = \"/de.html\"\nconditions = { Language = [\"de\"] }\n",
+            ),
+        ]);
+        assert_eq!(site.resolve("/blog/hello"), "301 -> /posts/hello");
+        // Conditional rules are skipped
+        assert_eq!(site.resolve("/de/x"), "not found");
+    }
+
+    #[test]
+    fn vercel_clean_urls_and_trailing_slash() {
+        let site = Site::new(&[
+            ("index.html", ""),
+            ("about.html", ""),
+            ("app.js", ""),
+            ("vercel.json", r#"{"cleanUrls": true, "trailingSlash": false, "rewrites": [{"source": "/app/(.*)", "destination": "/index.html"}]}"#),
+        ]);
+        assert_eq!(site.resolve("/about.html"), "308 -> /about");
+        assert_eq!(site.resolve("/index.html"), "308 -> /");
+        assert_eq!(site.resolve("/about/"), "308 -> /about");
+        assert_eq!(site.resolve("/about"), "200 about.html");
+        assert_eq!(site.resolve("/app/settings"), "200 index.html");
+        assert_eq!(site.resolve("/app.js"), "200 app.js");
+    }
+
+    #[test]
+    fn vercel_without_clean_urls_needs_the_extension() {
+        let site = Site::new(&[("about.html", ""), ("vercel.json", r#"{"trailingSlash": true}"#)]);
+        assert_eq!(site.resolve("/about"), "308 -> /about/");
+        assert_eq!(site.resolve("/about/"), "not found");
+        assert_eq!(site.resolve("/about.html"), "200 about.html");
+    }
+
+    #[test]
+    fn custom_404_page() {
+        let site = Site::new(&[("index.html", ""), ("404.html", "")]);
+        assert_eq!(site.resolve("/missing"), "404 404.html");
+    }
+
+    #[test]
+    fn spa_fallback_only_without_config() {
+        let site = Site::new(&[("index.html", ""), ("docs/index.html", "")]);
+        assert_eq!(site.resolve("/docs/"), "200 docs/index.html");
+        assert_eq!(site.resolve("/dashboard/settings"), "200 index.html");
+        assert_eq!(site.resolve("/missing.png"), "not found");
+
+        let rules = RoutingRules::load(&site.0).without_spa_fallback();
+        assert_eq!(site.resolve_with(&rules, "/dashboard/settings"), "not found");
+        assert_eq!(site.resolve_with(&rules, "/docs/"), "200 docs/index.html");
+
+        // Any host config turns the guess off; a declared rewrite still applies
+        let site = Site::new(&[("index.html", ""), ("_headers", "/*\n  X-Test: 1\n")]);
+        assert_eq!(site.resolve("/dashboard"), "not found");
+        let site = Site::new(&[("index.html", ""), ("_redirects", "/*  /index.html  200\n")]);
+        let rules = RoutingRules::load(&site.0).without_spa_fallback();
+        assert_eq!(site.resolve_with(&rules, "/dashboard"), "200 index.html");
+    }
+
+    #[test]
+    fn paths_outside_the_root_are_not_served() {
+        let site = Site::new(&[("index.html", "")]);
+        let rules = RoutingRules::load(&site.0).without_spa_fallback();
+        assert_eq!(site.resolve_with(&rules, "/../../etc/passwd"), "not found");
+    }
+}
@@ -0,0 +1,463 @@
+// ── Run Configuration — .mydevify/services.toml ───────────────
+//
+// A project can check in a declarative description of how to run it,
+// so nobody has to re-derive the dev command from package.json
+// heuristics. Each `[services.<name>]` table declares a command, cwd,
+// env, port (fixed or a pattern to find it in the output), a readiness
+// probe and which services must be ready first. The service marked
+// `preview = true` (or the only one) runs as the dev server shown in the
+// preview pane; the rest run under the supervisor (supervisor.rs).
+//
+//     [services.api]
+//     command = "npm run api"
+//     port = 4000
+//     ready = { http = "/health" }
+//
+//     [services.web]
+//     command = "npm run dev"
+//     cwd = "frontend"
+//     env = { API_URL = "http://localhost:4000" }
+//     port_pattern = "localhost:(\\d+)"
+//     depends_on = ["api"]
+//     preview = true
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use crate::dev_server::{self, DevServerOptions, DevServerStarted};
+use crate::supervisor::{self, ServiceDefinition, ServiceState, ServiceStatus};
+
+/// Readiness wait when the probe doesn't set its own timeout
+const DEFAULT_READY_TIMEOUT: Duration = Duration::from_secs(60);
+/// Used for the preview service when it declares neither port nor pattern
+const DEFAULT_PORT_PATTERN: &str = r"localhost:(\d+)";
+
+// ── Data Model ────────────────────────────────────────────────
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RunConfig {
+    #[serde(default)]
+    pub services: BTreeMap<String, ServiceConfig>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ServiceConfig {
+    pub command: String,
+    /// Relative to the project root (default: root)
+    pub cwd: Option<String>,
+    #[serde(default)]
+    pub env: HashMap<String, String>,
+    /// Port the service listens on, when it's fixed
+    pub port: Option<u16>,
+    /// Regex with one capture group for the port, e.g. "localhost:(\\d+)"
+    pub port_pattern: Option<String>,
+    /// Services that must be ready before this one starts
+    #[serde(default)]
+    pub depends_on: Vec<String>,
+    pub ready: Option<ReadinessProbe>,
+    /// Show this service in the preview pane
+    #[serde(default)]
+    pub preview: bool,
+    /// Restart the preview service if it crashes (other services aren't restarted)
+    #[serde(default)]
+    pub auto_restart: bool,
+}
+
+/// Exactly one of `http`, `tcp` or `log`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReadinessProbe {
+    /// Path that must answer HTTP on the service's port
+    pub http: Option<String>,
+    /// Port that must accept TCP connections
+    pub tcp: Option<u16>,
+    /// Regex that must match a line of output
+    pub log: Option<String>,
+    pub timeout_secs: Option<u64>,
+}
+
+/// What the frontend needs to know about a project's services.toml
+#[derive(Debug, Clone, Serialize)]
+pub struct RunConfigInfo {
+    pub services: BTreeMap<String, ServiceConfig>,
+    pub start_order: Vec<String>,
+    pub preview_service: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RunConfigStarted {
+    /// The preview service, once it's serving
+    pub preview: Option<DevServerStarted>,
+    pub preview_service: Option<String>,
+    /// Supervisor-managed services in start order
+    pub services: Vec<ServiceStatus>,
+}
+
+// ── Loading ───────────────────────────────────────────────────
+
+fn config_path(root: &Path) -> PathBuf {
+    root.join(".mydevify").join("services.toml")
+}
+
+fn project_root(project: &str) -> Result<PathBuf, String> {
+    let path = PathBuf::from(project);
+    if !path.is_dir() {
+        return Err(format!("Directory not found: {}", project));
+    }
+    Ok(path.canonicalize().unwrap_or(path))
+}
+
+/// Read and validate the project's services.toml. Ok(None) if it has none.
+pub fn load(project: &str) -> Result<Option<RunConfig>, String> {
+    let path = config_path(&project_root(project)?);
+    let content = match fs::read_to_string(&path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(format!("Failed to read {}: {}", path.display(), e)),
+    };
+
+    let config: RunConfig = toml::from_str(&content)
+        .map_err(|e| format!("Invalid .mydevify/services.toml: {}", e))?;
+    validate(&config)?;
+    Ok(Some(config))
+}
+
+/// Parsed config plus derived start order and preview service
+pub fn describe(project: &str) -> Result<Option<RunConfigInfo>, String> {
+    let config = match load(project)? {
+        Some(config) => config,
+        None => return Ok(None),
+    };
+    Ok(Some(RunConfigInfo {
+        start_order: start_order(&config)?,
+        preview_service: preview_service(&config),
+        services: config.services,
+    }))
+}
+
+fn validate(config: &RunConfig) -> Result<(), String> {
+    if config.services.is_empty() {
+        return Err("services.toml declares no services".to_string());
+    }
+
+    for (name, service) in &config.services {
+        if service.command.trim().is_empty() {
+            return Err(format!("Service '{}' has no command", name));
+        }
+        if let Some(ref pattern) = service.port_pattern {
+            regex::Regex::new(pattern)
+                .map_err(|e| format!("Invalid port pattern for '{}': {}", name, e))?;
+        }
+        for dep in &service.depends_on {
+            if !config.services.contains_key(dep) {
+                return Err(format!("Service '{}' depends on unknown service '{}'", name, dep));
+            }
+        }
+        if let Some(ref probe) = service.ready {
+            let kinds = [probe.http.is_some(), probe.tcp.is_some(), probe.log.is_some()];
+            if kinds.iter().filter(|k| **k).count() != 1 {
+                return Err(format!(
+                    "Service '{}': `ready` needs exactly one of http, tcp or log",
+                    name
+                ));
+            }
+            if let Some(ref pattern) = probe.log {
+                regex::Regex::new(pattern)
+                    .map_err(|e| format!("Invalid log probe for '{}': {}", name, e))?;
+            }
+            if probe.http.is_some() && service.port.is_none() && service.port_pattern.is_none() && !service.preview {
+                return Err(format!(
+                    "Service '{}': an http probe needs `port` or `port_pattern`",
+                    name
+                ));
+            }
+        }
+    }
+
+    if config.services.values().filter(|s| s.preview).count() > 1 {
+        return Err("Only one service can have preview = true".to_string());
+    }
+
+    start_order(config).map(|_| ())
+}
+
+/// Dependencies first; ties broken by name so the order is stable
+pub fn start_order(config: &RunConfig) -> Result<Vec<String>, String> {
+    let mut order: Vec<String> = Vec::new();
+    let mut remaining: Vec<&String> = config.services.keys().collect();
+
+    while !remaining.is_empty() {
+        let ready = remaining.iter().position(|name| {
+            config.services[*name]
+                .depends_on
+                .iter()
+                .all(|dep| order.contains(dep))
+        });
+        match ready {
+            Some(i) => order.push(remaining.remove(i).clone()),
+            None => {
+                let names: Vec<&str> = remaining.iter().map(|n| n.as_str()).collect();
+                return Err(format!("Dependency cycle between services: {}", names.join(", ")));
+            }
+        }
+    }
+
+    Ok(order)
+}
+
+/// The service shown in the preview pane: the one marked `preview`,
+/// or the only service if there's just one
+pub fn preview_service(config: &RunConfig) -> Option<String> {
+    config
+        .services
+        .iter()
+        .find(|(_, s)| s.preview)
+        .map(|(name, _)| name.clone())
+        .or_else(|| {
+            (config.services.len() == 1).then(|| config.services.keys().next().cloned()).flatten()
+        })
+}
+
+// ── Start / Stop ──────────────────────────────────────────────
+
+/// Start every service in dependency order, waiting for each to be ready
+/// before starting the ones that depend on it. If one fails, the ones
+/// already started are stopped again. Blocks — call from a blocking task.
+pub fn start(app_handle: &tauri::AppHandle, project: &str) -> Result<RunConfigStarted, String> {
+    let root = project_root(project)?;
+    let key = root.to_string_lossy().to_string();
+    let config = load(&key)?.ok_or_else(|| "This project has no .mydevify/services.toml".to_string())?;
+    let preview_name = preview_service(&config);
+
+    // Restart semantics — stop whatever is still running from last time
+    stop_services(&key, &config);
+
+    let mut result = RunConfigStarted {
+        preview: None,
+        preview_service: preview_name.clone(),
+        services: Vec::new(),
+    };
+
+    for name in start_order(&config)? {
+        let service = &config.services[&name];
+        let outcome = if preview_name.as_deref() == Some(name.as_str()) {
+            start_preview(app_handle, &root, service).map(|started| result.preview = Some(started))
+        } else {
+            start_supervised(app_handle, &key, &name, service).map(|status| result.services.push(status))
+        };
+
+        if let Err(e) = outcome {
+            stop_services(&key, &config);
+            if result.preview.is_some() {
+                dev_server::stop_internal();
+            }
+            return Err(format!("Service '{}' failed to start: {}", name, e));
+        }
+    }
+
+    Ok(result)
+}
+
+/// Stop all services declared in the project's services.toml
+pub fn stop(project: &str) -> Result<(), String> {
+    let root = project_root(project)?;
+    let key = root.to_string_lossy().to_string();
+    let config = load(&key)?.ok_or_else(|| "This project has no .mydevify/services.toml".to_string())?;
+    if preview_service(&config).is_some() {
+        dev_server::stop_internal();
+    }
+    stop_services(&key, &config);
+    Ok(())
+}
+
+/// Stop the supervisor-managed services, dependents first
+fn stop_services(key: &str, config: &RunConfig) {
+    let running: Vec<String> = supervisor::list(key)
+        .map(|statuses| {
+            statuses
+                .into_iter()
+                .filter(|s| s.pid.is_some())
+                .map(|s| s.name)
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let mut order = start_order(config).unwrap_or_default();
+    order.reverse();
+    for name in order.iter().filter(|n| running.contains(n)) {
+        let _ = supervisor::stop(key, name);
+    }
+}
+
+fn start_preview(
+    app_handle: &tauri::AppHandle,
+    root: &Path,
+    service: &ServiceConfig,
+) -> Result<DevServerStarted, String> {
+    let cwd = service_cwd(root, service);
+    let health_check_path = match service.ready {
+        Some(ReadinessProbe { http: Some(ref path), .. }) => Some(path.clone()),
+        // tcp/log probes are checked once the dev server is up
+        Some(_) => None,
+        None => DevServerOptions::default().health_check_path,
+    };
+    let options = DevServerOptions {
+        auto_restart: service.auto_restart,
+        health_check_path,
+        env: service.env.clone(),
+        port: service.port,
+        ..DevServerOptions::default()
+    };
+    let pattern = service
+        .port_pattern
+        .clone()
+        .unwrap_or_else(|| DEFAULT_PORT_PATTERN.to_string());
+
+    let started = dev_server::start(
+        app_handle,
+        service.command.clone(),
+        cwd.to_string_lossy().to_string(),
+        pattern,
+        options,
+    )?;
+    if let Err(e) = wait_until_preview_ready(service) {
+        dev_server::stop_internal();
+        return Err(e);
+    }
+    Ok(started)
+}
+
+fn start_supervised(
+    app_handle: &tauri::AppHandle,
+    key: &str,
+    name: &str,
+    service: &ServiceConfig,
+) -> Result<ServiceStatus, String> {
+    let definition = ServiceDefinition {
+        name: name.to_string(),
+        command: service.command.clone(),
+        cwd: service.cwd.clone(),
+        env: service.env.clone(),
+        port_pattern: service.port_pattern.clone(),
+    };
+    supervisor::start_definition(app_handle, key, definition)?;
+    wait_until_ready(key, name, service)
+}
+
+fn service_cwd(root: &Path, service: &ServiceConfig) -> PathBuf {
+    match service.cwd {
+        Some(ref rel) => root.join(rel),
+        None => root.to_path_buf(),
+    }
+}
+
+// ── Readiness ─────────────────────────────────────────────────
+
+/// Poll the preview service's tcp or log probe (http is handled by the
+/// dev server's own health check) until it passes, the dev server dies,
+/// or the probe times out
+fn wait_until_preview_ready(service: &ServiceConfig) -> Result<(), String> {
+    let Some(ref probe) = service.ready else {
+        return Ok(());
+    };
+    let log_re = match probe.log {
+        Some(ref pattern) => Some(regex::Regex::new(pattern).map_err(|e| e.to_string())?),
+        None => None,
+    };
+    if probe.tcp.is_none() && log_re.is_none() {
+        return Ok(());
+    }
+    let timeout = probe.timeout_secs.map(Duration::from_secs).unwrap_or(DEFAULT_READY_TIMEOUT);
+    let deadline = Instant::now() + timeout;
+    let mut cursor = None;
+    let mut log_matched = false;
+
+    loop {
+        if dev_server::current_port().is_none() {
+            return Err("exited before it was ready".to_string());
+        }
+
+        if let Some(ref re) = log_re {
+            let read = dev_server::read_output(&crate::log_buffer::LogReadOptions {
+                since: cursor,
+                limit: None,
+                strip_ansi: true,
+            });
+            cursor = Some(read.cursor);
+            log_matched = log_matched || read.lines.iter().any(|line| re.is_match(&line.text));
+        }
+        let ready = match probe.tcp {
+            Some(port) => dev_server::port_open(port),
+            None => log_matched,
+        };
+
+        if ready {
+            return Ok(());
+        }
+        if Instant::now() >= deadline {
+            return Err(format!("not ready after {} seconds", timeout.as_secs()));
+        }
+        std::thread::sleep(Duration::from_millis(250));
+    }
+}
+
+/// Poll the service's probe until it passes, the service dies, or the
+/// probe times out
+fn wait_until_ready(key: &str, name: &str, service: &ServiceConfig) -> Result<ServiceStatus, String> {
+    let timeout = service
+        .ready
+        .as_ref()
+        .and_then(|p| p.timeout_secs)
+        .map(Duration::from_secs)
+        .unwrap_or(DEFAULT_READY_TIMEOUT);
+    let log_re = match service.ready {
+        Some(ReadinessProbe { log: Some(ref pattern), .. }) => {
+            Some(regex::Regex::new(pattern).map_err(|e| e.to_string())?)
+        }
+        _ => None,
+    };
+    let deadline = Instant::now() + timeout;
+
+    loop {
+        let status = supervisor::status(key, name)?
+            .ok_or_else(|| "Service disappeared while starting".to_string())?;
+
+        match status.state {
+            ServiceState::Exited { code } => {
+                let code = code.map_or("?".to_string(), |c| c.to_string());
+                return Err(format!("exited with code {}", code));
+            }
+            ServiceState::Failed { ref error } => return Err(error.clone()),
+            ServiceState::Stopped => return Err("was stopped".to_string()),
+            _ => {}
+        }
+
+        let port = service.port.or(status.port);
+        let ready = match service.ready {
+            Some(ReadinessProbe { http: Some(ref path), .. }) => {
+                port.is_some_and(|p| dev_server::http_probe(p, path).is_some())
+            }
+            Some(ReadinessProbe { tcp: Some(p), .. }) => dev_server::port_open(p),
+            Some(_) => supervisor::get_logs(key, name, Some(2_000))?
+                .iter()
+                .any(|line| log_re.as_ref().is_some_and(|re| re.is_match(&crate::strip_ansi_codes(&line.text)))),
+            // No probe: a fixed port must accept connections, a pattern must
+            // have matched, otherwise the process being up is enough
+            None => match (service.port, &service.port_pattern) {
+                (Some(p), _) => dev_server::port_open(p),
+                (None, Some(_)) => status.state == ServiceState::Running,
+                (None, None) => true,
+            },
+        };
+
+        if ready {
+            return Ok(status);
+        }
+        if Instant::now() >= deadline {
+            return Err(format!("not ready after {} seconds", timeout.as_secs()));
+        }
+        std::thread::sleep(Duration::from_millis(250));
+    }
+}
@@ -0,0 +1,319 @@
+// ── Command Sandbox — Linux (Landlock) ────────────────────────
+//
+// Opt-in confinement for shell commands the AI (or a scheduled task) runs.
+// Reads stay open so toolchains keep working, but writes are limited to the
+// project root, temp dirs and a few device nodes. Network access can be cut
+// off with a private user + network namespace, falling back to Landlock TCP
+// rules on kernels where unprivileged namespaces are disabled.
+// Other platforms report the sandbox as unavailable.
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+// ── Data Model ────────────────────────────────────────────────
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SandboxConfig {
+    /// Confine `execute_command` and task RunCommand/RunScript steps
+    pub enabled: bool,
+    /// Deny all network access to sandboxed commands
+    pub block_network: bool,
+    /// Additional writable directories (e.g. ~/.npm for installs)
+    #[serde(default)]
+    pub extra_writable_paths: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NetworkMode {
+    /// Network access is not restricted
+    Allowed,
+    /// Command runs in an empty network namespace (no interfaces at all)
+    Namespace,
+    /// Landlock denies TCP bind/connect (UDP is not covered)
+    LandlockTcp,
+}
+
+/// Attached to a CommandResult when the command ran inside the sandbox
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SandboxReport {
+    pub writable_roots: Vec<String>,
+    pub network: NetworkMode,
+    /// Output lines that look like the sandbox denied something
+    pub violations: Vec<String>,
+}
+
+/// What the running system can enforce
+#[derive(Debug, Clone, Serialize)]
+pub struct SandboxSupport {
+    pub platform_supported: bool,
+    /// Landlock ABI version reported by the kernel (0 = unavailable)
+    pub landlock_abi: i32,
+    pub user_namespaces: bool,
+}
+
+// ── Storage ───────────────────────────────────────────────────
+
+/// Get the path to the sandbox settings file in the app data directory
+fn get_config_file_path() -> PathBuf {
+    let home = dirs_next::home_dir().unwrap_or_else(|| PathBuf::from("."));
+    let data_dir = home.join(".mydevify").join("data");
+    fs::create_dir_all(&data_dir).ok();
+    data_dir.join("sandbox.json")
+}
+
+fn load_config() -> SandboxConfig {
+    fs::read_to_string(get_config_file_path())
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn save_config(config: &SandboxConfig) -> Result<(), String> {
+    let json = serde_json::to_string_pretty(config).map_err(|e| e.to_string())?;
+    fs::write(get_config_file_path(), json).map_err(|e| e.to_string())
+}
+
+static SANDBOX_CONFIG: once_cell::sync::Lazy<Mutex<SandboxConfig>> =
+    once_cell::sync::Lazy::new(|| Mutex::new(load_config()));
+
+pub fn get_config() -> SandboxConfig {
+    SANDBOX_CONFIG.lock().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+/// Update and persist the sandbox settings.
+/// Refuses to enable the sandbox on systems that can't enforce it.
+pub fn set_config(config: SandboxConfig) -> Result<SandboxConfig, String> {
+    if config.enabled {
+        let support = get_support();
+        if !support.platform_supported {
+            return Err("The command sandbox is only available on Linux".to_string());
+        }
+        if support.landlock_abi < 1 {
+            return Err("Landlock is not enabled in this kernel (requires Linux 5.13+)".to_string());
+        }
+        if config.block_network && !support.user_namespaces && support.landlock_abi < 4 {
+            return Err(
+                "Blocking network access needs unprivileged user namespaces or Linux 6.7+".to_string(),
+            );
+        }
+    }
+
+    save_config(&config)?;
+    *SANDBOX_CONFIG.lock().map_err(|e| e.to_string())? = config.clone();
+    Ok(config)
+}
+
+pub fn get_support() -> SandboxSupport {
+    SandboxSupport {
+        platform_supported: cfg!(target_os = "linux"),
+        landlock_abi: landlock_abi(),
+        user_namespaces: user_namespaces_available(),
+    }
+}
+
+// ── Applying the Sandbox ──────────────────────────────────────
+
+/// Confine `cmd` if the sandbox is enabled. Writes are allowed under
+/// `writable_root`, temp dirs and any configured extra paths.
+/// Returns None when the sandbox is off, or an error if it's on but
+/// can't be enforced (we never silently run unconfined).
+pub fn apply(
+    cmd: &mut std::process::Command,
+    writable_root: &Path,
+) -> Result<Option<SandboxReport>, String> {
+    let config = get_config();
+    if !config.enabled {
+        return Ok(None);
+    }
+
+    let mut writable: Vec<PathBuf> = vec![writable_root.to_path_buf(), std::env::temp_dir()];
+    for extra in ["/tmp", "/var/tmp"] {
+        let p = PathBuf::from(extra);
+        if !writable.contains(&p) {
+            writable.push(p);
+        }
+    }
+    writable.extend(config.extra_writable_paths.iter().map(PathBuf::from));
+
+    let network = confine(cmd, &writable, config.block_network)?;
+
+    Ok(Some(SandboxReport {
+        writable_roots: writable
+            .iter()
+            .map(|p| p.to_string_lossy().to_string())
+            .collect(),
+        network,
+        violations: Vec::new(),
+    }))
+}
+
+/// Scan command output for signs that the sandbox blocked something.
+/// Landlock doesn't report denials to the caller, so this matches the
+/// error messages tools print when they get EACCES/EPERM/EROFS or
+/// can't reach the network.
+pub fn collect_violations(report: &mut SandboxReport, output: &str) {
+    let mut patterns = vec![
+        "permission denied",
+        "operation not permitted",
+        "read-only file system",
+        "eacces",
+        "eperm",
+        "erofs",
+    ];
+    if report.network != NetworkMode::Allowed {
+        patterns.extend([
+            "network is unreachable",
+            "enetunreach",
+            "could not resolve host",
+            "temporary failure in name resolution",
+            "eai_again",
+            "getaddrinfo",
+        ]);
+    }
+
+    for line in output.lines() {
+        let lower = line.to_lowercase();
+        if patterns.iter().any(|p| lower.contains(p)) {
+            let trimmed = line.trim().to_string();
+            if !report.violations.contains(&trimmed) {
+                report.violations.push(trimmed);
+            }
+            // Keep the report small — the full output is still in stderr
+            if report.violations.len() >= 20 {
+                break;
+            }
+        }
+    }
+}
+
+// ── Platform Implementation ───────────────────────────────────
+
+#[cfg(target_os = "linux")]
+fn landlock_abi() -> i32 {
+    // LANDLOCK_CREATE_RULESET_VERSION = 1 << 0
+    let v = unsafe {
+        libc::syscall(
+            libc::SYS_landlock_create_ruleset,
+            std::ptr::null::<libc::c_void>(),
+            0usize,
+            1u32,
+        )
+    };
+    if v < 0 {
+        0
+    } else {
+        v as i32
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+fn landlock_abi() -> i32 {
+    0
+}
+
+/// Check the sysctls distros use to turn off unprivileged user namespaces
+#[cfg(target_os = "linux")]
+fn user_namespaces_available() -> bool {
+    let read = |p: &str| fs::read_to_string(p).ok().map(|s| s.trim().to_string());
+
+    if read("/proc/sys/user/max_user_namespaces").as_deref() == Some("0") {
+        return false;
+    }
+    // Debian/older Ubuntu
+    if read("/proc/sys/kernel/unprivileged_userns_clone").as_deref() == Some("0") {
+        return false;
+    }
+    // Ubuntu 24.04+ AppArmor restriction
+    if read("/proc/sys/kernel/apparmor_restrict_unprivileged_userns").as_deref() == Some("1") {
+        return false;
+    }
+    true
+}
+
+#[cfg(not(target_os = "linux"))]
+fn user_namespaces_available() -> bool {
+    false
+}
+
+/// Build the Landlock ruleset in the parent and enforce it in the child
+/// right before exec. Only raw syscalls run after fork.
+#[cfg(target_os = "linux")]
+fn confine(
+    cmd: &mut std::process::Command,
+    writable: &[PathBuf],
+    block_network: bool,
+) -> Result<NetworkMode, String> {
+    use landlock::{
+        path_beneath_rules, Access, AccessFs, AccessNet, Ruleset, RulesetAttr, RulesetCreatedAttr,
+        ABI,
+    };
+    use std::os::unix::process::CommandExt;
+
+    let abi = ABI::from(landlock_abi());
+    if abi == ABI::Unsupported {
+        return Err("Sandbox is enabled but Landlock is not available in this kernel".to_string());
+    }
+
+    let network = if !block_network {
+        NetworkMode::Allowed
+    } else if user_namespaces_available() {
+        NetworkMode::Namespace
+    } else if abi >= ABI::V4 {
+        NetworkMode::LandlockTcp
+    } else {
+        return Err("Sandbox can't block network access on this system".to_string());
+    };
+
+    let sandbox_err = |e: landlock::RulesetError| format!("Failed to set up sandbox: {}", e);
+
+    let mut ruleset = Ruleset::default()
+        .handle_access(AccessFs::from_all(abi))
+        .map_err(sandbox_err)?;
+    if network == NetworkMode::LandlockTcp {
+        // Handling TCP access without adding any port rules denies it all
+        ruleset = ruleset
+            .handle_access(AccessNet::from_all(abi))
+            .map_err(sandbox_err)?;
+    }
+
+    let devices = ["/dev/null", "/dev/zero", "/dev/full", "/dev/tty", "/dev/pts", "/dev/shm"];
+    let ruleset = ruleset
+        .create()
+        .map_err(sandbox_err)?
+        .add_rules(path_beneath_rules(["/"], AccessFs::from_read(abi)))
+        .map_err(sandbox_err)?
+        .add_rules(path_beneath_rules(writable, AccessFs::from_all(abi)))
+        .map_err(sandbox_err)?
+        .add_rules(path_beneath_rules(devices, AccessFs::from_all(abi)))
+        .map_err(sandbox_err)?;
+
+    let unshare_net = network == NetworkMode::Namespace;
+    let mut ruleset = Some(ruleset);
+
+    unsafe {
+        cmd.pre_exec(move || {
+            if unshare_net && libc::unshare(libc::CLONE_NEWUSER | libc::CLONE_NEWNET) != 0 {
+                return Err(std::io::Error::last_os_error());
+            }
+            if let Some(r) = ruleset.take() {
+                r.restrict_self()
+                    .map_err(|_| std::io::Error::from(std::io::ErrorKind::PermissionDenied))?;
+            }
+            Ok(())
+        });
+    }
+
+    Ok(network)
+}
+
+#[cfg(not(target_os = "linux"))]
+fn confine(
+    _cmd: &mut std::process::Command,
+    _writable: &[PathBuf],
+    _block_network: bool,
+) -> Result<NetworkMode, String> {
+    Err("The command sandbox is only available on Linux".to_string())
+}
@@ -1,15 +1,121 @@
+use crate::console_capture;
+use crate::live_reload::{self, LiveReload, ReloadNotice};
+use crate::mock_api::MockCache;
+use crate::request_log::{self, MissingFile, ResolvedFile};
+use crate::routing_rules::{Resolution, RulesCache};
 use axum::Router;
-use axum::extract::State;
-use axum::http::StatusCode;
+use axum::extract::{ConnectInfo, State};
+use axum::body::Body;
+use axum::http::{header, HeaderMap, HeaderValue, StatusCode};
+use axum::response::sse::{Event, KeepAlive, Sse};
 use axum::response::{Html, IntoResponse, Response};
-use std::net::SocketAddr;
+use hyper_util::client::legacy::connect::HttpConnector;
+use hyper_util::client::legacy::Client;
+use hyper_util::rt::{TokioExecutor, TokioIo};
+use hyper_util::service::TowerToHyperService;
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::convert::Infallible;
+use std::net::{IpAddr, SocketAddr};
 use std::path::PathBuf;
 use std::sync::{Arc, Mutex};
-use tokio::sync::oneshot;
+use tokio::io::{AsyncReadExt, AsyncSeekExt};
+use tokio::sync::{broadcast, watch};
+use tokio_rustls::TlsAcceptor;
+use tokio_util::io::ReaderStream;
+use tower_http::compression::predicate::{Predicate, SizeAbove};
+use tower_http::compression::CompressionLayer;
 
-// Server state - tracks the running server so we can shut it down
-static SERVER_SHUTDOWN: Mutex<Option<oneshot::Sender<()>>> = Mutex::new(None);
-static SERVER_PORT: Mutex<Option<u16>> = Mutex::new(None);
+// Running preview servers keyed by project path (or window id), so each
+// project window keeps its own preview
+static PREVIEW_SERVERS: Lazy<Mutex<HashMap<String, PreviewServer>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// Ports tried after the preferred one before letting the OS pick
+const PORT_ATTEMPTS: u16 = 20;
+
+/// One running preview server
+struct PreviewServer {
+    port: u16,
+    mode: PreviewMode,
+    root: Option<PathBuf>,
+    options: PreviewOptions,
+    /// Kept so LAN sharing can serve the same app on another address
+    router: Router,
+    /// Dropping (or firing) it stops the server
+    shutdown: watch::Sender<()>,
+    /// Static mode only — dropped on stop
+    live_reload: Option<Arc<LiveReload>>,
+    /// Why files aren't being watched, when watching failed
+    live_reload_error: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PreviewMode {
+    /// Serves files from a project directory
+    Static,
+    /// Reverse proxy in front of the dev server
+    Proxy,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PreviewServerInfo {
+    pub key: String,
+    pub port: u16,
+    pub mode: PreviewMode,
+    pub root: Option<String>,
+    pub https: bool,
+    pub production: bool,
+    /// Set when files couldn't be watched, so pages won't reload on change
+    pub live_reload_error: Option<String>,
+    pub url: String,
+    pub lan_shared: bool,
+}
+
+/// State of the server in static mode
+struct ServerState {
+    root: PathBuf,
+    production: bool,
+    /// None in production mode, or when the project couldn't be watched
+    live_reload: Option<Arc<LiveReload>>,
+    routing: RulesCache,
+    mocks: MockCache,
+}
+
+/// State of the server in reverse-proxy mode
+struct ProxyState {
+    client: Client<HttpConnector, Body>,
+    /// Whether clients reach the proxy over TLS
+    https: bool,
+}
+
+/// A running preview server's router and how it's served
+#[derive(Clone)]
+pub(crate) struct ServedApp {
+    pub router: Router,
+    pub port: u16,
+    pub https: bool,
+}
+
+/// How the preview server is exposed
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct PreviewOptions {
+    /// Serve over HTTPS with a certificate from the local CA
+    #[serde(default)]
+    pub https: bool,
+    /// Serve a build output as the host would: no live reload, and the
+    /// project's Cache-Control rules apply
+    #[serde(default)]
+    pub production: bool,
+}
+
+/// Larger proxied pages would be buffered just to inject the console reporter
+const MAX_INJECTED_HTML: usize = 16 * 1024 * 1024;
+
+/// How long open connections get to finish once the server is stopped
+const SHUTDOWN_GRACE: std::time::Duration = std::time::Duration::from_secs(2);
 
 /// Determine the correct Content-Type for a file based on its extension.
 fn content_type_for(path: &str) -> &'static str {
@@ -59,131 +165,665 @@ fn content_type_for(path: &str) -> &'static str {
 
 /// Handler that serves static files from the project directory.
 async fn serve_file(
-    State(root): State<Arc<PathBuf>>,
+    State(state): State<Arc<ServerState>>,
     request: axum::extract::Request,
 ) -> Response {
-    let req_path = request.uri().path().trim_start_matches('/');
+    let req_path = request.uri().path();
 
-    // Build the file path, default to index.html for root
-    let file_path = if req_path.is_empty() {
-        root.join("index.html")
-    } else {
-        let joined = root.join(req_path);
-        if joined.is_dir() {
-            joined.join("index.html")
-        } else {
-            joined
-        }
-    };
+    // Mock API routes answer before any file lookup
+    if let Some(response) = state
+        .mocks
+        .current()
+        .respond(request.method(), req_path, request.uri().query())
+        .await
+    {
+        return response;
+    }
+
+    let rules = state.routing.current();
 
-    // Security: make sure resolved path is within the project root
-    let canonical = match file_path.canonicalize() {
-        Ok(p) => p,
-        Err(_) => {
-            return (StatusCode::NOT_FOUND, "Not found").into_response();
+    // Host routing (redirects, rewrites, clean URLs, 404 page) decides
+    // which file answers; it only ever returns files inside the root
+    let (canonical, status) = match rules.resolve(&state.root, req_path, request.uri().query()) {
+        Resolution::File { path, status } => (path, status),
+        Resolution::Redirect { location, status } => {
+            let status = StatusCode::from_u16(status).unwrap_or(StatusCode::FOUND);
+            let mut response = status.into_response();
+            if let Ok(v) = HeaderValue::from_str(&location) {
+                response.headers_mut().insert(header::LOCATION, v);
+            }
+            return response;
         }
-    };
-    let root_canonical = match root.canonicalize() {
-        Ok(p) => p,
-        Err(_) => {
-            return (StatusCode::INTERNAL_SERVER_ERROR, "Server error").into_response();
+        Resolution::NotFound => {
+            let mut response = (StatusCode::NOT_FOUND, "Not found").into_response();
+            let missing = state.root.join(req_path.trim_start_matches('/'));
+            response.extensions_mut().insert(MissingFile(missing));
+            return response;
         }
     };
-    if !canonical.starts_with(&root_canonical) {
-        return (StatusCode::FORBIDDEN, "Forbidden").into_response();
-    }
+    let status = StatusCode::from_u16(status).unwrap_or(StatusCode::OK);
 
-    // Read the file
-    let bytes = match tokio::fs::read(&canonical).await {
-        Ok(b) => b,
-        Err(_) => {
-            return (StatusCode::NOT_FOUND, "Not found").into_response();
+    let metadata = match tokio::fs::metadata(&canonical).await {
+        Ok(m) if m.is_file() => m,
+        _ => {
+            let mut response = (StatusCode::NOT_FOUND, "Not found").into_response();
+            response.extensions_mut().insert(MissingFile(canonical));
+            return response;
         }
     };
 
     let path_str = canonical.to_string_lossy();
     let ct = content_type_for(&path_str);
+    let validators = Validators::new(&metadata);
+    let request_headers = request.headers();
 
-    // For HTML files, use axum's Html wrapper to guarantee text/html
-    if ct.starts_with("text/html") {
+    if status == StatusCode::OK && validators.not_modified(request_headers) {
+        let mut response = StatusCode::NOT_MODIFIED.into_response();
+        validators.apply(response.headers_mut());
+        response.extensions_mut().insert(ResolvedFile(canonical.clone()));
+        return response;
+    }
+
+    // For HTML files, use axum's Html wrapper to guarantee text/html.
+    // HTML is small and gets the console reporter (and, outside production
+    // mode, the live reload script) injected, so it's read whole rather
+    // than streamed.
+    let mut response = if ct.starts_with("text/html") {
+        let bytes = match tokio::fs::read(&canonical).await {
+            Ok(b) => b,
+            Err(_) => {
+                return (StatusCode::NOT_FOUND, "Not found").into_response();
+            }
+        };
         match String::from_utf8(bytes) {
-            Ok(html_string) => Html(html_string).into_response(),
+            Ok(html_string) => {
+                let html = console_capture::inject_reporter(&html_string);
+                match state.live_reload {
+                    Some(_) => Html(live_reload::inject_client(&html)).into_response(),
+                    None => Html(html).into_response(),
+                }
+            }
             Err(e) => {
                 (
                     StatusCode::OK,
-                    [("content-type", ct), ("cache-control", "no-cache")],
+                    [("content-type", ct)],
                     e.into_bytes(),
                 ).into_response()
             }
         }
     } else {
-        (
-            StatusCode::OK,
-            [("content-type", ct), ("cache-control", "no-cache")],
-            bytes,
-        ).into_response()
+        // Ranges only make sense for the file the URL actually names
+        let request_headers = if status == StatusCode::OK { request_headers } else { &HeaderMap::new() };
+        match stream_file(&canonical, metadata.len(), ct, &validators, request_headers).await {
+            Ok(r) => r,
+            Err(_) => {
+                return (StatusCode::NOT_FOUND, "Not found").into_response();
+            }
+        }
+    };
+
+    if status != StatusCode::OK {
+        *response.status_mut() = status;
     }
+    validators.apply(response.headers_mut());
+    apply_custom_headers(response.headers_mut(), &rules.headers_for(req_path), state.production);
+    response.extensions_mut().insert(ResolvedFile(canonical.clone()));
+    response
 }
 
-/// Start a static file server for the given project directory.
-pub async fn start(project_path: &str, preferred_port: u16) -> Result<u16, String> {
-    // Stop any existing server first
-    stop();
+/// Headers from `_headers` / netlify.toml / vercel.json. Cache-Control is
+/// left alone unless `cache_control` is set, so long-lived caching rules
+/// don't break live reload.
+fn apply_custom_headers(headers: &mut HeaderMap, custom: &[(String, String)], cache_control: bool) {
+    for (name, value) in custom {
+        let (Ok(name), Ok(value)) = (header::HeaderName::from_bytes(name.as_bytes()), HeaderValue::from_str(value)) else {
+            continue;
+        };
+        if cache_control || name != header::CACHE_CONTROL {
+            headers.insert(name, value);
+        }
+    }
+}
 
-    let path = PathBuf::from(project_path);
-    if !path.exists() || !path.is_dir() {
-        return Err("Invalid project path".to_string());
+/// Cache validators for a file: a weak ETag (the body may be compressed
+/// or have the live reload script injected) and Last-Modified.
+struct Validators {
+    etag: String,
+    last_modified: Option<String>,
+    modified_secs: Option<i64>,
+}
+
+impl Validators {
+    fn new(metadata: &std::fs::Metadata) -> Self {
+        let modified = metadata
+            .modified()
+            .ok()
+            .map(chrono::DateTime::<chrono::Utc>::from);
+        let nanos = modified
+            .and_then(|m| m.timestamp_nanos_opt())
+            .unwrap_or_default();
+        Self {
+            etag: format!("W/\"{:x}-{:x}\"", metadata.len(), nanos),
+            last_modified: modified.map(|m| m.format("%a, %d %b %Y %H:%M:%S GMT").to_string()),
+            modified_secs: modified.map(|m| m.timestamp()),
+        }
+    }
+
+    /// Whether the client's cached copy is still current. If-None-Match
+    /// wins over If-Modified-Since when both are sent.
+    fn not_modified(&self, headers: &HeaderMap) -> bool {
+        if let Some(if_none_match) = header_str(headers, header::IF_NONE_MATCH) {
+            return if_none_match
+                .split(',')
+                .map(|tag| tag.trim())
+                .any(|tag| tag == "*" || strip_weak(tag) == strip_weak(&self.etag));
+        }
+        match (header_str(headers, header::IF_MODIFIED_SINCE), self.modified_secs) {
+            (Some(since), Some(modified)) => chrono::DateTime::parse_from_rfc2822(since)
+                .map(|since| modified <= since.timestamp())
+                .unwrap_or(false),
+            _ => false,
+        }
     }
 
-    let root = Arc::new(path);
+    /// If-Range is compared leniently — the ETag is weak, but an unchanged
+    /// file is exactly what the client is asking about
+    fn matches_if_range(&self, value: &str) -> bool {
+        strip_weak(value) == strip_weak(&self.etag) || self.last_modified.as_deref() == Some(value)
+    }
 
-    let app = Router::new()
-        .fallback(serve_file)
-        .with_state(root);
-
-    // Try preferred port first, fall back to port 0 (OS picks available port)
-    let listener = match tokio::net::TcpListener::bind(
-        SocketAddr::from(([127, 0, 0, 1], preferred_port))
-    ).await {
-        Ok(l) => l,
-        Err(_) => {
-            tokio::net::TcpListener::bind(SocketAddr::from(([127, 0, 0, 1], 0)))
-                .await
-                .map_err(|e| format!("Failed to bind any port: {}", e))?
+    fn apply(&self, headers: &mut HeaderMap) {
+        if let Ok(v) = HeaderValue::from_str(&self.etag) {
+            headers.insert(header::ETAG, v);
+        }
+        if let Some(v) = self.last_modified.as_deref().and_then(|lm| HeaderValue::from_str(lm).ok()) {
+            headers.insert(header::LAST_MODIFIED, v);
+        }
+        // Always revalidate — files change constantly while editing
+        headers.insert(header::CACHE_CONTROL, HeaderValue::from_static("no-cache"));
+    }
+}
+
+fn header_str(headers: &HeaderMap, name: header::HeaderName) -> Option<&str> {
+    headers.get(name).and_then(|v| v.to_str().ok())
+}
+
+fn strip_weak(tag: &str) -> &str {
+    tag.trim_start_matches("W/")
+}
+
+/// Parse a single `bytes=` range against a file length. Returns
+/// Ok(None) for no/unsupported range (serve the whole file) and
+/// Err(()) when the range can't be satisfied.
+fn parse_range(value: &str, len: u64) -> Result<Option<(u64, u64)>, ()> {
+    let spec = match value.trim().strip_prefix("bytes=") {
+        Some(s) => s.trim(),
+        None => return Ok(None),
+    };
+    // Multipart ranges are rare for media; the full body is a valid answer
+    if spec.contains(',') {
+        return Ok(None);
+    }
+    let (start, end) = spec.split_once('-').ok_or(())?;
+    let (start, end) = match (start.trim(), end.trim()) {
+        ("", suffix) => {
+            let suffix: u64 = suffix.parse().map_err(|_| ())?;
+            if suffix == 0 {
+                return Err(());
+            }
+            (len.saturating_sub(suffix), len.saturating_sub(1))
+        }
+        (start, "") => (start.parse().map_err(|_| ())?, len.saturating_sub(1)),
+        (start, end) => {
+            let start: u64 = start.parse().map_err(|_| ())?;
+            let end: u64 = end.parse().map_err(|_| ())?;
+            (start, end.min(len.saturating_sub(1)))
+        }
+    };
+    if len == 0 || start >= len || start > end {
+        return Err(());
+    }
+    Ok(Some((start, end)))
+}
+
+/// Stream a non-HTML file, honouring a byte Range request.
+async fn stream_file(
+    path: &std::path::Path,
+    len: u64,
+    content_type: &'static str,
+    validators: &Validators,
+    request_headers: &HeaderMap,
+) -> std::io::Result<Response> {
+    let range_applies = header_str(request_headers, header::IF_RANGE)
+        .map(|v| validators.matches_if_range(v))
+        .unwrap_or(true);
+    let range = match header_str(request_headers, header::RANGE) {
+        Some(value) if range_applies => parse_range(value, len),
+        _ => Ok(None),
+    };
+
+    let mut file = tokio::fs::File::open(path).await?;
+    let builder = Response::builder()
+        .header(header::CONTENT_TYPE, content_type)
+        .header(header::ACCEPT_RANGES, "bytes");
+
+    let response = match range {
+        Ok(Some((start, end))) => {
+            file.seek(std::io::SeekFrom::Start(start)).await?;
+            let length = end - start + 1;
+            builder
+                .status(StatusCode::PARTIAL_CONTENT)
+                .header(header::CONTENT_LENGTH, length)
+                .header(header::CONTENT_RANGE, format!("bytes {}-{}/{}", start, end, len))
+                .body(Body::from_stream(ReaderStream::new(file.take(length))))
+        }
+        Ok(None) => builder
+            .status(StatusCode::OK)
+            .header(header::CONTENT_LENGTH, len)
+            .body(Body::from_stream(ReaderStream::new(file))),
+        Err(()) => builder
+            .status(StatusCode::RANGE_NOT_SATISFIABLE)
+            .header(header::CONTENT_RANGE, format!("bytes */{}", len))
+            .body(Body::empty()),
+    };
+    Ok(response.unwrap_or_else(|_| StatusCode::INTERNAL_SERVER_ERROR.into_response()))
+}
+
+/// Compress text-like responses only — media and fonts are already
+/// compressed. Partial (206) responses are never compressed.
+fn should_compress(headers: &HeaderMap) -> bool {
+    let ct = header_str(headers, header::CONTENT_TYPE).unwrap_or("");
+    [
+        "text/html",
+        "text/css",
+        "text/plain",
+        "application/javascript",
+        "application/json",
+        "application/xml",
+        "image/svg+xml",
+        "application/wasm",
+    ]
+    .iter()
+    .any(|t| ct.starts_with(t))
+}
+
+/// SSE stream of reload notices for the injected client script.
+async fn live_reload_events(State(state): State<Arc<ServerState>>) -> Response {
+    let Some(live_reload) = &state.live_reload else {
+        return StatusCode::NOT_FOUND.into_response();
+    };
+    let rx = live_reload.subscribe();
+    let stream = futures_util::stream::unfold(rx, |mut rx| async move {
+        let event = match rx.recv().await {
+            Ok(ReloadNotice::Shutdown) | Err(broadcast::error::RecvError::Closed) => return None,
+            Ok(notice) => Event::default().json_data(&notice).unwrap_or_default(),
+            // Missed notices still mean "something changed"
+            Err(broadcast::error::RecvError::Lagged(_)) => {
+                Event::default().data(r#"{"kind":"reload","paths":[]}"#)
+            }
+        };
+        Some((Ok::<_, Infallible>(event), rx))
+    });
+    Sse::new(stream).keep_alive(KeepAlive::default()).into_response()
+}
+
+/// Forward a request to the dev server, including WebSocket upgrades
+/// (HMR). The upstream port is looked up per request so the proxy keeps
+/// working when the dev server restarts on a different port.
+async fn proxy_request(
+    State(state): State<Arc<ProxyState>>,
+    mut request: axum::extract::Request,
+) -> Response {
+    let Some(port) = crate::dev_server::current_port() else {
+        return (StatusCode::BAD_GATEWAY, "Dev server is not running").into_response();
+    };
+
+    let path_and_query = request.uri().path_and_query().map(|p| p.as_str()).unwrap_or("/");
+    match format!("http://127.0.0.1:{}{}", port, path_and_query).parse() {
+        Ok(uri) => *request.uri_mut() = uri,
+        Err(_) => return (StatusCode::BAD_REQUEST, "Bad request").into_response(),
+    }
+
+    let is_upgrade = request.headers().contains_key(header::UPGRADE);
+    let client_upgrade = is_upgrade.then(|| hyper::upgrade::on(&mut request));
+
+    let headers = request.headers_mut();
+    if !is_upgrade {
+        strip_hop_by_hop(headers);
+    }
+    // Pages come back uncompressed so the console reporter can be
+    // injected; the compression layer re-compresses them for the client
+    let wants_html = header_str(headers, header::ACCEPT).is_some_and(|a| a.contains("text/html"));
+    if wants_html {
+        headers.remove(header::ACCEPT_ENCODING);
+    }
+    // Dev servers reject unfamiliar Host headers (DNS rebinding protection)
+    if let Some(host) = headers.get(header::HOST).cloned() {
+        headers.insert("x-forwarded-host", host);
+    }
+    let proto = if state.https { "https" } else { "http" };
+    headers.insert("x-forwarded-proto", HeaderValue::from_static(proto));
+    if let Ok(host) = HeaderValue::from_str(&format!("localhost:{}", port)) {
+        headers.insert(header::HOST, host);
+    }
+
+    let mut response = match state.client.request(request).await {
+        Ok(r) => r,
+        Err(e) => {
+            return (
+                StatusCode::BAD_GATEWAY,
+                format!("Dev server on port {} is not answering: {}", port, e),
+            ).into_response();
         }
     };
 
+    if response.status() == StatusCode::SWITCHING_PROTOCOLS {
+        if let Some(client_upgrade) = client_upgrade {
+            let upstream_upgrade = hyper::upgrade::on(&mut response);
+            tokio::spawn(async move {
+                if let (Ok(client), Ok(upstream)) = tokio::join!(client_upgrade, upstream_upgrade) {
+                    let _ = tokio::io::copy_bidirectional(&mut TokioIo::new(client), &mut TokioIo::new(upstream)).await;
+                }
+            });
+        }
+        return response.map(|_| Body::empty());
+    }
+
+    let (mut parts, body) = response.into_parts();
+    strip_hop_by_hop(&mut parts.headers);
+    let is_html = header_str(&parts.headers, header::CONTENT_TYPE).is_some_and(|ct| ct.starts_with("text/html"));
+    if !(wants_html && is_html) || parts.headers.contains_key(header::CONTENT_ENCODING) {
+        return Response::from_parts(parts, Body::new(body));
+    }
+
+    match axum::body::to_bytes(Body::new(body), MAX_INJECTED_HTML).await {
+        Ok(bytes) => {
+            let html = console_capture::inject_reporter(&String::from_utf8_lossy(&bytes));
+            parts.headers.remove(header::CONTENT_LENGTH);
+            Response::from_parts(parts, Body::from(html))
+        }
+        Err(_) => (StatusCode::BAD_GATEWAY, "Dev server page could not be read").into_response(),
+    }
+}
+
+/// Drop connection-specific headers that mustn't be forwarded by a proxy
+fn strip_hop_by_hop(headers: &mut HeaderMap) {
+    for name in [
+        header::CONNECTION,
+        header::PROXY_AUTHENTICATE,
+        header::PROXY_AUTHORIZATION,
+        header::TE,
+        header::TRAILER,
+        header::TRANSFER_ENCODING,
+        header::UPGRADE,
+    ] {
+        headers.remove(name);
+    }
+    headers.remove("keep-alive");
+}
+
+/// Wrap a router in the compression layer shared by both modes
+fn with_compression(app: Router) -> Router {
+    app.layer(CompressionLayer::new().compress_when(SizeAbove::new(256).and(
+        |_: StatusCode, _: axum::http::Version, headers: &HeaderMap, _: &axum::http::Extensions| {
+            should_compress(headers)
+        },
+    )))
+}
+
+/// Bind `ip` (the preferred port or one of the next few, else any free
+/// one) and serve `app` until the returned sender fires or is dropped.
+/// Handlers can read the peer address through `ConnectInfo<SocketAddr>`.
+pub(crate) async fn serve(
+    app: Router,
+    ip: IpAddr,
+    preferred_port: u16,
+    tls: Option<Arc<rustls::ServerConfig>>,
+) -> Result<(u16, watch::Sender<()>), String> {
+    // Try preferred port first (other previews may hold it), fall back to
+    // port 0 (OS picks available port)
+    let mut listener = None;
+    if preferred_port != 0 {
+        for port in preferred_port..=preferred_port.saturating_add(PORT_ATTEMPTS) {
+            if let Ok(l) = tokio::net::TcpListener::bind(SocketAddr::new(ip, port)).await {
+                listener = Some(l);
+                break;
+            }
+        }
+    }
+    let listener = match listener {
+        Some(l) => l,
+        None => tokio::net::TcpListener::bind(SocketAddr::new(ip, 0))
+            .await
+            .map_err(|e| format!("Failed to bind any port: {}", e))?,
+    };
+
     let actual_port = listener
         .local_addr()
         .map_err(|e| e.to_string())?
         .port();
 
-    let (shutdown_tx, shutdown_rx) = oneshot::channel::<()>();
+    let (shutdown_tx, mut shutdown_rx) = watch::channel(());
+    let acceptor = tls.map(TlsAcceptor::from);
 
     tokio::spawn(async move {
-        axum::serve(listener, app)
-            .with_graceful_shutdown(async {
-                let _ = shutdown_rx.await;
-            })
-            .await
-            .ok();
+        let mut connections = tokio::task::JoinSet::new();
+        loop {
+            tokio::select! {
+                accepted = listener.accept() => {
+                    let Ok((stream, peer)) = accepted else { continue };
+                    let _ = stream.set_nodelay(true);
+                    let app = app.clone().layer(axum::Extension(ConnectInfo(peer)));
+                    let acceptor = acceptor.clone();
+                    connections.spawn(async move {
+                        match acceptor {
+                            Some(acceptor) => {
+                                if let Ok(stream) = acceptor.accept(stream).await {
+                                    serve_connection(stream, app).await;
+                                }
+                            }
+                            None => serve_connection(stream, app).await,
+                        }
+                    });
+                }
+                // Reap finished connections as we go
+                Some(_) = connections.join_next(), if !connections.is_empty() => {}
+                _ = shutdown_rx.changed() => break,
+            }
+        }
+        // The listener is dropped here, freeing the port. Open connections
+        // (keep-alive, event streams, WebSockets) get a bounded grace period.
+        drop(listener);
+        let _ = tokio::time::timeout(SHUTDOWN_GRACE, async {
+            while connections.join_next().await.is_some() {}
+        }).await;
+    });
+
+    Ok((actual_port, shutdown_tx))
+}
+
+/// Serve HTTP/1.1 on one accepted connection, allowing upgrades
+async fn serve_connection<S>(stream: S, app: Router)
+where
+    S: tokio::io::AsyncRead + tokio::io::AsyncWrite + Unpin + Send + 'static,
+{
+    let service = TowerToHyperService::new(app);
+    let _ = hyper::server::conn::http1::Builder::new()
+        .serve_connection(TokioIo::new(stream), service)
+        .with_upgrades()
+        .await;
+}
+
+/// Issue a certificate when HTTPS is requested
+fn tls_config(options: &PreviewOptions) -> Result<Option<Arc<rustls::ServerConfig>>, String> {
+    if options.https {
+        crate::local_ca::server_config(&[]).map(Some)
+    } else {
+        Ok(None)
+    }
+}
+
+/// Serve `app` on localhost and record it under `key`, replacing any
+/// server already running for that key. A restarted server gets its old
+/// port back when it's free, so preview URLs stay stable.
+async fn launch(
+    key: &str,
+    app: Router,
+    preferred_port: u16,
+    mode: PreviewMode,
+    root: Option<PathBuf>,
+    options: &PreviewOptions,
+    live_reload: Option<Arc<LiveReload>>,
+) -> Result<u16, String> {
+    let previous_port = stop(key);
+    let app = with_compression(app);
+    let (port, shutdown) = serve(
+        app.clone(),
+        IpAddr::from([127, 0, 0, 1]),
+        previous_port.unwrap_or(preferred_port),
+        tls_config(options)?,
+    ).await?;
+
+    PREVIEW_SERVERS.lock().unwrap().insert(
+        key.to_string(),
+        PreviewServer {
+            port,
+            mode,
+            root,
+            options: options.clone(),
+            router: app,
+            shutdown,
+            live_reload,
+            live_reload_error: None,
+        },
+    );
+
+    Ok(port)
+}
+
+/// Start a static file server for the given project directory. In
+/// production mode (`options.production`) files aren't watched and pages
+/// don't reload.
+pub async fn start(
+    app_handle: &tauri::AppHandle,
+    key: &str,
+    project_path: &str, preferred_port: u16, options: &PreviewOptions) -> Result<u16, String> {
+    let path = PathBuf::from(project_path);
+    if !path.exists() || !path.is_dir() {
+        return Err("Invalid project path".to_string());
+    }
+
+    // If watching fails (e.g. the OS watch limit is exhausted) still serve,
+    // just without reloads, and report why in the server's status
+    let (live_reload, live_reload_error) = if options.production {
+        (None, None)
+    } else {
+        match LiveReload::watch(&path) {
+            Ok(live_reload) => (Some(Arc::new(live_reload)), None),
+            Err(e) => (None, Some(e)),
+        }
+    };
+    let state = Arc::new(ServerState {
+        routing: RulesCache::new(&path),
+        mocks: MockCache::new(&path),
+        root: path.clone(),
+        production: options.production,
+        live_reload: live_reload.clone(),
     });
 
-    *SERVER_SHUTDOWN.lock().unwrap() = Some(shutdown_tx);
-    *SERVER_PORT.lock().unwrap() = Some(actual_port);
+    let app = Router::new()
+        .route(live_reload::EVENTS_PATH, axum::routing::get(live_reload_events))
+        .fallback(serve_file)
+        .with_state(state)
+        .merge(console_capture::routes(key, app_handle));
+    let app = request_log::layer(app, key, app_handle);
+
+    let port = launch(key, app, preferred_port, PreviewMode::Static, Some(path), options, live_reload).await?;
+    if let Some(server) = PREVIEW_SERVERS.lock().unwrap().get_mut(key) {
+        server.live_reload_error = live_reload_error;
+    }
+    Ok(port)
+}
+
+/// Start a preview server as a reverse proxy in front of the running
+/// dev server, so framework previews get the same stable URL as static
+/// ones. HMR keeps working through WebSocket upgrades.
+pub async fn start_proxy(
+    app_handle: &tauri::AppHandle,
+    key: &str,
+    preferred_port: u16, options: &PreviewOptions) -> Result<u16, String> {
+    if crate::dev_server::current_port().is_none() {
+        return Err("Dev server is not running".to_string());
+    }
+
+    let state = Arc::new(ProxyState {
+        client: Client::builder(TokioExecutor::new()).build_http(),
+        https: options.https,
+    });
+    let app = Router::new()
+        .fallback(proxy_request)
+        .with_state(state)
+        .merge(console_capture::routes(key, app_handle));
+    let app = request_log::layer(app, key, app_handle);
 
-    Ok(actual_port)
+    launch(key, app, preferred_port, PreviewMode::Proxy, None, options, None).await
 }
 
-/// Stop the running preview server.
-pub fn stop() {
-    if let Some(tx) = SERVER_SHUTDOWN.lock().unwrap().take() {
-        let _ = tx.send(());
+/// Stop the preview server running under `key`. Returns the port it had.
+pub fn stop(key: &str) -> Option<u16> {
+    crate::lan_share::stop(key);
+    let server = PREVIEW_SERVERS.lock().unwrap().remove(key)?;
+    // End open live reload streams first, otherwise graceful shutdown
+    // waits on them until the grace period runs out
+    if let Some(live_reload) = &server.live_reload {
+        live_reload.notify(ReloadNotice::Shutdown);
     }
-    *SERVER_PORT.lock().unwrap() = None;
+    let _ = server.shutdown.send(());
+    Some(server.port)
 }
 
-/// Get the port of the currently running server, if any.
-pub fn get_port() -> Option<u16> {
-    *SERVER_PORT.lock().unwrap()
-}
\ No newline at end of file
+/// Stop every preview server.
+pub fn stop_all() {
+    let keys: Vec<String> = PREVIEW_SERVERS.lock().unwrap().keys().cloned().collect();
+    for key in keys {
+        stop(&key);
+    }
+}
+
+/// A running server's router, for serving it on another address
+pub(crate) fn served_app(key: &str) -> Option<ServedApp> {
+    PREVIEW_SERVERS.lock().unwrap().get(key).map(|server| ServedApp {
+        router: server.router.clone(),
+        port: server.port,
+        https: server.options.https,
+    })
+}
+
+/// Get the port of the server running under `key`, if any.
+pub fn get_port(key: &str) -> Option<u16> {
+    PREVIEW_SERVERS.lock().unwrap().get(key).map(|server| server.port)
+}
+
+/// Every running preview server.
+pub fn list() -> Vec<PreviewServerInfo> {
+    let servers = PREVIEW_SERVERS.lock().unwrap();
+    let mut list: Vec<PreviewServerInfo> = servers
+        .iter()
+        .map(|(key, server)| {
+            let scheme = if server.options.https { "https" } else { "http" };
+            PreviewServerInfo {
+                key: key.clone(),
+                port: server.port,
+                mode: server.mode,
+                root: server.root.as_ref().map(|r| r.to_string_lossy().to_string()),
+                https: server.options.https,
+                production: server.options.production,
+                live_reload_error: server.live_reload_error.clone(),
+                url: format!("{}://localhost:{}", scheme, server.port),
+                lan_shared: crate::lan_share::status(key).is_some(),
+            }
+        })
+        .collect();
+    list.sort_by_key(|info| info.port);
+    list
+}
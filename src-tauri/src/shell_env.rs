@@ -0,0 +1,272 @@
+// ── Login Shell Environment ───────────────────────────────────
+//
+// GUI-launched apps on Linux/macOS inherit a minimal environment, so
+// tools installed via nvm, asdf, Homebrew or rustup aren't on PATH.
+// We run the user's login shell ($SHELL -l -i) once, capture its `env`,
+// cache it, and overlay it on every process we spawn.
+// Windows already gives GUI apps the full user environment — no-op there.
+
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Marker printed around `env` output so shell startup noise
+/// (motd, prompts, nvm messages) can be ignored.
+const ENV_MARKER: &str = "__MYDEVIFY_ENV_7f3a__";
+
+/// Give up on slow shell startup files after this long
+const RESOLVE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Tools checked by the diagnostic command
+const DIAGNOSTIC_TOOLS: &[&str] = &[
+    "node", "npm", "npx", "pnpm", "yarn", "bun", "deno", "python3", "pip3", "cargo", "rustc", "git",
+];
+
+// ── Data Model ────────────────────────────────────────────────
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EnvSource {
+    /// Captured from the user's login shell
+    LoginShell,
+    /// Login shell failed — using the app's own environment
+    Process,
+}
+
+#[derive(Debug, Clone)]
+pub struct ResolvedEnv {
+    pub shell: Option<String>,
+    pub source: EnvSource,
+    pub vars: HashMap<String, String>,
+    pub error: Option<String>,
+    pub resolve_ms: u128,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ToolResolution {
+    pub name: String,
+    /// Absolute path the tool resolved to (None = not found)
+    pub path: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ShellEnvDiagnostics {
+    pub shell: Option<String>,
+    pub source: EnvSource,
+    pub error: Option<String>,
+    pub resolve_ms: u128,
+    pub path_entries: Vec<String>,
+    pub tools: Vec<ToolResolution>,
+}
+
+// ── Shared State ──────────────────────────────────────────────
+
+static RESOLVED_ENV: Mutex<Option<Arc<ResolvedEnv>>> = Mutex::new(None);
+
+/// Get the cached environment, resolving it on first use.
+pub fn get() -> Arc<ResolvedEnv> {
+    let mut cached = RESOLVED_ENV.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(env) = cached.as_ref() {
+        return env.clone();
+    }
+    let env = Arc::new(resolve());
+    *cached = Some(env.clone());
+    env
+}
+
+/// Drop the cached environment and resolve it again
+/// (e.g. after the user installs a new Node version).
+pub fn reload() -> Arc<ResolvedEnv> {
+    let env = Arc::new(resolve());
+    *RESOLVED_ENV.lock().unwrap_or_else(|e| e.into_inner()) = Some(env.clone());
+    env
+}
+
+/// Resolve in the background on app launch so the first command doesn't wait.
+pub fn warm_up() {
+    std::thread::spawn(|| {
+        let _ = get();
+    });
+}
+
+/// Overlay the resolved environment onto a command before spawning it.
+pub fn apply(cmd: &mut std::process::Command) {
+    if cfg!(target_os = "windows") {
+        return;
+    }
+    let env = get();
+    if let EnvSource::LoginShell = env.source {
+        cmd.envs(env.vars.iter());
+    }
+}
+
+// ── Resolution ────────────────────────────────────────────────
+
+fn process_env(shell: Option<String>, error: Option<String>, started: Instant) -> ResolvedEnv {
+    ResolvedEnv {
+        shell,
+        source: EnvSource::Process,
+        vars: std::env::vars().collect(),
+        error,
+        resolve_ms: started.elapsed().as_millis(),
+    }
+}
+
+fn resolve() -> ResolvedEnv {
+    let started = Instant::now();
+
+    if cfg!(target_os = "windows") {
+        return process_env(None, None, started);
+    }
+
+    let shell = std::env::var("SHELL")
+        .ok()
+        .filter(|s| !s.is_empty())
+        .unwrap_or_else(|| {
+            if cfg!(target_os = "macos") {
+                "/bin/zsh".to_string()
+            } else {
+                "/bin/sh".to_string()
+            }
+        });
+
+    match capture_login_env(&shell) {
+        Ok(vars) => ResolvedEnv {
+            shell: Some(shell),
+            source: EnvSource::LoginShell,
+            vars,
+            error: None,
+            resolve_ms: started.elapsed().as_millis(),
+        },
+        Err(e) => process_env(Some(shell), Some(e), started),
+    }
+}
+
+/// Run `$SHELL -l -i -c 'env -0'` and parse the NUL-separated output.
+/// Interactive mode is needed because nvm and friends hook into .bashrc/.zshrc.
+fn capture_login_env(shell: &str) -> Result<HashMap<String, String>, String> {
+    use std::io::Read;
+    use std::process::Stdio;
+
+    let script = format!(
+        "printf '%s' '{m}'; /usr/bin/env -0; printf '%s' '{m}'",
+        m = ENV_MARKER
+    );
+
+    let mut cmd = std::process::Command::new(shell);
+    cmd.arg("-l")
+        .arg("-i")
+        .arg("-c")
+        .arg(&script)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null());
+    // An interactive shell would take over the app's terminal (when started
+    // from one) for job control; its own session has no terminal to take
+    crate::process_registry::isolate(&mut cmd);
+    let mut child = cmd
+        .spawn()
+        .map_err(|e| format!("Failed to start login shell {}: {}", shell, e))?;
+
+    // Read on a separate thread so a hung shell can be killed on timeout
+    let mut stdout = child.stdout.take().ok_or("Login shell has no stdout")?;
+    let (tx, rx) = std::sync::mpsc::channel();
+    std::thread::spawn(move || {
+        let mut buf = Vec::new();
+        let _ = stdout.read_to_end(&mut buf);
+        let _ = tx.send(buf);
+    });
+
+    let output = match rx.recv_timeout(RESOLVE_TIMEOUT) {
+        Ok(buf) => buf,
+        Err(_) => {
+            // Whatever the rc files started goes down with the shell
+            #[cfg(unix)]
+            crate::process_registry::terminate(child.id());
+            let _ = child.kill();
+            let _ = child.wait();
+            return Err(format!(
+                "Login shell {} did not finish within {} seconds",
+                shell,
+                RESOLVE_TIMEOUT.as_secs()
+            ));
+        }
+    };
+    let _ = child.wait();
+
+    let text = String::from_utf8_lossy(&output);
+    let start = text
+        .find(ENV_MARKER)
+        .ok_or("Login shell output did not contain the environment")?
+        + ENV_MARKER.len();
+    let end = text[start..]
+        .find(ENV_MARKER)
+        .map(|i| start + i)
+        .ok_or("Login shell output was truncated")?;
+
+    let vars: HashMap<String, String> = text[start..end]
+        .split('\0')
+        .filter_map(|entry| entry.split_once('='))
+        .filter(|(key, _)| !key.is_empty())
+        .map(|(key, value)| (key.to_string(), value.to_string()))
+        // Shell-internal values that would be wrong for child processes
+        .filter(|(key, _)| !matches!(key.as_str(), "_" | "SHLVL" | "PWD" | "OLDPWD"))
+        .collect();
+
+    if !vars.contains_key("PATH") {
+        return Err("Login shell environment has no PATH".to_string());
+    }
+
+    Ok(vars)
+}
+
+// ── Diagnostics ───────────────────────────────────────────────
+
+/// Find an executable on the given PATH (like `which`)
+fn find_in_path(name: &str, path_var: &str) -> Option<PathBuf> {
+    let extensions: Vec<String> = if cfg!(target_os = "windows") {
+        std::env::var("PATHEXT")
+            .unwrap_or_else(|_| ".EXE;.CMD;.BAT".to_string())
+            .split(';')
+            .map(|e| e.to_lowercase())
+            .collect()
+    } else {
+        vec![String::new()]
+    };
+
+    std::env::split_paths(path_var).find_map(|dir| {
+        extensions.iter().find_map(|ext| {
+            let candidate = dir.join(format!("{}{}", name, ext));
+            if candidate.is_file() {
+                Some(candidate)
+            } else {
+                None
+            }
+        })
+    })
+}
+
+/// Report where the resolved environment came from and where common tools live.
+pub fn diagnose() -> ShellEnvDiagnostics {
+    let env = get();
+    let path_var = env.vars.get("PATH").cloned().unwrap_or_default();
+
+    ShellEnvDiagnostics {
+        shell: env.shell.clone(),
+        source: env.source.clone(),
+        error: env.error.clone(),
+        resolve_ms: env.resolve_ms,
+        path_entries: std::env::split_paths(&path_var)
+            .map(|p| p.to_string_lossy().to_string())
+            .collect(),
+        tools: DIAGNOSTIC_TOOLS
+            .iter()
+            .map(|name| ToolResolution {
+                name: name.to_string(),
+                path: find_in_path(name, &path_var).map(|p| p.to_string_lossy().to_string()),
+            })
+            .collect(),
+    }
+}
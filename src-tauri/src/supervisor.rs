@@ -0,0 +1,429 @@
+// ── Service Supervisor — Named Project Services ───────────────
+//
+// A project can declare several long-running services (frontend, API,
+// worker…), each with its own command, cwd, env and port pattern.
+// They're started, stopped and restarted individually, keep their own
+// log buffers, and report live status via `service-status` events.
+// Definitions are stored per project in ~/.mydevify/data/services.json.
+// The single-slot `start_dev_server` flow in lib.rs is unaffected.
+
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use tauri::Emitter;
+
+use crate::log_buffer::LogStream;
+
+/// Lines of output kept per service
+const MAX_LOG_LINES: usize = 2_000;
+
+// ── Data Model ────────────────────────────────────────────────
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ServiceDefinition {
+    /// Unique within the project, e.g. "web", "api", "worker"
+    pub name: String,
+    pub command: String,
+    /// Working directory relative to the project root (default: root)
+    pub cwd: Option<String>,
+    #[serde(default)]
+    pub env: HashMap<String, String>,
+    /// Regex with one capture group for the port, e.g. "localhost:(\\d+)"
+    pub port_pattern: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "state", rename_all = "snake_case")]
+pub enum ServiceState {
+    Stopped,
+    /// Spawned, waiting for the port pattern to match
+    Starting,
+    Running,
+    /// Process exited on its own
+    Exited { code: Option<i32> },
+    Failed { error: String },
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ServiceStatus {
+    pub project: String,
+    pub name: String,
+    pub command: String,
+    #[serde(flatten)]
+    pub state: ServiceState,
+    pub pid: Option<u32>,
+    pub port: Option<u16>,
+    pub started_at: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ServiceLogLine {
+    pub stream: LogStream,
+    pub text: String,
+    pub timestamp: String, // ISO 8601 string
+}
+
+// ── Runtime State ─────────────────────────────────────────────
+
+/// A spawned service. Shared with its reader/waiter threads.
+struct ServiceRuntime {
+    status: Mutex<ServiceStatus>,
+    logs: Mutex<VecDeque<ServiceLogLine>>,
+    /// Set by stop() so the waiter reports Stopped instead of Exited
+    stopping: std::sync::atomic::AtomicBool,
+}
+
+type Registry = HashMap<String, HashMap<String, Arc<ServiceRuntime>>>;
+
+/// project path → service name → runtime
+static SERVICES: once_cell::sync::Lazy<Mutex<Registry>> =
+    once_cell::sync::Lazy::new(|| Mutex::new(HashMap::new()));
+
+// ── Definition Storage ────────────────────────────────────────
+
+fn get_definitions_file_path() -> PathBuf {
+    let home = dirs_next::home_dir().unwrap_or_else(|| PathBuf::from("."));
+    let data_dir = home.join(".mydevify").join("data");
+    fs::create_dir_all(&data_dir).ok();
+    data_dir.join("services.json")
+}
+
+fn load_definitions() -> HashMap<String, Vec<ServiceDefinition>> {
+    fs::read_to_string(get_definitions_file_path())
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+/// Normalize a project path so the same folder always maps to one key
+fn project_key(project: &str) -> Result<String, String> {
+    let path = PathBuf::from(project);
+    if !path.is_dir() {
+        return Err(format!("Directory not found: {}", project));
+    }
+    Ok(path
+        .canonicalize()
+        .map_err(|e| e.to_string())?
+        .to_string_lossy()
+        .to_string())
+}
+
+pub fn get_definitions(project: &str) -> Result<Vec<ServiceDefinition>, String> {
+    let key = project_key(project)?;
+    Ok(load_definitions().remove(&key).unwrap_or_default())
+}
+
+pub fn save_definitions(
+    project: &str,
+    services: Vec<ServiceDefinition>,
+) -> Result<Vec<ServiceDefinition>, String> {
+    let key = project_key(project)?;
+
+    for (i, service) in services.iter().enumerate() {
+        if service.name.trim().is_empty() {
+            return Err("Service name cannot be empty".to_string());
+        }
+        if service.command.trim().is_empty() {
+            return Err(format!("Service '{}' has no command", service.name));
+        }
+        if services[..i].iter().any(|s| s.name == service.name) {
+            return Err(format!("Duplicate service name: {}", service.name));
+        }
+        if let Some(ref pattern) = service.port_pattern {
+            regex::Regex::new(pattern)
+                .map_err(|e| format!("Invalid port pattern for '{}': {}", service.name, e))?;
+        }
+    }
+
+    let mut all = load_definitions();
+    all.insert(key, services.clone());
+    let json = serde_json::to_string_pretty(&all).map_err(|e| e.to_string())?;
+    fs::write(get_definitions_file_path(), json).map_err(|e| e.to_string())?;
+    Ok(services)
+}
+
+// ── Lifecycle ─────────────────────────────────────────────────
+
+fn emit_status(app_handle: &tauri::AppHandle, runtime: &ServiceRuntime) {
+    let status = runtime.status.lock().unwrap_or_else(|e| e.into_inner()).clone();
+    let _ = app_handle.emit("service-status", status);
+}
+
+fn set_state(app_handle: &tauri::AppHandle, runtime: &ServiceRuntime, state: ServiceState) {
+    runtime.status.lock().unwrap_or_else(|e| e.into_inner()).state = state;
+    emit_status(app_handle, runtime);
+}
+
+fn find_runtime(key: &str, name: &str) -> Option<Arc<ServiceRuntime>> {
+    SERVICES
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .get(key)
+        .and_then(|services| services.get(name))
+        .cloned()
+}
+
+fn is_active(runtime: &ServiceRuntime) -> bool {
+    matches!(
+        runtime.status.lock().unwrap_or_else(|e| e.into_inner()).state,
+        ServiceState::Starting | ServiceState::Running
+    )
+}
+
+/// Spawn a service. Returns as soon as the process is running —
+/// port detection and exit tracking continue in the background.
+pub fn start(
+    app_handle: &tauri::AppHandle,
+    project: &str,
+    name: &str,
+) -> Result<ServiceStatus, String> {
+    let key = project_key(project)?;
+    let definition = get_definitions(&key)?
+        .into_iter()
+        .find(|s| s.name == name)
+        .ok_or_else(|| format!("Service not found: {}", name))?;
+    start_definition(app_handle, &key, definition)
+}
+
+/// Spawn a service from a definition that isn't stored in services.json
+/// (e.g. one declared in the project's .mydevify/services.toml).
+pub fn start_definition(
+    app_handle: &tauri::AppHandle,
+    project: &str,
+    definition: ServiceDefinition,
+) -> Result<ServiceStatus, String> {
+    use std::io::{BufRead, BufReader};
+    use std::process::Stdio;
+
+    let key = project_key(project)?;
+    let name = definition.name.as_str();
+
+    if let Some(existing) = find_runtime(&key, name) {
+        if is_active(&existing) {
+            return Err(format!("Service '{}' is already running", name));
+        }
+    }
+
+    let cwd = match definition.cwd {
+        Some(ref rel) => PathBuf::from(&key).join(rel),
+        None => PathBuf::from(&key),
+    };
+    if !cwd.is_dir() {
+        return Err(format!("Directory not found: {}", cwd.display()));
+    }
+
+    let port_re = match definition.port_pattern {
+        Some(ref p) => Some(regex::Regex::new(p).map_err(|e| format!("Invalid port pattern: {}", e))?),
+        None => None,
+    };
+
+    let mut child = {
+        let mut cmd = crate::build_hidden_shell_command(&definition.command, &cwd);
+        cmd.envs(definition.env.iter());
+        cmd.stdout(Stdio::piped()).stderr(Stdio::piped());
+        cmd.spawn()
+    }
+    .map_err(|e| format!("Failed to start service '{}': {}", name, e))?;
+    let pid = child.id();
+    crate::process_registry::track(pid, &definition.command);
+
+    let runtime = Arc::new(ServiceRuntime {
+        status: Mutex::new(ServiceStatus {
+            project: key.clone(),
+            name: name.to_string(),
+            command: definition.command.clone(),
+            // Without a port pattern there's nothing to wait for
+            state: if port_re.is_some() {
+                ServiceState::Starting
+            } else {
+                ServiceState::Running
+            },
+            pid: Some(pid),
+            port: None,
+            started_at: Some(Utc::now().to_rfc3339()),
+        }),
+        logs: Mutex::new(VecDeque::new()),
+        stopping: std::sync::atomic::AtomicBool::new(false),
+    });
+
+    SERVICES
+        .lock()
+        .map_err(|e| e.to_string())?
+        .entry(key.clone())
+        .or_default()
+        .insert(name.to_string(), runtime.clone());
+
+    // Output readers — one per stream, both feed the same log buffer
+    let streams: Vec<(LogStream, Box<dyn std::io::Read + Send>)> = [
+        child.stdout.take().map(|s| (LogStream::Stdout, Box::new(s) as Box<dyn std::io::Read + Send>)),
+        child.stderr.take().map(|s| (LogStream::Stderr, Box::new(s) as Box<dyn std::io::Read + Send>)),
+    ]
+    .into_iter()
+    .flatten()
+    .collect();
+
+    for (stream, reader) in streams {
+        let runtime = runtime.clone();
+        let port_re = port_re.clone();
+        let handle = app_handle.clone();
+        std::thread::spawn(move || {
+            for line in BufReader::new(reader).lines().map_while(Result::ok) {
+                let clean = crate::strip_ansi_codes(&line);
+
+                if let Some(ref re) = port_re {
+                    let detected = re
+                        .captures(&clean)
+                        .and_then(|caps| caps.get(1))
+                        .and_then(|m| m.as_str().parse::<u16>().ok());
+                    if let Some(port) = detected {
+                        let mut became_running = false;
+                        {
+                            let mut status = runtime.status.lock().unwrap_or_else(|e| e.into_inner());
+                            if status.state == ServiceState::Starting {
+                                status.state = ServiceState::Running;
+                                status.port = Some(port);
+                                became_running = true;
+                            }
+                        }
+                        if became_running {
+                            emit_status(&handle, &runtime);
+                        }
+                    }
+                }
+
+                let mut logs = runtime.logs.lock().unwrap_or_else(|e| e.into_inner());
+                if logs.len() >= MAX_LOG_LINES {
+                    logs.pop_front();
+                }
+                logs.push_back(ServiceLogLine {
+                    stream,
+                    text: line,
+                    timestamp: Utc::now().to_rfc3339(),
+                });
+            }
+        });
+    }
+
+    // Waiter — records how the process ended
+    {
+        let runtime = runtime.clone();
+        let handle = app_handle.clone();
+        std::thread::spawn(move || {
+            let result = child.wait();
+            crate::process_registry::exited(pid);
+            {
+                let mut status = runtime.status.lock().unwrap_or_else(|e| e.into_inner());
+                status.pid = None;
+                status.port = None;
+            }
+            let state = if runtime.stopping.load(std::sync::atomic::Ordering::Relaxed) {
+                ServiceState::Stopped
+            } else {
+                match result {
+                    Ok(exit) => ServiceState::Exited { code: exit.code() },
+                    Err(e) => ServiceState::Failed { error: e.to_string() },
+                }
+            };
+            set_state(&handle, &runtime, state);
+        });
+    }
+
+    emit_status(app_handle, &runtime);
+    let status = runtime.status.lock().map_err(|e| e.to_string())?.clone();
+    Ok(status)
+}
+
+/// Stop a running service (kills its whole process tree).
+pub fn stop(project: &str, name: &str) -> Result<(), String> {
+    let key = project_key(project)?;
+    let runtime = find_runtime(&key, name).ok_or_else(|| format!("Service not running: {}", name))?;
+
+    let pid = runtime.status.lock().map_err(|e| e.to_string())?.pid;
+    if let Some(pid) = pid {
+        runtime.stopping.store(true, std::sync::atomic::Ordering::Relaxed);
+        crate::kill_process_tree(pid);
+    }
+    Ok(())
+}
+
+/// Stop a service, wait for it to exit, then start it again.
+pub fn restart(
+    app_handle: &tauri::AppHandle,
+    project: &str,
+    name: &str,
+) -> Result<ServiceStatus, String> {
+    let key = project_key(project)?;
+    if let Some(runtime) = find_runtime(&key, name) {
+        if runtime.status.lock().map_err(|e| e.to_string())?.pid.is_some() {
+            stop(&key, name)?;
+            // Give the waiter thread a moment to observe the exit
+            for _ in 0..50 {
+                if runtime.status.lock().map_err(|e| e.to_string())?.pid.is_none() {
+                    break;
+                }
+                std::thread::sleep(std::time::Duration::from_millis(100));
+            }
+        }
+    }
+    start(app_handle, &key, name)
+}
+
+/// Status of every declared service in a project (running or not)
+pub fn list(project: &str) -> Result<Vec<ServiceStatus>, String> {
+    let key = project_key(project)?;
+    let definitions = get_definitions(&key)?;
+
+    // Services started from services.toml have a runtime but no stored definition
+    let mut extra: Vec<ServiceStatus> = SERVICES
+        .lock()
+        .map_err(|e| e.to_string())?
+        .get(&key)
+        .map(|services| {
+            services
+                .iter()
+                .filter(|(name, _)| !definitions.iter().any(|d| &d.name == *name))
+                .map(|(_, runtime)| runtime.status.lock().unwrap_or_else(|e| e.into_inner()).clone())
+                .collect()
+        })
+        .unwrap_or_default();
+    extra.sort_by(|a, b| a.name.cmp(&b.name));
+
+    Ok(definitions
+        .into_iter()
+        .map(|def| match find_runtime(&key, &def.name) {
+            Some(runtime) => runtime.status.lock().unwrap_or_else(|e| e.into_inner()).clone(),
+            None => ServiceStatus {
+                project: key.clone(),
+                name: def.name,
+                command: def.command,
+                state: ServiceState::Stopped,
+                pid: None,
+                port: None,
+                started_at: None,
+            },
+        })
+        .chain(extra)
+        .collect())
+}
+
+/// Current status of a service that has been started this session
+pub fn status(project: &str, name: &str) -> Result<Option<ServiceStatus>, String> {
+    let key = project_key(project)?;
+    Ok(find_runtime(&key, name)
+        .map(|runtime| runtime.status.lock().unwrap_or_else(|e| e.into_inner()).clone()))
+}
+
+/// Most recent log lines for a service (up to `tail`, default 200)
+pub fn get_logs(project: &str, name: &str, tail: Option<usize>) -> Result<Vec<ServiceLogLine>, String> {
+    let key = project_key(project)?;
+    let runtime = match find_runtime(&key, name) {
+        Some(r) => r,
+        None => return Ok(Vec::new()),
+    };
+    let logs = runtime.logs.lock().map_err(|e| e.to_string())?;
+    let tail = tail.unwrap_or(200).min(logs.len());
+    Ok(logs.iter().skip(logs.len() - tail).cloned().collect())
+}
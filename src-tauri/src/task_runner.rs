@@ -242,7 +242,7 @@ async fn execute_local_step(
                     .to_string_lossy()
                     .to_string()
             });
-            run_shell_command(command, &work_dir)
+            run_sandboxed_command(command, &work_dir, &sandbox_root(task))
         }
 
         StepAction::BackupFiles { source, destination } => {
@@ -318,7 +318,7 @@ async fn execute_local_step(
                     }
                 }
             };
-            run_shell_command(&cmd, &work_dir)
+            run_sandboxed_command(&cmd, &work_dir, &sandbox_root(task))
         }
 
         StepAction::DeleteFiles { path, pattern } => {
@@ -348,12 +348,66 @@ fn run_shell_command(
     command: &str,
     cwd: &str,
 ) -> (StepStatus, Option<String>, Option<String>) {
+    let mut cmd = build_shell_command(command, cwd);
+    collect_output(cmd.output(), None)
+}
+
+/// Writable root for a sandboxed step: the task's project, or a scratch
+/// directory for general tasks — never the working directory, which
+/// defaults to the home directory. The scratch directory is only created
+/// when the sandbox is on.
+fn sandbox_root(task: &ScheduledTask) -> String {
+    task.project_id.clone().unwrap_or_else(|| {
+        let scratch = std::env::temp_dir().join("mydevify-tasks").join(&task.id);
+        if crate::sandbox::get_config().enabled {
+            let _ = std::fs::create_dir_all(&scratch);
+        }
+        scratch.to_string_lossy().to_string()
+    })
+}
+
+/// Run a RunCommand/RunScript step: these execute arbitrary code, so they go
+/// through the command sandbox (when enabled) and into the project's history.
+fn run_sandboxed_command(
+    command: &str,
+    cwd: &str,
+    writable_root: &str,
+) -> (StepStatus, Option<String>, Option<String>) {
+    let project_root = PathBuf::from(writable_root);
+    let mut cmd = build_shell_command(command, cwd);
+    let report = match crate::sandbox::apply(&mut cmd, &project_root) {
+        Ok(report) => report,
+        Err(e) => return (StepStatus::Failed, None, Some(e)),
+    };
+
+    let started_at = Utc::now();
+    let output = cmd.output();
+    if let Ok(ref out) = output {
+        let _ = crate::command_history::record(
+            &project_root,
+            crate::command_history::FinishedCommand {
+                command,
+                cwd: &PathBuf::from(cwd),
+                initiator: crate::command_history::Initiator::Task,
+                exit_code: out.status.code().unwrap_or(-1),
+                stdout: &String::from_utf8_lossy(&out.stdout),
+                stderr: &String::from_utf8_lossy(&out.stderr),
+                started_at,
+            },
+        );
+    }
+
+    collect_output(output, report)
+}
+
+fn build_shell_command(command: &str, cwd: &str) -> std::process::Command {
     let cwd_path = PathBuf::from(cwd);
 
-    let output = if cfg!(target_os = "windows") {
+    if cfg!(target_os = "windows") {
         let mut cmd = std::process::Command::new("cmd.exe");
         cmd.arg("/D").arg("/S").arg("/C").arg(command);
         cmd.current_dir(&cwd_path);
+        crate::shell_env::apply(&mut cmd);
 
         #[cfg(target_os = "windows")]
         {
@@ -361,15 +415,21 @@ fn run_shell_command(
             cmd.creation_flags(0x08000000); // CREATE_NO_WINDOW
         }
 
-        cmd.output()
+        cmd
     } else {
-        std::process::Command::new("/bin/sh")
-            .arg("-c")
-            .arg(command)
-            .current_dir(&cwd_path)
-            .output()
-    };
+        let mut cmd = std::process::Command::new("/bin/sh");
+        cmd.arg("-c").arg(command).current_dir(&cwd_path);
+        crate::shell_env::apply(&mut cmd);
+        cmd
+    }
+}
 
+/// Map a finished command's output to a step result.
+/// Sandbox violations (if any) are appended to the error text.
+fn collect_output(
+    output: std::io::Result<std::process::Output>,
+    sandbox_report: Option<crate::sandbox::SandboxReport>,
+) -> (StepStatus, Option<String>, Option<String>) {
     match output {
         Ok(out) => {
             let stdout = String::from_utf8_lossy(&out.stdout).to_string();
@@ -384,11 +444,20 @@ fn run_shell_command(
                 };
                 (StepStatus::Success, output_text, None)
             } else {
-                let error_text = if !stderr.is_empty() {
+                let mut error_text = if !stderr.is_empty() {
                     stderr.trim().to_string()
                 } else {
                     format!("Command exited with code {}", exit_code)
                 };
+                if let Some(mut report) = sandbox_report {
+                    crate::sandbox::collect_violations(&mut report, &stderr);
+                    if !report.violations.is_empty() {
+                        error_text.push_str(&format!(
+                            "\n\nBlocked by sandbox:\n{}",
+                            report.violations.join("\n")
+                        ));
+                    }
+                }
                 (
                     StepStatus::Failed,
                     if !stdout.is_empty() { Some(stdout.trim().to_string()) } else { None },
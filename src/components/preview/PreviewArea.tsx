@@ -1,4 +1,4 @@
-import { ExternalLink, PanelRightClose, FileCode, Copy, Check, Globe, RefreshCw, Pencil, Save, X, Download, Terminal, AlertCircle, Loader } from "lucide-react";
+import { ExternalLink, PanelRightClose, FileCode, Copy, Check, Globe, RefreshCw, Pencil, Save, X, Download, Terminal, AlertCircle, Loader, Smartphone } from "lucide-react";
 import { useState, useEffect, useRef } from "react";
 import { useSettingsStore } from "../../stores/settingsStore";
 import { useProjectStore } from "../../stores/projectStore";
@@ -9,8 +9,16 @@ import { updateManifestEntry, getRelativePath } from "../../services/manifestSer
 import { detectProjectType, ProjectDetection } from "../../services/projectDetector";
 import { convertFileSrc } from "@tauri-apps/api/core";
 import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
 import { openUrl } from "@tauri-apps/plugin-opener";
 
+// Preview shared on the local network (see lan_share.rs)
+interface LanShare {
+  urls: string[];
+  expires_at: string;
+  requests: { timestamp: string; remote: string; method: string; path: string; status: number }[];
+}
+
 interface PreviewAreaProps {
   onClose: () => void;
 }
@@ -28,6 +36,33 @@ type PreviewStatus =
   | "non-web"          // Not a web project
   | "error";           // Something went wrong
 
+// Dev server output read from its log buffer; pass `cursor` back as `since`
+interface DevServerOutput {
+  lines: { seq: number; stream: "stdout" | "stderr"; text: string; timestamp: string }[];
+  cursor: number;
+  dropped: number;
+}
+
+// Result of a dependency install job (see installer.rs)
+interface InstallFinished {
+  job_id: string;
+  success: boolean;
+  cancelled: boolean;
+  exit_code: number | null;
+  output_tail: string[];
+}
+
+// Parsed build error from the dev server output (see diagnostics.rs)
+interface BuildDiagnostic {
+  severity: "error" | "warning";
+  tool: string;
+  file: string | null;
+  line: number | null;
+  column: number | null;
+  message: string;
+  code_frame: string | null;
+}
+
 function PreviewArea({ onClose }: PreviewAreaProps) {
   const [tooltip, setTooltip] = useState<string | null>(null);
   const [fileContent, setFileContent] = useState<string | null>(null);
@@ -46,6 +81,7 @@ function PreviewArea({ onClose }: PreviewAreaProps) {
   // Live preview state
   const [previewMode, setPreviewMode] = useState<"file" | "live">("live");
   const [serverPort, setServerPort] = useState<number | null>(null);
+  const [serverScheme, setServerScheme] = useState<"http" | "https">("http");
   const [refreshKey, setRefreshKey] = useState(0);
   const iframeRef = useRef<HTMLIFrameElement>(null);
 
@@ -54,13 +90,20 @@ function PreviewArea({ onClose }: PreviewAreaProps) {
   const [detection, setDetection] = useState<ProjectDetection | null>(null);
   const [statusMessage, setStatusMessage] = useState<string>("");
   const [installOutput, setInstallOutput] = useState<string>("");
+  const [installJobId, setInstallJobId] = useState<string | null>(null);
 
-  const { theme } = useSettingsStore();
+  const { theme, previewHttps } = useSettingsStore();
   const { selectedFile, projectPath, fileTree, setFileTree, manifest, setManifest, setBuildError, autoFixCount, resetAutoFix, externalFileChange, setExternalFileChange } = useProjectStore();
   const { isLoading: aiIsLoading } = useChatStore();
   const prevAiLoadingRef = useRef(false);
   const [errorPollTrigger, setErrorPollTrigger] = useState(0);
   const previewVersionRef = useRef(0);
+  // Last dev server log line the error poller has seen (see log_buffer.rs)
+  const outputCursorRef = useRef<number | null>(null);
+  // Whether the iframe points at the preview proxy rather than the dev server
+  const proxyingRef = useRef(false);
+  const [lanShare, setLanShare] = useState<LanShare | null>(null);
+  const [lanShareOpen, setLanShareOpen] = useState(false);
   const t = themes[theme];
 
   const isImageFile = selectedFile ? IMAGE_EXTENSIONS.some(ext =>
@@ -100,9 +143,13 @@ function PreviewArea({ onClose }: PreviewAreaProps) {
                 await new Promise((r) => setTimeout(r, 2000));
               }
               if (previewVersionRef.current !== thisVersion) return;
-              const port = await invoke<number>("start_preview_server", { path: projectPath });
+              const port = await invoke<number>("start_preview_server", {
+                path: projectPath,
+                options: { https: previewHttps },
+              });
               if (!cancelled && previewVersionRef.current === thisVersion) {
                 setServerPort(port);
+                setServerScheme(previewHttps ? "https" : "http");
                 setRefreshKey((k) => k + 1);
                 setStatusMessage("");
               }
@@ -144,9 +191,9 @@ function PreviewArea({ onClose }: PreviewAreaProps) {
 
     return () => {
       cancelled = true;
-      invoke("stop_preview_server").catch(() => {});
+      invoke("stop_preview_server", { key: projectPath }).catch(() => {});
     };
-  }, [projectPath]);
+  }, [projectPath, previewHttps]);
 
   // Stop all servers on component unmount (app closing)
   useEffect(() => {
@@ -156,9 +203,40 @@ function PreviewArea({ onClose }: PreviewAreaProps) {
     };
   }, []);
 
-  // Auto-refresh live preview when file tree changes (static mode)
+  // Dev server crashed / came back (emitted by the Rust dev server manager)
   useEffect(() => {
-    if (previewMode === "live" && serverPort && (previewStatus === "static" || previewStatus === "dev-running")) {
+    const unlisteners = [
+      listen<{ exit_code: number | null; last_lines: string[]; will_restart: boolean; restart_delay_ms: number | null; error: string | null }>(
+        "dev-server-exited",
+        (event) => {
+          const { exit_code, last_lines, will_restart, restart_delay_ms, error } = event.payload;
+          if (will_restart) {
+            setPreviewStatus("starting-dev");
+            setStatusMessage(`Dev server stopped (exit code ${exit_code ?? "?"}). Restarting in ${Math.round((restart_delay_ms || 0) / 1000)}s...`);
+          } else {
+            setPreviewStatus("error");
+            setStatusMessage(error ? `Dev server failed to restart: ${error}` : `Dev server exited with code ${exit_code ?? "?"}`);
+            setInstallOutput(last_lines.join("\n"));
+          }
+        }
+      ),
+      listen<{ port: number }>("dev-server-restarted", (event) => {
+        // The preview proxy follows the dev server to its new port by itself
+        if (!proxyingRef.current) setServerPort(event.payload.port);
+        setPreviewStatus("dev-running");
+        setStatusMessage("");
+        setRefreshKey((k) => k + 1);
+      }),
+    ];
+    return () => {
+      unlisteners.forEach((p) => p.then((unlisten) => unlisten()));
+    };
+  }, []);
+
+  // Auto-refresh live preview when file tree changes (dev server mode).
+  // The static preview server pushes its own live reload, including CSS hot-swaps.
+  useEffect(() => {
+    if (previewMode === "live" && serverPort && previewStatus === "dev-running") {
       setRefreshKey((k) => k + 1);
     }
   }, [fileTree]);
@@ -196,8 +274,37 @@ function PreviewArea({ onClose }: PreviewAreaProps) {
       if (cancelled) return;
 
       try {
-        const output = await invoke<string>("get_dev_server_output");
-        if (cancelled || !output) return;
+        // Prefer the parsed diagnostics — exact file/line plus the code frame
+        const diagnostics = await invoke<BuildDiagnostic[]>("get_dev_server_diagnostics");
+        if (cancelled) return;
+        const errors = diagnostics.filter((d) => d.severity === "error");
+        if (errors.length > 0) {
+          const errorText = errors
+            .slice(0, 5)
+            .map((d) => {
+              const location = d.file ? `${d.file}${d.line ? `:${d.line}` : ""}${d.column ? `:${d.column}` : ""}` : "";
+              return [location, d.message, d.code_frame].filter(Boolean).join("\n");
+            })
+            .join("\n\n")
+            .slice(0, 1500);
+          // Move past the raw output so the fallback below doesn't re-report it
+          const read = await invoke<DevServerOutput>("get_dev_server_output", {
+            options: { since: Number.MAX_SAFE_INTEGER, limit: 0 },
+          });
+          outputCursorRef.current = read.cursor;
+          console.log("[preview] Build error detected, sending to AI...");
+          setBuildError(errorText);
+          return;
+        }
+
+        // Fall back to pattern matching on the raw output
+        const read = await invoke<DevServerOutput>("get_dev_server_output", {
+          options: { since: outputCursorRef.current, strip_ansi: true },
+        });
+        if (cancelled) return;
+        outputCursorRef.current = read.cursor;
+        const output = read.lines.map((l) => l.text).join("\n");
+        if (!output) return;
 
         // Look for error patterns in the dev server output
         const errorPatterns = [
@@ -293,7 +400,7 @@ function PreviewArea({ onClose }: PreviewAreaProps) {
 
     try {
       // 1. Stop static server
-      await invoke("stop_preview_server").catch(() => {});
+      await invoke("stop_preview_server", { key: projectPath }).catch(() => {});
       if (isStale()) return;
 
       // 2. Kill old dev server via Rust and wait for port release
@@ -315,6 +422,7 @@ function PreviewArea({ onClose }: PreviewAreaProps) {
         await invoke("execute_command", {
           command: 'for /f "tokens=5" %a in (\'netstat -aon ^| findstr ":300[0-9] " ^| findstr LISTENING\') do taskkill /PID %a /F 2>nul',
           cwd: projectPath,
+          initiator: "app",
         }).catch(() => {});
 
         // 4. Clean framework lock files that prevent restart
@@ -334,23 +442,51 @@ function PreviewArea({ onClose }: PreviewAreaProps) {
       console.log("[preview] Starting dev server:", det.devCommand);
       setStatusMessage(`Starting ${det.framework} dev server...`);
 
-      const port = await invoke<number>("start_dev_server", {
-        command: det.devCommand,
-        cwd: projectPath,
-        portPattern: det.portPattern.source,
-      });
+      let port: number;
+      let detected_by: string;
+      if (det.runConfig) {
+        // .mydevify/services.toml — Rust starts every service in order
+        const started = await invoke<{ preview: { port: number; detected_by: string } | null }>("start_run_config", {
+          project: projectPath,
+        });
+        if (!started.preview) throw new Error("services.toml has no preview service");
+        ({ port, detected_by } = started.preview);
+      } else {
+        ({ port, detected_by } = await invoke<{ port: number; detected_by: string }>("start_dev_server", {
+          command: det.devCommand,
+          cwd: projectPath,
+          portPattern: det.portPattern.source,
+          options: { auto_restart: true },
+        }));
+      }
 
       if (isStale()) return;
-      console.log("[preview] Dev server reported port:", port);
+      console.log("[preview] Dev server reported port:", port, `(${detected_by})`);
+
+      // Error polling starts from here — startup output isn't a build error
+      const startupOutput = await invoke<DevServerOutput>("get_dev_server_output", {
+        options: { since: Number.MAX_SAFE_INTEGER, limit: 0 },
+      });
+      outputCursorRef.current = startupOutput.cursor;
 
       // 6. Wait for initial compilation
       setStatusMessage(`Waiting for ${det.framework} to compile...`);
       await new Promise((r) => setTimeout(r, 3000));
       if (isStale()) return;
 
-      // 7. Show the preview
-      console.log("[preview] Showing iframe on port", port);
-      setServerPort(port);
+      // 7. Serve it through the preview proxy so the URL stays stable across
+      //    dev server restarts; fall back to the dev server's own port
+      const previewPort = await invoke<number>("start_preview_proxy", {
+        key: projectPath,
+        options: { https: previewHttps },
+      }).catch(() => port);
+      proxyingRef.current = previewPort !== port;
+      if (isStale()) return;
+
+      // 8. Show the preview
+      console.log("[preview] Showing iframe on port", previewPort);
+      setServerPort(previewPort);
+      setServerScheme(proxyingRef.current && previewHttps ? "https" : "http");
       setPreviewStatus("dev-running");
       setStatusMessage("");
       setRefreshKey((k) => k + 1);
@@ -373,8 +509,12 @@ function PreviewArea({ onClose }: PreviewAreaProps) {
       case "static":
         setPreviewStatus("static");
         try {
-          const port = await invoke<number>("start_preview_server", { path: projectPath });
+          const port = await invoke<number>("start_preview_server", {
+            path: projectPath,
+            options: { https: previewHttps },
+          });
           setServerPort(port);
+          setServerScheme(previewHttps ? "https" : "http");
           setRefreshKey((k) => k + 1);
           setStatusMessage("");
         } catch (err: any) {
@@ -416,20 +556,57 @@ function PreviewArea({ onClose }: PreviewAreaProps) {
     setStatusMessage(`Running ${detection.installCommand}...`);
     setInstallOutput("");
 
+    // Runs as a background job in Rust (installer.rs) — follow it via events
+    let jobId: string | null = null;
+    let unlistenProgress: (() => void) | null = null;
+    let unlistenFinished: (() => void) | null = null;
+
     try {
-      const result = await invoke<{ stdout: string; stderr: string; exit_code: number }>("execute_command", {
-        command: detection.installCommand,
-        cwd: projectPath,
+      // A fast failure can finish before start_install returns the job ID,
+      // so keep every result and pick ours out once we know it
+      const results = new Map<string, InstallFinished>();
+      let onResult: () => void = () => {};
+      unlistenFinished = await listen<InstallFinished>("install-finished", (event) => {
+        results.set(event.payload.job_id, event.payload);
+        onResult();
+      });
+      unlistenProgress = await listen<{ job_id: string; phase: string; percent: number | null; message: string }>(
+        "install-progress",
+        (event) => {
+          if (event.payload.job_id !== jobId) return;
+          const { phase, percent, message } = event.payload;
+          setStatusMessage(percent !== null ? `${phase} (${percent}%)` : phase);
+          setInstallOutput(message);
+        }
+      );
+
+      const job = await invoke<{ job_id: string; command: string }>("start_install", { project: projectPath });
+      jobId = job.job_id;
+      setInstallJobId(job.job_id);
+
+      const result = await new Promise<InstallFinished>((resolve) => {
+        onResult = () => {
+          const done = results.get(job.job_id);
+          if (done) resolve(done);
+        };
+        onResult();
       });
+      setInstallJobId(null);
 
-      if (result.exit_code !== 0) {
+      if (result.cancelled) {
+        setPreviewStatus("needs-install");
+        setStatusMessage("Install cancelled.");
+        setInstallOutput("");
+        return;
+      }
+      if (!result.success) {
         // Install failed
-        const output = (result.stderr || result.stdout || "Unknown error").trim();
         setPreviewStatus("error");
-        setStatusMessage("Install failed");
-        setInstallOutput(output);
+        setStatusMessage(`Install failed (exit code ${result.exit_code ?? "?"})`);
+        setInstallOutput(result.output_tail.join("\n") || "Unknown error");
         return;
       }
+      setInstallOutput("");
 
       // Install succeeded — refresh file tree (node_modules appeared) and start dev server
       try {
@@ -443,8 +620,21 @@ function PreviewArea({ onClose }: PreviewAreaProps) {
 
       await startDevServer(updatedDetection);
     } catch (err: any) {
+      setInstallJobId(null);
       setPreviewStatus("error");
       setStatusMessage(err.toString());
+    } finally {
+      unlistenProgress?.();
+      unlistenFinished?.();
+    }
+  };
+
+  const handleCancelInstall = async () => {
+    if (!installJobId) return;
+    try {
+      await invoke("cancel_install", { jobId: installJobId });
+    } catch (err) {
+      console.error("[preview] Failed to cancel install:", err);
     }
   };
 
@@ -512,15 +702,46 @@ function PreviewArea({ onClose }: PreviewAreaProps) {
   const handleOpenInBrowser = async () => {
     try {
       if (previewMode === "live" && serverPort) {
-        await openUrl(`http://localhost:${serverPort}`);
+        await openUrl(`${serverScheme}://localhost:${serverPort}`);
       } else if (selectedFile && serverPort) {
-        await openUrl(`http://localhost:${serverPort}/${selectedFile.name}`);
+        await openUrl(`${serverScheme}://localhost:${serverPort}/${selectedFile.name}`);
       }
     } catch (err) {
       console.error("Failed to open in browser:", err);
     }
   };
 
+  const handleToggleLanShare = async () => {
+    if (lanShare) {
+      await invoke("stop_lan_share", { key: projectPath }).catch(() => {});
+      setLanShare(null);
+      setLanShareOpen(false);
+      return;
+    }
+    try {
+      setLanShare(await invoke<LanShare>("start_lan_share", { key: projectPath, durationMinutes: 30 }));
+      setLanShareOpen(true);
+    } catch (err: any) {
+      setStatusMessage(err.toString());
+    }
+  };
+
+  // While sharing, pick up remote requests and notice when the share expires
+  useEffect(() => {
+    if (!lanShare) return;
+    const timer = setInterval(async () => {
+      const current = await invoke<LanShare | null>("get_lan_share", { key: projectPath }).catch(() => null);
+      setLanShare(current);
+      if (!current) setLanShareOpen(false);
+    }, 3000);
+    return () => clearInterval(timer);
+  }, [lanShare !== null]);
+
+  // Sharing ends with the server it belongs to
+  useEffect(() => {
+    if (!serverPort) setLanShare(null);
+  }, [serverPort]);
+
   const handleSwitchToLive = () => {
     setPreviewMode("live");
     setIsEditing(false);
@@ -776,6 +997,47 @@ function PreviewArea({ onClose }: PreviewAreaProps) {
             </div>
           )}
 
+          {/* Share on the local network (phones, other machines) */}
+          {serverPort && (
+            <div className="relative">
+              <button
+                onClick={() => (lanShare ? setLanShareOpen(!lanShareOpen) : handleToggleLanShare())}
+                onMouseEnter={() => setTooltip("lan")}
+                onMouseLeave={() => setTooltip(null)}
+                className={`${lanShare ? "bg-green-600 text-white" : `${t.colors.bgTertiary} ${t.colors.text}`} hover:opacity-80 p-2 ${t.borderRadius}`}
+              >
+                <Smartphone size={15} />
+              </button>
+              {tooltip === "lan" && !lanShareOpen && (
+                <div className={`absolute right-0 top-full mt-1 px-2 py-1 text-xs whitespace-nowrap ${t.colors.bgTertiary} ${t.colors.text} ${t.borderRadius} shadow-lg z-50`}>
+                  {lanShare ? "Shared on your network" : "Open on phone (share on your network)"}
+                </div>
+              )}
+              {lanShare && lanShareOpen && (
+                <div className={`absolute right-0 top-full mt-1 p-3 w-80 text-xs ${t.colors.bgSecondary} ${t.colors.border} border ${t.colors.text} ${t.borderRadius} shadow-lg z-50`}>
+                  <p className="font-medium mb-1">Open this on a device on the same network:</p>
+                  {lanShare.urls.map((url) => (
+                    <p key={url} className="font-mono break-all select-text mb-1">{url}</p>
+                  ))}
+                  <p className={`${t.colors.textMuted} mb-2`}>
+                    Expires at {new Date(lanShare.expires_at).toLocaleTimeString()} · {lanShare.requests.length} request(s) from other devices
+                  </p>
+                  {lanShare.requests.slice(-5).map((r, i) => (
+                    <p key={i} className={`font-mono truncate ${t.colors.textMuted}`}>
+                      {r.remote} {r.method} {r.path} → {r.status}
+                    </p>
+                  ))}
+                  <button
+                    onClick={handleToggleLanShare}
+                    className={`mt-2 px-2 py-1 ${t.colors.bgTertiary} ${t.borderRadius}`}
+                  >
+                    Stop sharing
+                  </button>
+                </div>
+              )}
+            </div>
+          )}
+
           {/* Open in Browser - always available when server is running */}
           {serverPort && (
             <div className="relative">
@@ -902,8 +1164,19 @@ function PreviewArea({ onClose }: PreviewAreaProps) {
                 <div className="text-center">
                   <p className={`text-sm font-medium ${t.colors.text}`}>Installing dependencies...</p>
                   <p className="text-xs mt-1">{statusMessage}</p>
+                  {installOutput && (
+                    <p className="text-xs mt-1 opacity-60 truncate max-w-xs">{installOutput}</p>
+                  )}
                   <p className="text-xs mt-2 opacity-60">This may take a minute</p>
                 </div>
+                {installJobId && (
+                  <button
+                    onClick={handleCancelInstall}
+                    className={`px-3 py-1.5 text-sm ${t.colors.bgTertiary} ${t.colors.text} ${t.borderRadius}`}
+                  >
+                    Cancel
+                  </button>
+                )}
               </div>
             )}
 
@@ -967,7 +1240,7 @@ function PreviewArea({ onClose }: PreviewAreaProps) {
                 <iframe
                   ref={iframeRef}
                   key={refreshKey}
-                  src={`http://localhost:${serverPort}?_r=${refreshKey}`}
+                  src={`${serverScheme}://localhost:${serverPort}?_r=${refreshKey}`}
                   className="w-full h-full border-0 bg-white"
                   title="Live Preview"
                 />
@@ -1,4 +1,6 @@
 import { useState } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { save } from "@tauri-apps/plugin-dialog";
 import { useSettingsStore } from "../../stores/settingsStore";
 import { themes, ThemeKey } from "../../config/themes";
 import { Eye, EyeOff, ExternalLink } from "lucide-react";
@@ -6,13 +8,28 @@ import { Eye, EyeOff, ExternalLink } from "lucide-react";
 function GeneralSettings() {
   const {
     theme, mode, timeFormat, fontSize, confirmBeforeDelete, autoSaveFiles,
-    webSearchEnabled, searchApiKey,
+    webSearchEnabled, searchApiKey, previewHttps,
     setTheme, setMode, setTimeFormat, setFontSize, setConfirmBeforeDelete, setAutoSaveFiles,
-    setWebSearchEnabled, setSearchApiKey,
+    setWebSearchEnabled, setSearchApiKey, setPreviewHttps,
     resetToDefaults,
   } = useSettingsStore();
   const t = themes[theme];
   const [showKey, setShowKey] = useState(false);
+  const [caExportMessage, setCaExportMessage] = useState("");
+
+  const handleExportCa = async () => {
+    const destination = await save({
+      defaultPath: "mydevify-ca.pem",
+      filters: [{ name: "Certificate", extensions: ["pem", "crt"] }],
+    });
+    if (!destination) return;
+    try {
+      await invoke("export_local_ca", { destination });
+      setCaExportMessage(`Saved to ${destination}`);
+    } catch (err: any) {
+      setCaExportMessage(err.toString());
+    }
+  };
 
   const themeKeys = Object.keys(themes) as ThemeKey[];
 
@@ -125,6 +142,39 @@ function GeneralSettings() {
         </label>
       </div>
 
+      {/* ── Preview ────────────────────────────────────────────── */}
+      <div className="mb-6 pt-4 border-t border-gray-700">
+        <h2 className="text-lg font-semibold mb-4">Preview</h2>
+
+        <div className="mb-4">
+          <label className="flex items-center gap-3 cursor-pointer">
+            <input
+              type="checkbox"
+              checked={previewHttps}
+              onChange={(e) => setPreviewHttps(e.target.checked)}
+              className="w-4 h-4"
+            />
+            <div>
+              <span className="font-medium">Serve preview over HTTPS</span>
+              <p className={`text-sm ${t.colors.textMuted}`}>
+                Needed to test service workers, secure cookies and other secure-context features.
+                Trust the local certificate authority once so browsers accept it.
+              </p>
+            </div>
+          </label>
+        </div>
+
+        <button
+          onClick={handleExportCa}
+          className={`${t.colors.bgSecondary} ${t.colors.border} border ${t.borderRadius} px-3 py-2 text-sm`}
+        >
+          Export certificate authority...
+        </button>
+        {caExportMessage && (
+          <p className={`text-sm mt-2 ${t.colors.textMuted}`}>{caExportMessage}</p>
+        )}
+      </div>
+
       {/* ── Web Search ─────────────────────────────────────────── */}
       <div className="mb-6 pt-4 border-t border-gray-700">
         <h2 className="text-lg font-semibold mb-4">Web Search</h2>
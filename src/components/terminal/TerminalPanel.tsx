@@ -31,6 +31,20 @@ function TerminalPanel() {
     }
   }, [projectPath]);
 
+  // Load persisted command history for this project (newest last for ↑/↓)
+  useEffect(() => {
+    if (!projectPath) return;
+    invoke<{ command: string }[]>("query_command_history", {
+      project: projectPath,
+      query: { initiator: "user", limit: 500 },
+    })
+      .then((entries) => {
+        commandHistory.current = entries.map((e) => e.command).reverse();
+        historyIndex.current = commandHistory.current.length;
+      })
+      .catch(() => {});
+  }, [projectPath]);
+
   // When project changes, reset cwd and notify user
   useEffect(() => {
     if (projectPath) {
@@ -114,6 +128,7 @@ function TerminalPanel() {
         {
           command: trimmed,
           cwd: cwdRef.current || projectPathRef.current || "",
+          initiator: "user",
         }
       );
 
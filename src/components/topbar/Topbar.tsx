@@ -112,13 +112,13 @@ function Topbar({ terminalOpen, onToggleTerminal, onToolsNavigate, onSettingsCli
       try {
         const result = await invoke<{ stdout: string; stderr: string; exit_code: number }>(
           "execute_command",
-          { command: "git rev-parse --abbrev-ref HEAD", cwd: projectPath }
+          { command: "git rev-parse --abbrev-ref HEAD", cwd: projectPath, initiator: "app" }
         );
         if (result.exit_code === 0 && result.stdout.trim()) {
           setGitBranch(result.stdout.trim());
           const statusResult = await invoke<{ stdout: string; stderr: string; exit_code: number }>(
             "execute_command",
-            { command: "git status --porcelain", cwd: projectPath }
+            { command: "git status --porcelain", cwd: projectPath, initiator: "app" }
           );
           if (statusResult.exit_code === 0) {
             const lines = statusResult.stdout.trim().split("\n").filter((l: string) => l.length > 0);
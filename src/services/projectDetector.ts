@@ -11,6 +11,15 @@ export interface ProjectDetection {
   installCommand: string | null;
   portPattern: RegExp | null;
   needsInstall: boolean;
+  /** Started from .mydevify/services.toml via start_run_config */
+  runConfig?: boolean;
+}
+
+// Subset of run_config.rs RunConfigInfo the detector needs
+interface RunConfigInfo {
+  services: Record<string, { command: string; port: number | null; port_pattern: string | null }>;
+  start_order: string[];
+  preview_service: string | null;
 }
 
 // ── Framework Detection Map ───────────────────────────────────
@@ -117,6 +126,22 @@ function getDevCommand(manager: string, defaultCmd: string): string {
   return defaultCmd.replace(/^npm /, `${manager} `);
 }
 
+// Installed and up to date with the lockfile (see installer.rs).
+// Falls back to just checking for node_modules.
+async function checkDependencies(projectPath: string): Promise<{ needsInstall: boolean; installCommand: string | null }> {
+  try {
+    const status = await invoke<{ manager: string | null; installed: boolean; stale: boolean; install_command: string | null }>(
+      "get_install_status",
+      { project: projectPath }
+    );
+    // No manifest at all — nothing to install
+    if (!status.manager) return { needsInstall: false, installCommand: null };
+    return { needsInstall: !status.installed || status.stale, installCommand: status.install_command };
+  } catch {
+    return { needsInstall: !(await checkNodeModules(projectPath)), installCommand: null };
+  }
+}
+
 async function checkNodeModules(projectPath: string): Promise<boolean> {
   try {
     await invoke<string>("resolve_path", {
@@ -151,6 +176,27 @@ export async function detectProjectType(projectPath: string): Promise<ProjectDet
     console.warn("[detector] set_project_path failed:", err);
   }
 
+  // 0. A checked-in run configuration beats any heuristic
+  try {
+    const runConfig = await invoke<RunConfigInfo | null>("get_run_config", { project: projectPath });
+    if (runConfig && runConfig.preview_service) {
+      const service = runConfig.services[runConfig.preview_service];
+      const deps = await checkDependencies(projectPath);
+      console.log("[detector] → framework (services.toml), preview service:", runConfig.preview_service);
+      return {
+        type: "framework",
+        framework: runConfig.preview_service,
+        devCommand: service.command,
+        installCommand: deps.installCommand,
+        portPattern: new RegExp(service.port_pattern || "localhost:(\\d+)"),
+        needsInstall: deps.needsInstall,
+        runConfig: true,
+      };
+    }
+  } catch (err) {
+    console.warn("[detector] services.toml could not be used:", err);
+  }
+
   // List root directory
   let rootEntries: { name: string; path: string; is_dir: boolean }[] = [];
   try {
@@ -207,16 +253,16 @@ export async function detectProjectType(projectPath: string): Promise<ProjectDet
       for (const { pkg, info } of FRAMEWORK_MAP) {
         if (allDeps[pkg]) {
           const manager = detectPackageManager(rootFileNames);
-          const hasModules = await checkNodeModules(projectPath);
-          console.log("[detector] → framework:", info.name, "manager:", manager, "hasModules:", hasModules);
+          const deps = await checkDependencies(projectPath);
+          console.log("[detector] → framework:", info.name, "manager:", manager, "needsInstall:", deps.needsInstall);
 
           return {
             type: "framework",
             framework: info.name,
             devCommand: getDevCommand(manager, info.devCommand),
-            installCommand: getInstallCommand(manager),
+            installCommand: deps.installCommand || getInstallCommand(manager),
             portPattern: info.portPattern,
-            needsInstall: !hasModules,
+            needsInstall: deps.needsInstall,
           };
         }
       }
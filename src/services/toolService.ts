@@ -1506,6 +1506,7 @@ export async function executeTool(
         const result = await invoke<{ stdout: string; stderr: string; exit_code: number }>("execute_command", {
           command,
           cwd,
+          initiator: "ai",
         });
 
         const output: string[] = [];
@@ -12,6 +12,7 @@ interface SettingsState {
   webSearchEnabled: boolean;
   searchApiKey: string;
   onboardingCompleted: boolean;
+  previewHttps: boolean;
   setTheme: (theme: ThemeKey) => void;
   setMode: (mode: "simple" | "technical") => void;
   setTimeFormat: (format: "12h" | "24h") => void;
@@ -21,6 +22,7 @@ interface SettingsState {
   setWebSearchEnabled: (value: boolean) => void;
   setSearchApiKey: (key: string) => void;
   setOnboardingCompleted: (value: boolean) => void;
+  setPreviewHttps: (value: boolean) => void;
   cycleTheme: () => void;
   toggleMode: () => void;
   resetToDefaults: () => void;
@@ -40,6 +42,7 @@ const defaults = {
   webSearchEnabled: false,
   searchApiKey: "",
   onboardingCompleted: false,
+  previewHttps: false,
 };
 
 // Persist a single setting to SQLite (fire-and-forget)
@@ -97,6 +100,11 @@ export const useSettingsStore = create<SettingsState>((set) => ({
     persistSetting("onboardingCompleted", onboardingCompleted);
   },
 
+  setPreviewHttps: (previewHttps) => {
+    set({ previewHttps });
+    persistSetting("previewHttps", previewHttps);
+  },
+
   cycleTheme: () =>
     set((state) => {
       const currentIndex = themeOrder.indexOf(state.theme);
@@ -155,6 +163,9 @@ export const useSettingsStore = create<SettingsState>((set) => ({
       if (all.onboardingCompleted !== undefined) {
         try { updates.onboardingCompleted = JSON.parse(all.onboardingCompleted); } catch { /* keep default */ }
       }
+      if (all.previewHttps !== undefined) {
+        try { updates.previewHttps = JSON.parse(all.previewHttps); } catch { /* keep default */ }
+      }
 
       if (Object.keys(updates).length > 0) {
         useSettingsStore.setState(updates);